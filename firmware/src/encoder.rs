@@ -0,0 +1,49 @@
+//! Polled quadrature decoding for rotary encoders
+//!
+//! The A/B pins are sampled on every pass of the main loop, which runs far
+//! faster than a hand-turned encoder can step, so no interrupts are needed.
+
+/// Quadrature steps per detent on common mechanical encoders
+const STEPS_PER_DETENT: i8 = 4;
+
+/// Direction of each transition, indexed by `(previous AB << 2) | current AB`.
+/// Transitions where both pins changed at once are bounce and count as zero.
+const TRANSITIONS: [i8; 16] = [0, -1, 1, 0, 1, 0, 0, -1, -1, 0, 0, 1, 0, 1, -1, 0];
+
+/// Turns A/B pin samples into whole detents turned since the last read
+pub struct QuadratureDecoder {
+    state: u8,
+    steps: i8,
+    detents: i16,
+}
+
+impl QuadratureDecoder {
+    /// Starts at rest with both pins high, as they idle on the pull-ups
+    pub const fn new() -> Self {
+        Self {
+            state: 0b11,
+            steps: 0,
+            detents: 0,
+        }
+    }
+
+    /// Feeds one sample of the A and B pins
+    pub fn update(&mut self, a: bool, b: bool) {
+        let current = (u8::from(a) << 1) | u8::from(b);
+        self.steps += TRANSITIONS[usize::from((self.state << 2) | current)];
+        self.state = current;
+
+        if self.steps >= STEPS_PER_DETENT {
+            self.steps -= STEPS_PER_DETENT;
+            self.detents = self.detents.saturating_add(1);
+        } else if self.steps <= -STEPS_PER_DETENT {
+            self.steps += STEPS_PER_DETENT;
+            self.detents = self.detents.saturating_sub(1);
+        }
+    }
+
+    /// Detents turned since the previous call, positive clockwise
+    pub fn take_delta(&mut self) -> i16 {
+        core::mem::take(&mut self.detents)
+    }
+}
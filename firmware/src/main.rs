@@ -3,17 +3,45 @@
 //! This application reads analog values from 3 potentiometers connected to ADC pins
 //! and transmits their values to a PC over USB CDC (serial communication).
 //!
+//! Each sent value is the mean of `ADC_AVERAGE_SAMPLES` readings taken
+//! between sends.
+//!
 //! Potentiometer connections:
 //! - Pot 1: GPIO26 (ADC0)
 //! - Pot 2: GPIO27 (ADC1)
 //! - Pot 3: GPIO28 (ADC2)
 //!
+//! Mute buttons (to ground, using the internal pull-ups):
+//! - Button 1: GPIO2
+//! - Button 2: GPIO3
+//! - Button 3: GPIO4
+//!
+//! Rotary encoder (with `--features encoder`, replacing pot 1 as channel 1):
+//! - A: GPIO6
+//! - B: GPIO7
+//!
+//! SSD1306 OLED (with `--features oled`, 128x64 on I2C0 at 400 kHz):
+//! - SDA: GPIO0
+//! - SCL: GPIO1
+//!
+//! Onboard LED: off until USB is set up, solid once the host has enumerated
+//! the device, blinking while readings are being taken off the link. The host
+//! can override it with `LED ON` / `LED OFF` and hand it back with `LED AUTO`.
+//!
+//! With `--features diagnostics`, JSON frames also carry the die temperature
+//! (`temp_c`, from the internal sensor on ADC4) and `uptime_ms`.
+//!
 //! Note: GPIO29 (ADC3) is not available on this board.
 //! For additional channels, consider using an external ADC like MCP3008.
 
 #![no_std]
 #![no_main]
 
+#[cfg(feature = "encoder")]
+mod encoder;
+#[cfg(feature = "oled")]
+mod oled;
+
 // The macro for our start-up function
 use rp_pico::entry;
 
@@ -43,13 +71,15 @@ use hal::{
 use nb::block;
 // Import embedded-hal v0.2 traits
 use embedded_hal::adc::OneShot;
-use embedded_hal::digital::v2::{OutputPin, StatefulOutputPin};
+use embedded_hal::digital::v2::{InputPin, OutputPin, StatefulOutputPin};
 
 use usb_device::device::StringDescriptors;
 use usb_device::{class_prelude::*, prelude::*};
 use usbd_serial::SerialPort;
 
+#[cfg(not(feature = "binary-protocol"))]
 use core::fmt::Write;
+#[cfg(not(feature = "binary-protocol"))]
 use heapless::String;
 
 use serde::Serialize;
@@ -60,6 +90,304 @@ struct PotentiometerData {
     pot1: u16,
     pot2: u16,
     pot3: u16,
+    btn1: bool,
+    btn2: bool,
+    btn3: bool,
+}
+
+/// Consecutive identical samples required before a button changes state
+const DEBOUNCE_SAMPLES: u8 = 2;
+
+/// Filters contact bounce by only accepting a state once it has been
+/// sampled `DEBOUNCE_SAMPLES` times in a row
+struct Debouncer {
+    stable: bool,
+    candidate: bool,
+    count: u8,
+}
+
+impl Debouncer {
+    const fn new() -> Self {
+        Self {
+            stable: false,
+            candidate: false,
+            count: 0,
+        }
+    }
+
+    /// Feeds a raw sample and returns the debounced state
+    fn update(&mut self, sample: bool) -> bool {
+        if sample == self.candidate {
+            self.count = self.count.saturating_add(1);
+        } else {
+            self.candidate = sample;
+            self.count = 1;
+        }
+
+        if self.count >= DEBOUNCE_SAMPLES {
+            self.stable = self.candidate;
+        }
+        self.stable
+    }
+}
+
+/// Full-scale reading of the 12-bit ADC
+const ADC_MAX: u16 = 4095;
+
+/// Converts a temperature sensor reading to degrees Celsius, using the
+/// formula from the RP2040 datasheet (0.706 V at 27 C, -1.721 mV per degree)
+#[cfg(all(feature = "diagnostics", not(feature = "binary-protocol")))]
+fn die_temperature_c(raw: u16) -> f32 {
+    let volts = f32::from(raw) * 3.3 / 4096.0;
+    27.0 - (volts - 0.706) / 0.001721
+}
+
+/// Readings at or below this snap to 0, so a pot at the bottom really mutes
+const DEADZONE_LOW: u16 = 16;
+/// Readings at or above this snap to `ADC_MAX`, so a pot at the top is really full
+const DEADZONE_HIGH: u16 = 4080;
+
+/// Snaps readings near either end of travel to the rail, where slide pots
+/// jitter by a few counts
+#[derive(Clone, Copy)]
+struct Deadzone {
+    low: u16,
+    high: u16,
+}
+
+impl Deadzone {
+    const fn new(low: u16, high: u16) -> Self {
+        Self { low, high }
+    }
+
+    fn apply(&self, raw: u16) -> u16 {
+        if raw <= self.low {
+            0
+        } else if raw >= self.high {
+            ADC_MAX
+        } else {
+            raw
+        }
+    }
+}
+
+/// Number of potentiometer channels sent per frame
+const CHANNEL_COUNT: usize = 3;
+
+/// ADC readings averaged per channel into each sent value
+const ADC_AVERAGE_SAMPLES: u32 = 8;
+
+/// Sums readings taken between sends, so each frame carries their mean
+/// rather than a single noisy sample
+struct AdcAverage {
+    sums: [u32; CHANNEL_COUNT],
+    count: u32,
+}
+
+impl AdcAverage {
+    const fn new() -> Self {
+        Self {
+            sums: [0; CHANNEL_COUNT],
+            count: 0,
+        }
+    }
+
+    fn add(&mut self, readings: [u16; CHANNEL_COUNT]) {
+        for (sum, reading) in self.sums.iter_mut().zip(readings) {
+            *sum += u32::from(reading);
+        }
+        self.count += 1;
+    }
+
+    /// Returns the mean of the readings so far and starts over, or `None` if
+    /// there were none
+    fn take_mean(&mut self) -> Option<[u16; CHANNEL_COUNT]> {
+        if self.count == 0 {
+            return None;
+        }
+        let count = self.count;
+        let mean = self.sums.map(|sum| ((sum + count / 2) / count) as u16);
+        *self = Self::new();
+        Some(mean)
+    }
+}
+
+/// Interval between readings until the host sends `RATE`
+const DEFAULT_SEND_INTERVAL_MS: u32 = 50;
+/// Bounds accepted by `RATE`, so a bad value can't flood or stall the link
+const MIN_SEND_INTERVAL_MS: u32 = 5;
+const MAX_SEND_INTERVAL_MS: u32 = 1000;
+
+/// Longest command line accepted; longer lines are discarded
+const COMMAND_BUFFER_LEN: usize = 32;
+
+/// Half of the LED blink period while streaming
+const LED_BLINK_HALF_PERIOD_US: u64 = 250_000;
+/// How long after the last frame the host accepted the link still counts as
+/// streaming; a host that stops reading lets the USB buffer fill up
+const STREAMING_TIMEOUT_US: u64 = 500_000;
+
+/// Faders a `SET` command can address
+const MOTOR_COUNT: usize = 3;
+/// Readings this close to a `SET` target count as there, so a motor doesn't
+/// hunt around it
+const MOTOR_DEADBAND: u16 = 32;
+
+/// What the onboard LED shows
+#[derive(Clone, Copy, PartialEq)]
+enum LedMode {
+    /// Follows the connection state
+    Auto,
+    On,
+    Off,
+}
+
+/// Commands sent by the host as newline-terminated ASCII lines
+enum Command {
+    /// `RATE <ms>`: milliseconds between readings
+    Rate(u32),
+    /// `ID?`: reply with `ID_REPLY`
+    Identify,
+    /// `LED ON|OFF|AUTO`: override the LED or let it show the connection state
+    Led(LedMode),
+    /// `SET <channel> <value>`: move motorized fader `channel` (from 1) to an
+    /// ADC reading
+    Set { channel: usize, value: u16 },
+}
+
+/// Sent in reply to `ID?` so the host can tell the mixer from other serial devices
+const ID_REPLY: &str = concat!(
+    "{\"device\":\"pc-audio-mixer\",\"fw\":\"",
+    env!("CARGO_PKG_VERSION"),
+    "\",\"channels\":3}\n"
+);
+
+fn parse_command(line: &[u8]) -> Option<Command> {
+    let line = core::str::from_utf8(line).ok()?;
+    let mut parts = line.split_whitespace();
+    match parts.next()? {
+        "RATE" => parts.next()?.parse().ok().map(Command::Rate),
+        "ID?" => Some(Command::Identify),
+        "LED" => match parts.next()? {
+            "ON" => Some(Command::Led(LedMode::On)),
+            "OFF" => Some(Command::Led(LedMode::Off)),
+            "AUTO" => Some(Command::Led(LedMode::Auto)),
+            _ => None,
+        },
+        "SET" => {
+            let channel: usize = parts.next()?.parse().ok()?;
+            let value: u16 = parts.next()?.parse().ok()?;
+            (1..=MOTOR_COUNT)
+                .contains(&channel)
+                .then_some(Command::Set {
+                    channel,
+                    value: value.min(ADC_MAX),
+                })
+        }
+        _ => None,
+    }
+}
+
+/// Which way a motorized fader at `reading` has to move to reach `target`:
+/// 1 up, -1 down, 0 once it's within `MOTOR_DEADBAND`
+fn motor_direction(target: u16, reading: u16) -> i8 {
+    if reading.abs_diff(target) <= MOTOR_DEADBAND {
+        0
+    } else if reading < target {
+        1
+    } else {
+        -1
+    }
+}
+
+/// Whether the LED should be lit. In `Auto` it's off until USB is configured,
+/// solid while connected but idle, and blinks while frames are flowing.
+/// Timed off the free-running counter, since a delay would stall USB.
+fn led_lit(mode: LedMode, configured: bool, streaming: bool, now_us: u64) -> bool {
+    match mode {
+        LedMode::On => true,
+        LedMode::Off => false,
+        LedMode::Auto if !configured => false,
+        LedMode::Auto if streaming => (now_us / LED_BLINK_HALF_PERIOD_US) % 2 == 0,
+        LedMode::Auto => true,
+    }
+}
+
+/// Accumulates incoming bytes into command lines
+struct CommandBuffer {
+    buf: [u8; COMMAND_BUFFER_LEN],
+    len: usize,
+    overflowed: bool,
+}
+
+impl CommandBuffer {
+    const fn new() -> Self {
+        Self {
+            buf: [0; COMMAND_BUFFER_LEN],
+            len: 0,
+            overflowed: false,
+        }
+    }
+
+    /// Feeds one byte, returning a command when it completes a valid line
+    fn push(&mut self, byte: u8) -> Option<Command> {
+        if byte == b'\n' || byte == b'\r' {
+            let command = if self.overflowed {
+                None
+            } else {
+                parse_command(&self.buf[..self.len])
+            };
+            self.len = 0;
+            self.overflowed = false;
+            return command;
+        }
+
+        if self.len < COMMAND_BUFFER_LEN {
+            self.buf[self.len] = byte;
+            self.len += 1;
+        } else {
+            self.overflowed = true;
+        }
+        None
+    }
+}
+
+/// First byte of every binary frame
+#[cfg(feature = "binary-protocol")]
+const BINARY_FRAME_SYNC: u8 = 0xA5;
+
+/// Sync byte, channel count, two bytes per channel and the CRC
+#[cfg(feature = "binary-protocol")]
+const BINARY_FRAME_LEN: usize = 2 + CHANNEL_COUNT * 2 + 1;
+
+/// CRC-8 with polynomial 0x07 and zero initial value (CRC-8/SMBUS)
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Encodes readings as `[0xA5][count][count x u16 LE][crc8]`, the CRC
+/// covering the count and values
+#[cfg(feature = "binary-protocol")]
+fn encode_binary_frame(values: &[u16; CHANNEL_COUNT]) -> [u8; BINARY_FRAME_LEN] {
+    let mut frame = [0u8; BINARY_FRAME_LEN];
+    frame[0] = BINARY_FRAME_SYNC;
+    frame[1] = CHANNEL_COUNT as u8;
+    for (i, value) in values.iter().enumerate() {
+        frame[2 + i * 2..4 + i * 2].copy_from_slice(&value.to_le_bytes());
+    }
+    frame[BINARY_FRAME_LEN - 1] = crc8(&frame[1..BINARY_FRAME_LEN - 1]);
+    frame
 }
 
 /// Drives the pin high
@@ -111,7 +439,8 @@ fn main() -> ! {
     .ok()
     .unwrap();
 
-    // let _timer = hal::Timer::new(pac.TIMER, &mut pac.RESETS, &clocks);
+    // Free-running microsecond timer used to pace readings
+    let timer = hal::Timer::new(pac.TIMER, &mut pac.RESETS, &clocks);
 
     // Set up the USB driver
     let usb_bus = UsbBusAllocator::new(hal::usb::UsbBus::new(
@@ -144,15 +473,18 @@ fn main() -> ! {
         &mut pac.RESETS,
     );
 
-    // Turn on the onboard LED to indicate the Pico is running
+    // Starts off; the main loop lights it once the host enumerates the device
     let mut led_pin = pins.led.into_push_pull_output();
-    pin_on(&mut led_pin).unwrap();
-
-    #[cfg(feature = "probe")]
-    debug!("LED turned on");
+    pin_off(&mut led_pin).unwrap();
+    let mut led_mode = LedMode::Auto;
+    let mut led_on = false;
+    // Time of the last frame the USB stack took, `None` until the first
+    let mut last_sent_ok_us: Option<u64> = None;
 
     // Initialize the ADC
     let mut adc = Adc::new(pac.ADC, &mut pac.RESETS);
+    #[cfg(all(feature = "diagnostics", not(feature = "binary-protocol")))]
+    let mut temp_sensor = adc.take_temp_sensor().unwrap();
 
     // Configure ADC pins for potentiometers
     // Using 3 available ADC inputs: ADC0 (GPIO26), ADC1 (GPIO27), ADC2 (GPIO28)
@@ -160,63 +492,225 @@ fn main() -> ! {
     let mut adc_pin_1 = AdcPin::new(pins.gpio27.into_floating_input()).unwrap();
     let mut adc_pin_2 = AdcPin::new(pins.gpio28.into_floating_input()).unwrap();
 
+    // Mute buttons pull the pin to ground when pressed
+    let btn_pin_1 = pins.gpio2.into_pull_up_input();
+    let btn_pin_2 = pins.gpio3.into_pull_up_input();
+    let btn_pin_3 = pins.gpio4.into_pull_up_input();
+    let mut buttons = [Debouncer::new(), Debouncer::new(), Debouncer::new()];
+
+    // Encoder contacts also switch to ground, so they idle high
+    #[cfg(feature = "encoder")]
+    let enc_pin_a = pins.gpio6.into_pull_up_input();
+    #[cfg(feature = "encoder")]
+    let enc_pin_b = pins.gpio7.into_pull_up_input();
+    #[cfg(feature = "encoder")]
+    let mut encoder = encoder::QuadratureDecoder::new();
+
+    // Runs without a display if none answers on the bus
+    #[cfg(feature = "oled")]
+    let mut oled = {
+        use hal::fugit::RateExtU32;
+        use hal::gpio::{FunctionI2C, Pin, PullUp};
+
+        let sda: Pin<_, FunctionI2C, PullUp> = pins.gpio0.reconfigure();
+        let scl: Pin<_, FunctionI2C, PullUp> = pins.gpio1.reconfigure();
+        let i2c = hal::I2C::i2c0(
+            pac.I2C0,
+            sda,
+            scl,
+            400.kHz(),
+            &mut pac.RESETS,
+            &clocks.system_clock,
+        );
+        oled::LevelDisplay::new(ssd1306::I2CDisplayInterface::new(i2c))
+    };
+    #[cfg(feature = "oled")]
+    let mut last_oled_us = 0u64;
+    #[cfg(feature = "oled")]
+    let mut levels = [0u8; 3];
+
     // Don't use cortex_m delay - it blocks USB!
 
+    #[cfg(feature = "greeting")]
     let mut said_hello = false;
-    let mut counter = 0u32;
+    let mut commands = CommandBuffer::new();
+    // Positions requested with `SET`, until each fader gets there
+    let mut motor_targets: [Option<u16>; MOTOR_COUNT] = [None; MOTOR_COUNT];
+    // Kept in a variable rather than used as consts so it can later be set over serial
+    let deadzone = Deadzone::new(DEADZONE_LOW, DEADZONE_HIGH);
+    let mut send_interval_us = u64::from(DEFAULT_SEND_INTERVAL_MS) * 1000;
+    let mut last_send_us = 0u64;
+    // Also a variable so it can later be set over serial
+    let average_samples = ADC_AVERAGE_SAMPLES;
+    let mut average = AdcAverage::new();
+    let mut last_sample_us = 0u64;
     loop {
-        // A welcome message at the beginning
+        // A welcome message at the beginning. Off by default since it isn't
+        // part of the data stream the host expects.
+        #[cfg(feature = "greeting")]
         if !said_hello {
             said_hello = true;
             let _ = serial.write(b"Hello, World!\r\n");
         }
 
-        // Check for new data
+        // Handle commands from the host. Only `ID?` gets a reply, so the
+        // outgoing stream otherwise stays data-only.
         if usb_dev.poll(&mut [&mut serial]) {
             let mut buf = [0u8; 64];
-            match serial.read(&mut buf) {
-                Err(_e) => {
-                    // Do nothing
+            if let Ok(count) = serial.read(&mut buf) {
+                for &byte in &buf[..count] {
+                    match commands.push(byte) {
+                        Some(Command::Rate(ms)) => {
+                            let ms = ms.clamp(MIN_SEND_INTERVAL_MS, MAX_SEND_INTERVAL_MS);
+                            send_interval_us = u64::from(ms) * 1000;
+                        }
+                        Some(Command::Identify) => {
+                            let _ = serial.write(ID_REPLY.as_bytes());
+                        }
+                        Some(Command::Led(mode)) => led_mode = mode,
+                        Some(Command::Set { channel, value }) => {
+                            motor_targets[channel - 1] = Some(value);
+                        }
+                        None => {}
+                    }
+                }
+            }
+        }
+
+        // Sampled every pass so no quadrature step is missed between sends
+        #[cfg(feature = "encoder")]
+        encoder.update(
+            enc_pin_a.is_high().unwrap_or(true),
+            enc_pin_b.is_high().unwrap_or(true),
+        );
+
+        // Spread the averaged readings evenly across the send interval, one
+        // round per pass, so no pass spends long enough on the ADC to hold up
+        // USB polling
+        let now_us = timer.get_counter().ticks();
+        let sample_interval_us = send_interval_us / u64::from(average_samples.max(1));
+        if average.count < average_samples
+            && now_us.wrapping_sub(last_sample_us) >= sample_interval_us
+        {
+            last_sample_us = now_us;
+            average.add([
+                block!(adc.read(&mut adc_pin_0)).unwrap_or(0),
+                block!(adc.read(&mut adc_pin_1)).unwrap_or(0),
+                block!(adc.read(&mut adc_pin_2)).unwrap_or(0),
+            ]);
+        }
+
+        // Send readings once per interval (50ms unless changed with `RATE`)
+        let now_us = timer.get_counter().ticks();
+        if now_us.wrapping_sub(last_send_us) >= send_interval_us {
+            last_send_us = now_us;
+            // Read potentiometers, falling back to a fresh reading if none
+            // were averaged yet
+            let [pot1_raw, pot2_raw, pot3_raw] = average
+                .take_mean()
+                .unwrap_or_else(|| {
+                    [
+                        block!(adc.read(&mut adc_pin_0)).unwrap_or(0),
+                        block!(adc.read(&mut adc_pin_1)).unwrap_or(0),
+                        block!(adc.read(&mut adc_pin_2)).unwrap_or(0),
+                    ]
+                })
+                .map(|raw| deadzone.apply(raw));
+
+            #[cfg(feature = "oled")]
+            {
+                let percent = |raw: u16| (u32::from(raw) * 100 / u32::from(ADC_MAX)) as u8;
+                levels = [percent(pot1_raw), percent(pot2_raw), percent(pot3_raw)];
+            }
+
+            // No motor driver is fitted yet. A build with one would drive its
+            // H-bridge from `motor_direction` here; the target is dropped once
+            // reached, so a hand on the fader takes over again.
+            for (target, reading) in motor_targets.iter_mut().zip([pot1_raw, pot2_raw, pot3_raw]) {
+                if target.is_some_and(|target| motor_direction(target, reading) == 0) {
+                    *target = None;
+                }
+            }
+
+            // Sampled at the send rate, which also spaces out the debounce samples
+            let btn1 = buttons[0].update(btn_pin_1.is_low().unwrap_or(false));
+            let btn2 = buttons[1].update(btn_pin_2.is_low().unwrap_or(false));
+            let btn3 = buttons[2].update(btn_pin_3.is_low().unwrap_or(false));
+
+            #[cfg(feature = "binary-protocol")]
+            {
+                // Buttons and encoders are only reported by the JSON protocol for now
+                let _ = (btn1, btn2, btn3);
+                #[cfg(feature = "encoder")]
+                let _ = encoder.take_delta();
+                let frame = encode_binary_frame(&[pot1_raw, pot2_raw, pot3_raw]);
+                if serial.write(&frame).is_ok() {
+                    last_sent_ok_us = Some(now_us);
                 }
-                Ok(0) => {
-                    // Do nothing
+            }
+
+            #[cfg(not(feature = "binary-protocol"))]
+            {
+                // Create JSON manually to avoid heap allocation. The trailing
+                // crc field covers every byte before it, so the host can
+                // reject frames garbled in transit.
+                let mut json: String<160> = String::new();
+                let _ = write!(
+                    &mut json,
+                    "{{\"pot1\":{},\"pot2\":{},\"pot3\":{},\"btn1\":{},\"btn2\":{},\"btn3\":{}",
+                    pot1_raw, pot2_raw, pot3_raw, btn1, btn2, btn3
+                );
+                // Detents since the previous frame; the key's presence tells the
+                // host channel 1 is relative and its pot reading should be ignored
+                #[cfg(feature = "encoder")]
+                let _ = write!(&mut json, ",\"enc1\":{}", encoder.take_delta());
+                #[cfg(feature = "diagnostics")]
+                {
+                    let temp_c = die_temperature_c(block!(adc.read(&mut temp_sensor)).unwrap_or(0));
+                    let _ = write!(
+                        &mut json,
+                        ",\"temp_c\":{:.1},\"uptime_ms\":{}",
+                        temp_c,
+                        now_us / 1000
+                    );
                 }
-                Ok(count) => {
-                    // Convert to upper case
-                    buf.iter_mut().take(count).for_each(|b| {
-                        b.make_ascii_uppercase();
-                    });
-                    // Send back to the host
-                    let mut wr_ptr = &buf[..count];
-                    while !wr_ptr.is_empty() {
-                        match serial.write(wr_ptr) {
-                            Ok(len) => wr_ptr = &wr_ptr[len..],
-                            // On error, just drop unwritten data.
-                            Err(_) => break,
-                        };
-                    }
+                let crc = crc8(json.as_bytes());
+                let _ = writeln!(&mut json, ",\"crc\":{}}}", crc);
+                if serial.write(json.as_bytes()).is_ok() {
+                    last_sent_ok_us = Some(now_us);
                 }
             }
         }
 
-        // Send JSON data periodically (roughly every 10000 polls for ~50ms at USB polling rate)
-        if counter.is_multiple_of(10000) {
-            // Read potentiometers
-            let pot1_raw: u16 = block!(adc.read(&mut adc_pin_0)).unwrap_or(0);
-            let pot2_raw: u16 = block!(adc.read(&mut adc_pin_1)).unwrap_or(0);
-            let pot3_raw: u16 = block!(adc.read(&mut adc_pin_2)).unwrap_or(0);
+        // Only touches the pin when the state changes
+        let now_us = timer.get_counter().ticks();
+        let streaming =
+            last_sent_ok_us.is_some_and(|sent| now_us.wrapping_sub(sent) < STREAMING_TIMEOUT_US);
+        let lit = led_lit(
+            led_mode,
+            usb_dev.state() == UsbDeviceState::Configured,
+            streaming,
+            now_us,
+        );
+        if lit != led_on {
+            led_on = lit;
+            let _ = if lit {
+                pin_on(&mut led_pin)
+            } else {
+                pin_off(&mut led_pin)
+            };
+        }
 
-            // Create JSON manually to avoid heap allocation
-            let mut json: String<64> = String::new();
-            let _ = writeln!(
-                &mut json,
-                "{{\"pot1\":{},\"pot2\":{},\"pot3\":{}}}",
-                pot1_raw, pot2_raw, pot3_raw
-            );
-            let _ = serial.write(json.as_bytes());
+        // Drawn after the send so a slow I2C write never delays a reading
+        #[cfg(feature = "oled")]
+        if let Some(display) = oled.as_mut() {
+            let now_us = timer.get_counter().ticks();
+            if now_us.wrapping_sub(last_oled_us) >= oled::REFRESH_INTERVAL_US {
+                last_oled_us = now_us;
+                display.update(&levels);
+            }
         }
 
-        counter = counter.wrapping_add(1);
         // No delay - just keep polling USB!
     }
 }
@@ -51,6 +51,13 @@ struct PotentiometerData {
     // pot8: u16, // Uncomment for 8th channel
 }
 
+/// Sent in reply to `ID?` so the host can tell the mixer from other serial devices
+const ID_REPLY: &str = concat!(
+    "{\"device\":\"pc-audio-mixer\",\"fw\":\"",
+    env!("CARGO_PKG_VERSION"),
+    "\",\"channels\":6}\n"
+);
+
 type SpiType = Spi<
     Enabled,
     pac::SPI0,
@@ -181,9 +188,26 @@ fn main() -> ! {
 
     info!("Setup complete, starting main loop...");
 
+    // Partial command line from the host; only `ID?` is understood
+    let mut command = [0u8; 8];
+    let mut command_len = 0;
+
     loop {
         if usb_dev.poll(&mut [&mut serial]) {
-            // Handle USB events
+            let mut buf = [0u8; 64];
+            if let Ok(count) = serial.read(&mut buf) {
+                for &byte in &buf[..count] {
+                    if byte == b'\n' || byte == b'\r' {
+                        if &command[..command_len] == b"ID?" {
+                            let _ = serial.write(ID_REPLY.as_bytes());
+                        }
+                        command_len = 0;
+                    } else if command_len < command.len() {
+                        command[command_len] = byte;
+                        command_len += 1;
+                    }
+                }
+            }
         }
 
         // Read all 6 potentiometer channels
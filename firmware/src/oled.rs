@@ -0,0 +1,75 @@
+//! Level bars on an SSD1306 OLED (128x64, I2C)
+//!
+//! The I2C transfer blocks the main loop, so each refresh redraws at most one
+//! bar and only sends the columns that changed. A full-width change is two
+//! pages of 128 bytes, a few milliseconds at 400 kHz.
+
+use ssd1306::mode::BufferedGraphicsMode;
+use ssd1306::prelude::*;
+use ssd1306::Ssd1306;
+
+/// Minimum time between refreshes
+pub const REFRESH_INTERVAL_US: u64 = 30_000;
+
+const WIDTH: u32 = 128;
+/// Each bar covers two 8-pixel pages
+const BAR_HEIGHT: u32 = 16;
+/// Distance from the top of one bar to the next, leaving a blank page between
+const BAR_PITCH: u32 = 24;
+const BAR_COUNT: usize = 3;
+
+/// Horizontal bar per channel, filled in proportion to its percentage
+pub struct LevelDisplay<DI> {
+    display: Ssd1306<DI, DisplaySize128x64, BufferedGraphicsMode<DisplaySize128x64>>,
+    /// Bar widths currently on screen, in pixels
+    shown: [u32; BAR_COUNT],
+    /// Bar refreshed last, so a constantly moving one can't starve the others
+    last_drawn: usize,
+}
+
+impl<DI: WriteOnlyDataCommand> LevelDisplay<DI> {
+    /// Initializes the display, or returns `None` if it doesn't respond
+    pub fn new(interface: DI) -> Option<Self> {
+        let mut display = Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0)
+            .into_buffered_graphics_mode();
+        display.init().ok()?;
+        display.clear();
+        display.flush().ok()?;
+
+        Some(Self {
+            display,
+            shown: [0; BAR_COUNT],
+            last_drawn: BAR_COUNT - 1,
+        })
+    }
+
+    /// Redraws the next bar whose level changed, if any
+    pub fn update(&mut self, percentages: &[u8; BAR_COUNT]) {
+        for offset in 1..=BAR_COUNT {
+            let index = (self.last_drawn + offset) % BAR_COUNT;
+            let width = u32::from(percentages[index].min(100)) * WIDTH / 100;
+            if width == self.shown[index] {
+                continue;
+            }
+
+            // Only the columns between the old and new ends change
+            let (from, to) = if width > self.shown[index] {
+                (self.shown[index], width)
+            } else {
+                (width, self.shown[index])
+            };
+            let top = index as u32 * BAR_PITCH;
+            for y in top..top + BAR_HEIGHT {
+                for x in from..to {
+                    self.display.set_pixel(x, y, x < width);
+                }
+            }
+
+            self.shown[index] = width;
+            self.last_drawn = index;
+            // A failed write leaves stale pixels until that bar next changes
+            let _ = self.display.flush();
+            return;
+        }
+    }
+}
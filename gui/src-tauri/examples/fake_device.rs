@@ -0,0 +1,92 @@
+//! Stands in for the mixer firmware on a virtual serial port, so the app can
+//! be run end-to-end without a Pico.
+//!
+//! Create a connected pair of ports, run this on one end and connect the app
+//! to the other (virtual ports have no USB IDs, so pick it by name):
+//!
+//! ```text
+//! # Linux/macOS
+//! socat -d -d pty,raw,echo=0,link=/tmp/mixer-device pty,raw,echo=0,link=/tmp/mixer-app
+//! cargo run --example fake_device -- /tmp/mixer-device --sweep
+//!
+//! # Windows: create a com0com pair such as COM20 <-> COM21
+//! cargo run --example fake_device -- COM20 --sweep
+//! ```
+//!
+//! Without `--sweep` the faders sit still at half travel.
+
+use std::io::{self, Read, Write};
+use std::time::{Duration, Instant};
+
+/// The firmware's send rate
+const FRAME_INTERVAL: Duration = Duration::from_millis(50);
+const CHANNELS: usize = 3;
+const ADC_MAX: f32 = 4095.0;
+/// Seconds per full sweep of each fader, different so they don't move in lockstep
+const SWEEP_PERIODS: [f32; CHANNELS] = [4.0, 6.5, 9.0];
+
+const ID_REPLY: &str = concat!(
+    "{\"device\":\"pc-audio-mixer\",\"fw\":\"",
+    env!("CARGO_PKG_VERSION"),
+    "-fake\",\"channels\":3}\n"
+);
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let Some(port_name) = args.next() else {
+        eprintln!("usage: fake_device <port> [--sweep]");
+        std::process::exit(2);
+    };
+    let sweep = args.any(|arg| arg == "--sweep");
+
+    let mut port = serialport::new(&port_name, 115_200)
+        .timeout(Duration::from_millis(5))
+        .open()?;
+    println!("Sending frames on {} (sweep: {})", port_name, sweep);
+
+    let started = Instant::now();
+    let mut next_frame = Instant::now();
+    let mut buffer = [0u8; 64];
+    let mut command = Vec::new();
+
+    loop {
+        // Answer commands from the app, one per line like the firmware
+        match port.read(&mut buffer) {
+            Ok(n) => {
+                for &byte in &buffer[..n] {
+                    if byte != b'\n' {
+                        command.push(byte);
+                        continue;
+                    }
+                    if command.trim_ascii() == b"ID?" {
+                        port.write_all(ID_REPLY.as_bytes())?;
+                    }
+                    command.clear();
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        if Instant::now() < next_frame {
+            continue;
+        }
+        next_frame += FRAME_INTERVAL;
+
+        let seconds = started.elapsed().as_secs_f32();
+        let values = SWEEP_PERIODS.map(|period| {
+            let position = if sweep {
+                0.5 - 0.5 * (seconds / period * std::f32::consts::TAU).cos()
+            } else {
+                0.5
+            };
+            (position * ADC_MAX).round() as u16
+        });
+
+        let frame = format!(
+            "{{\"pot1\":{},\"pot2\":{},\"pot3\":{}}}\n",
+            values[0], values[1], values[2]
+        );
+        port.write_all(frame.as_bytes())?;
+    }
+}
@@ -1,13 +1,341 @@
-use crate::types::AudioSession;
+use crate::error::MixerError;
+use crate::types::{
+    AudioCapabilities, AudioDevice, AudioSession, BackendInfo, SessionDelta, SessionFilter,
+};
+use crate::volume::Volume;
 use anyhow::Result;
+use std::sync::Arc;
 
+/// Stands in for the Windows system sounds session, whose real process ID of
+/// 0 is already taken by master
+pub const SYSTEM_SOUNDS_PROCESS_ID: u32 = u32::MAX;
+
+/// Called with the new default output device whenever it changes
+pub type DeviceChangeCallback = Box<dyn Fn(AudioDevice) + Send + Sync>;
+
+/// Called whenever a session appears, goes away or changes volume
+pub type SessionChangeCallback = Arc<dyn Fn() + Send + Sync>;
+
+/// Mute states by process ID (master as 0), as returned by `mute_all`
+pub type SavedMutes = Vec<(u32, bool)>;
+
+/// One volume captured by `snapshot_volumes`. The process name is kept so a
+/// restarted app gets its volume back and a reused PID doesn't get another's.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedVolume {
+    pub process_id: u32,
+    pub process_name: String,
+    pub volume: f32,
+}
+
+/// Master (as process 0) and every session, as returned by `snapshot_volumes`
+pub type VolumeSnapshot = Vec<SavedVolume>;
+
+/// Volumes are read back in percent (0-100), as the UI shows them
 pub trait AudioManager: Send + Sync {
     fn get_audio_sessions(&self) -> Result<Vec<AudioSession>>;
-    fn set_app_volume(&self, process_id: u32, volume: f32) -> Result<()>;
-    fn set_master_volume(&self, volume: f32) -> Result<()>;
+    fn set_app_volume(&self, process_id: u32, volume: Volume) -> Result<()>;
+    fn set_master_volume(&self, volume: Volume) -> Result<()>;
     fn get_master_volume(&self) -> Result<f32>;
+    fn set_input_volume(&self, volume: Volume) -> Result<()>;
+    fn get_input_volume(&self) -> Result<f32>;
+    fn set_app_mute(&self, process_id: u32, muted: bool) -> Result<()>;
+    fn set_master_mute(&self, muted: bool) -> Result<()>;
+    fn get_master_mute(&self) -> Result<bool>;
+    fn set_input_mute(&self, muted: bool) -> Result<()>;
+    fn get_input_mute(&self) -> Result<bool>;
+    /// Active output devices, for mapping a channel to a whole device
+    fn list_output_devices(&self) -> Result<Vec<AudioDevice>>;
+    fn set_device_volume(&self, device_id: &str, volume: Volume) -> Result<()>;
+    fn get_device_volume(&self, device_id: &str) -> Result<f32>;
+    fn set_device_mute(&self, device_id: &str, muted: bool) -> Result<()>;
+    fn get_device_mute(&self, device_id: &str) -> Result<bool>;
+    /// Whether `set_app_volume` really changes an individual app's volume,
+    /// so the UI can hide app faders on backends where it would no-op
+    fn supports_per_app_volume(&self) -> bool;
+    /// Whether the mute setters really mute, so the UI can hide mute buttons
+    fn supports_mute(&self) -> bool;
+    /// Whether `get_peak_levels` reports real levels rather than silence
+    fn supports_metering(&self) -> bool;
+    /// Current peak level (0.0-1.0) of each session by process ID, with the
+    /// master output reported as process 0
+    fn get_peak_levels(&self) -> Result<Vec<(u32, f32)>>;
+    /// Short name of the backend, e.g. "windows" or "sim", for diagnostics
+    fn backend_name(&self) -> &'static str;
+
+    /// Whether this backend only stands in for a missing platform one, so
+    /// nothing it does reaches the OS
+    fn is_fallback(&self) -> bool {
+        false
+    }
+
+    /// Makes `device_id` the default output device. Backends that can't
+    /// switch it return an error.
+    fn set_default_output_device(&self, device_id: &str) -> Result<()> {
+        let _ = device_id;
+        Err(MixerError::Other(format!(
+            "The {} audio backend can't switch the default output device",
+            self.backend_name()
+        ))
+        .into())
+    }
+
+    /// Icon of the executable behind a session, as a base64-encoded PNG.
+    /// `None` where the backend has no icons.
+    fn get_session_icon(&self, process_id: u32) -> Result<Option<String>> {
+        let _ = process_id;
+        Ok(None)
+    }
+
+    /// Starts reporting default output device switches, e.g. when headphones
+    /// are plugged in. Backends without change notifications never call it.
+    fn watch_default_device(&self, on_change: DeviceChangeCallback) -> Result<()> {
+        let _ = on_change;
+        Ok(())
+    }
+
+    /// Whether `get_audio_sessions` lists the system sounds session, under
+    /// `SYSTEM_SOUNDS_PROCESS_ID`. Backends without one ignore it.
+    fn set_expose_system_sounds(&self, enabled: bool) {
+        let _ = enabled;
+    }
+
+    /// Starts reporting session changes so callers needn't poll for them.
+    /// Returns whether the callback will ever be called; backends without
+    /// notifications return `false` and leave callers polling.
+    fn watch_sessions(&self, on_change: SessionChangeCallback) -> Result<bool> {
+        let _ = on_change;
+        Ok(false)
+    }
+
+    fn capabilities(&self) -> AudioCapabilities {
+        AudioCapabilities {
+            per_app_volume: self.supports_per_app_volume(),
+            mute: self.supports_mute(),
+            metering: self.supports_metering(),
+        }
+    }
+
+    fn backend_info(&self) -> BackendInfo {
+        BackendInfo {
+            name: self.backend_name().to_string(),
+            os: std::env::consts::OS.to_string(),
+            is_fallback: self.is_fallback(),
+            capabilities: self.capabilities(),
+        }
+    }
+
+    /// Sets the volume of every session whose executable matches `name`,
+    /// returning how many sessions were changed
+    fn set_volume_by_process_name(&self, name: &str, volume: Volume) -> Result<usize> {
+        let sessions = self.get_audio_sessions()?;
+        let mut count = 0;
+
+        for session in sessions
+            .iter()
+            .filter(|s| s.process_id != 0 && s.process_name.eq_ignore_ascii_case(name))
+        {
+            self.set_app_volume(session.process_id, volume)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    /// Applies several `(process_id, volume)` updates at once. Backends where
+    /// each call enumerates sessions should override this to do it once.
+    fn set_volumes(&self, updates: &[(u32, Volume)]) -> Result<()> {
+        for &(process_id, volume) in updates {
+            self.set_app_volume(process_id, volume)?;
+        }
+        Ok(())
+    }
+
+    /// Mutes master and every session, returning the prior mute states by
+    /// process ID (master as 0) so `unmute_all` can restore them
+    fn mute_all(&self) -> Result<SavedMutes> {
+        let mut saved = vec![(0, self.get_master_mute()?)];
+        self.set_master_mute(true)?;
+
+        for session in self
+            .get_audio_sessions()?
+            .iter()
+            .filter(|s| s.process_id != 0)
+        {
+            saved.push((session.process_id, session.is_muted));
+            self.set_app_mute(session.process_id, true)?;
+        }
+
+        Ok(saved)
+    }
+
+    /// Restores mute states returned by `mute_all`
+    fn unmute_all(&self, saved: &[(u32, bool)]) -> Result<()> {
+        for &(process_id, muted) in saved {
+            let result = if process_id == 0 {
+                self.set_master_mute(muted)
+            } else {
+                self.set_app_mute(process_id, muted)
+            };
+            // Sessions may have closed since they were muted
+            if let Err(e) = result {
+                log::debug!("Failed to restore mute for process {}: {}", process_id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Current volume of master and every session, for `restore_volumes` to
+    /// put back later
+    fn snapshot_volumes(&self) -> Result<VolumeSnapshot> {
+        let mut saved = vec![SavedVolume {
+            process_id: 0,
+            process_name: String::new(),
+            volume: self.get_master_volume()?,
+        }];
+        saved.extend(
+            self.get_audio_sessions()?
+                .into_iter()
+                .filter(|s| s.process_id != 0)
+                .map(|s| SavedVolume {
+                    process_id: s.process_id,
+                    process_name: s.process_name,
+                    volume: s.volume,
+                }),
+        );
+        Ok(saved)
+    }
+
+    /// Re-applies volumes returned by `snapshot_volumes`. A session still
+    /// running under its PID gets its own volume back; one whose app restarted
+    /// is found by process name, and a PID now used by another app is skipped.
+    fn restore_volumes(&self, saved: &[SavedVolume]) -> Result<()> {
+        let sessions = self.get_audio_sessions()?;
+        for entry in saved {
+            let volume = Volume::from_percent(entry.volume)?;
+            if entry.process_id == 0 {
+                self.set_master_volume(volume)?;
+                continue;
+            }
+
+            let same_app = |s: &AudioSession| {
+                s.process_id != 0 && s.process_name.eq_ignore_ascii_case(&entry.process_name)
+            };
+            let targets: Vec<u32> = if sessions
+                .iter()
+                .any(|s| s.process_id == entry.process_id && same_app(s))
+            {
+                vec![entry.process_id]
+            } else {
+                // Sessions of the app that the snapshot doesn't cover by PID
+                sessions
+                    .iter()
+                    .filter(|s| same_app(s) && !saved.iter().any(|e| e.process_id == s.process_id))
+                    .map(|s| s.process_id)
+                    .collect()
+            };
+            for process_id in targets {
+                // Sessions may have closed since they were listed
+                if let Err(e) = self.set_app_volume(process_id, volume) {
+                    log::debug!("Failed to restore volume for process {}: {}", process_id, e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Mutes or unmutes every session whose executable matches `name`,
+    /// returning how many sessions were changed
+    fn set_mute_by_process_name(&self, name: &str, muted: bool) -> Result<usize> {
+        let sessions = self.get_audio_sessions()?;
+        let mut count = 0;
+
+        for session in sessions
+            .iter()
+            .filter(|s| s.process_id != 0 && s.process_name.eq_ignore_ascii_case(name))
+        {
+            self.set_app_mute(session.process_id, muted)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+}
+
+/// Collapses sessions of the same executable into one entry, keeping the
+/// first session's PID as the representative. Volume is averaged and the
+/// entry only reads as muted if every member is.
+pub fn merge_sessions_by_name(sessions: Vec<AudioSession>) -> Vec<AudioSession> {
+    let mut merged: Vec<AudioSession> = Vec::new();
+
+    for session in sessions {
+        let existing = merged.iter_mut().find(|m| {
+            session.process_id != 0
+                && m.process_id != 0
+                && m.process_name.eq_ignore_ascii_case(&session.process_name)
+        });
+
+        match existing {
+            Some(entry) => {
+                let count = entry.session_count as f32;
+                entry.volume = (entry.volume * count + session.volume) / (count + 1.0);
+                entry.is_muted &= session.is_muted;
+                entry.session_count += 1;
+            }
+            None => merged.push(session),
+        }
+    }
+
+    merged
 }
 
+/// Drops blocklisted executables and, per `filter`, muted or silent sessions,
+/// then sorts by display name if asked. Master always stays first.
+pub fn filter_sessions(
+    sessions: Vec<AudioSession>,
+    filter: SessionFilter,
+    blocklist: &[String],
+) -> Vec<AudioSession> {
+    let (master, mut apps): (Vec<AudioSession>, Vec<AudioSession>) =
+        sessions.into_iter().partition(|s| s.process_id == 0);
+
+    apps.retain(|s| {
+        !(blocklist
+            .iter()
+            .any(|name| s.process_name.eq_ignore_ascii_case(name))
+            || (filter.hide_muted && s.is_muted)
+            || (filter.hide_silent && s.volume <= 0.0))
+    });
+    if filter.sort_by_name {
+        apps.sort_by_cached_key(|s| s.display_name.to_lowercase());
+    }
+
+    master.into_iter().chain(apps).collect()
+}
+
+/// Sessions added, changed and removed between two polls, matched by process ID
+pub fn diff_sessions(previous: &[AudioSession], current: &[AudioSession]) -> SessionDelta {
+    let mut delta = SessionDelta::default();
+
+    for session in current {
+        match previous.iter().find(|p| p.process_id == session.process_id) {
+            None => delta.added.push(session.clone()),
+            Some(old) if old != session => delta.changed.push(session.clone()),
+            Some(_) => {}
+        }
+    }
+
+    delta.removed = previous
+        .iter()
+        .filter(|p| !current.iter().any(|s| s.process_id == p.process_id))
+        .map(|p| p.process_id)
+        .collect();
+
+    delta
+}
+
+pub mod sim_impl;
+
 #[cfg(target_os = "windows")]
 pub mod windows_impl;
 
@@ -23,3 +351,5 @@ pub type PlatformAudioManager = stub_impl::StubAudioManager;
 
 // Keep backward compatibility
 pub use PlatformAudioManager as WindowsAudioManager;
+
+pub use sim_impl::SimAudioManager;
@@ -0,0 +1,376 @@
+use crate::audio::AudioManager;
+use crate::error::MixerError;
+use crate::types::{AudioDevice, AudioSession};
+use crate::volume::Volume;
+use anyhow::Result;
+use std::sync::Mutex;
+
+/// Environment variable that swaps the platform backend for `SimAudioManager`
+pub const SIM_AUDIO_ENV_VAR: &str = "MIXER_SIM_AUDIO";
+
+struct Endpoint {
+    volume: f32,
+    muted: bool,
+}
+
+struct SimDevice {
+    info: AudioDevice,
+    endpoint: Endpoint,
+}
+
+struct SimState {
+    master: Endpoint,
+    input: Endpoint,
+    sessions: Vec<AudioSession>,
+    devices: Vec<SimDevice>,
+}
+
+/// In-memory backend for UI development. Unlike the stub, writes are kept and
+/// read back, so the UI and mapping loop can be exercised without hardware.
+pub struct SimAudioManager {
+    state: Mutex<SimState>,
+}
+
+fn sim_session(
+    process_id: u32,
+    process_name: &str,
+    display_name: &str,
+    volume: f32,
+) -> AudioSession {
+    AudioSession {
+        process_id,
+        process_name: process_name.to_string(),
+        display_name: display_name.to_string(),
+        volume,
+        is_muted: false,
+        session_count: 1,
+    }
+}
+
+fn sim_device(id: &str, name: &str, is_default: bool) -> SimDevice {
+    SimDevice {
+        info: AudioDevice {
+            id: id.to_string(),
+            name: name.to_string(),
+            is_default,
+        },
+        endpoint: Endpoint {
+            volume: 100.0,
+            muted: false,
+        },
+    }
+}
+
+impl SimAudioManager {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(SimState {
+                master: Endpoint {
+                    volume: 75.0,
+                    muted: false,
+                },
+                input: Endpoint {
+                    volume: 50.0,
+                    muted: false,
+                },
+                sessions: vec![
+                    sim_session(1234, "chrome.exe", "Google Chrome", 50.0),
+                    sim_session(1235, "chrome.exe", "Google Chrome", 50.0),
+                    sim_session(5678, "Spotify.exe", "Spotify", 65.0),
+                    sim_session(9012, "Discord.exe", "Discord", 80.0),
+                ],
+                devices: vec![
+                    sim_device("sim-speakers", "Speakers (Simulated)", true),
+                    sim_device("sim-headphones", "Headphones (Simulated)", false),
+                ],
+            }),
+        }
+    }
+
+    /// Whether the environment asks for the simulated backend
+    pub fn requested() -> bool {
+        std::env::var_os(SIM_AUDIO_ENV_VAR).is_some_and(|value| !value.is_empty() && value != "0")
+    }
+}
+
+impl AudioManager for SimAudioManager {
+    fn get_audio_sessions(&self) -> Result<Vec<AudioSession>> {
+        let state = self.state.lock().unwrap();
+
+        let mut sessions = vec![AudioSession {
+            process_id: 0,
+            process_name: "Master".to_string(),
+            display_name: "Master Volume".to_string(),
+            volume: state.master.volume,
+            is_muted: state.master.muted,
+            session_count: 1,
+        }];
+        sessions.extend(state.sessions.iter().cloned());
+
+        Ok(sessions)
+    }
+
+    fn set_app_volume(&self, process_id: u32, volume: Volume) -> Result<()> {
+        if process_id == 0 {
+            return self.set_master_volume(volume);
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let session = state
+            .sessions
+            .iter_mut()
+            .find(|s| s.process_id == process_id)
+            .ok_or_else(|| {
+                MixerError::SessionNotFound(format!("No audio session for process {}", process_id))
+            })?;
+        session.volume = volume.as_percent();
+        Ok(())
+    }
+
+    fn set_master_volume(&self, volume: Volume) -> Result<()> {
+        self.state.lock().unwrap().master.volume = volume.as_percent();
+        Ok(())
+    }
+
+    fn get_master_volume(&self) -> Result<f32> {
+        Ok(self.state.lock().unwrap().master.volume)
+    }
+
+    fn set_input_volume(&self, volume: Volume) -> Result<()> {
+        self.state.lock().unwrap().input.volume = volume.as_percent();
+        Ok(())
+    }
+
+    fn get_input_volume(&self) -> Result<f32> {
+        Ok(self.state.lock().unwrap().input.volume)
+    }
+
+    fn set_app_mute(&self, process_id: u32, muted: bool) -> Result<()> {
+        if process_id == 0 {
+            return self.set_master_mute(muted);
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let session = state
+            .sessions
+            .iter_mut()
+            .find(|s| s.process_id == process_id)
+            .ok_or_else(|| {
+                MixerError::SessionNotFound(format!("No audio session for process {}", process_id))
+            })?;
+        session.is_muted = muted;
+        Ok(())
+    }
+
+    fn set_master_mute(&self, muted: bool) -> Result<()> {
+        self.state.lock().unwrap().master.muted = muted;
+        Ok(())
+    }
+
+    fn get_master_mute(&self) -> Result<bool> {
+        Ok(self.state.lock().unwrap().master.muted)
+    }
+
+    fn set_input_mute(&self, muted: bool) -> Result<()> {
+        self.state.lock().unwrap().input.muted = muted;
+        Ok(())
+    }
+
+    fn get_input_mute(&self) -> Result<bool> {
+        Ok(self.state.lock().unwrap().input.muted)
+    }
+
+    fn list_output_devices(&self) -> Result<Vec<AudioDevice>> {
+        let state = self.state.lock().unwrap();
+        Ok(state.devices.iter().map(|d| d.info.clone()).collect())
+    }
+
+    fn set_default_output_device(&self, device_id: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if !state.devices.iter().any(|d| d.info.id == device_id) {
+            return Err(
+                MixerError::DeviceNotFound(format!("No audio device {}", device_id)).into(),
+            );
+        }
+        for device in &mut state.devices {
+            device.info.is_default = device.info.id == device_id;
+        }
+        Ok(())
+    }
+
+    fn set_device_volume(&self, device_id: &str, volume: Volume) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let device = state
+            .devices
+            .iter_mut()
+            .find(|d| d.info.id == device_id)
+            .ok_or_else(|| MixerError::DeviceNotFound(format!("No audio device {}", device_id)))?;
+        device.endpoint.volume = volume.as_percent();
+        Ok(())
+    }
+
+    fn get_device_volume(&self, device_id: &str) -> Result<f32> {
+        let state = self.state.lock().unwrap();
+        let device = state
+            .devices
+            .iter()
+            .find(|d| d.info.id == device_id)
+            .ok_or_else(|| MixerError::DeviceNotFound(format!("No audio device {}", device_id)))?;
+        Ok(device.endpoint.volume)
+    }
+
+    fn set_device_mute(&self, device_id: &str, muted: bool) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let device = state
+            .devices
+            .iter_mut()
+            .find(|d| d.info.id == device_id)
+            .ok_or_else(|| MixerError::DeviceNotFound(format!("No audio device {}", device_id)))?;
+        device.endpoint.muted = muted;
+        Ok(())
+    }
+
+    fn get_device_mute(&self, device_id: &str) -> Result<bool> {
+        let state = self.state.lock().unwrap();
+        let device = state
+            .devices
+            .iter()
+            .find(|d| d.info.id == device_id)
+            .ok_or_else(|| MixerError::DeviceNotFound(format!("No audio device {}", device_id)))?;
+        Ok(device.endpoint.muted)
+    }
+
+    fn supports_per_app_volume(&self) -> bool {
+        true
+    }
+
+    fn supports_mute(&self) -> bool {
+        true
+    }
+
+    fn supports_metering(&self) -> bool {
+        true
+    }
+
+    fn backend_name(&self) -> &'static str {
+        "sim"
+    }
+
+    fn get_peak_levels(&self) -> Result<Vec<(u32, f32)>> {
+        // Deterministic levels that follow volume and mute, so meters can be styled
+        Ok(self
+            .get_audio_sessions()?
+            .iter()
+            .map(|session| {
+                let level = if session.is_muted {
+                    0.0
+                } else {
+                    session.volume / 100.0 * 0.8
+                };
+                (session.process_id, level)
+            })
+            .collect())
+    }
+}
+
+impl Default for SimAudioManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn percent(value: f32) -> Volume {
+        Volume::from_percent(value).unwrap()
+    }
+
+    fn session_volume(manager: &SimAudioManager, process_id: u32) -> f32 {
+        manager
+            .get_audio_sessions()
+            .unwrap()
+            .into_iter()
+            .find(|s| s.process_id == process_id)
+            .unwrap()
+            .volume
+    }
+
+    #[test]
+    fn session_writes_are_read_back() {
+        let manager = SimAudioManager::new();
+
+        manager.set_app_volume(5678, percent(20.0)).unwrap();
+        manager.set_app_mute(9012, true).unwrap();
+
+        let sessions = manager.get_audio_sessions().unwrap();
+        assert_eq!(session_volume(&manager, 5678), 20.0);
+        assert!(sessions.iter().any(|s| s.process_id == 9012 && s.is_muted));
+        // The other session of the same app is left alone
+        manager.set_app_volume(1234, percent(10.0)).unwrap();
+        assert_eq!(session_volume(&manager, 1235), 50.0);
+    }
+
+    #[test]
+    fn process_zero_is_master() {
+        let manager = SimAudioManager::new();
+
+        manager.set_app_volume(0, percent(30.0)).unwrap();
+        manager.set_app_mute(0, true).unwrap();
+
+        assert_eq!(manager.get_master_volume().unwrap(), 30.0);
+        assert!(manager.get_master_mute().unwrap());
+        assert_eq!(session_volume(&manager, 0), 30.0);
+    }
+
+    #[test]
+    fn unknown_sessions_and_devices_are_not_found() {
+        let manager = SimAudioManager::new();
+
+        let error = manager.set_app_volume(4242, percent(10.0)).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<MixerError>(),
+            Some(MixerError::SessionNotFound(_))
+        ));
+        let error = manager.set_default_output_device("missing").unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<MixerError>(),
+            Some(MixerError::DeviceNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn choosing_a_default_device_clears_the_old_one() {
+        let manager = SimAudioManager::new();
+
+        manager.set_default_output_device("sim-headphones").unwrap();
+        manager
+            .set_device_volume("sim-headphones", percent(40.0))
+            .unwrap();
+
+        let defaults: Vec<String> = manager
+            .list_output_devices()
+            .unwrap()
+            .into_iter()
+            .filter(|d| d.is_default)
+            .map(|d| d.id)
+            .collect();
+        assert_eq!(defaults, vec!["sim-headphones"]);
+        assert_eq!(manager.get_device_volume("sim-headphones").unwrap(), 40.0);
+        assert_eq!(manager.get_device_volume("sim-speakers").unwrap(), 100.0);
+    }
+
+    #[test]
+    fn muted_sessions_meter_silence() {
+        let manager = SimAudioManager::new();
+
+        manager.set_app_mute(5678, true).unwrap();
+
+        let levels = manager.get_peak_levels().unwrap();
+        assert!(levels.contains(&(5678, 0.0)));
+        assert!(levels
+            .iter()
+            .any(|&(pid, level)| pid == 9012 && level > 0.0));
+    }
+}
@@ -1,82 +1,238 @@
 use crate::audio::AudioManager;
-use crate::types::AudioSession;
+use crate::error::MixerError;
+use crate::types::{AudioDevice, AudioSession};
+use crate::volume::Volume;
 use anyhow::Result;
+use std::sync::Mutex;
 
-pub struct StubAudioManager;
+/// Mock backend for non-Windows platforms. Nothing reaches the OS, but writes
+/// are kept so the UI reflects slider movement in demos.
+pub struct StubAudioManager {
+    // Master is the entry with process_id 0
+    sessions: Mutex<Vec<AudioSession>>,
+}
+
+fn mock_sessions() -> Vec<AudioSession> {
+    vec![
+        // Master Volume as special entry with process_id: 0
+        AudioSession {
+            process_id: 0,
+            process_name: "Master".to_string(),
+            display_name: "Master Volume".to_string(),
+            volume: 75.0,
+            is_muted: false,
+            session_count: 1,
+        },
+        // Common applications - using macOS/Linux process names
+        AudioSession {
+            process_id: 1234,
+            process_name: "Google Chrome".to_string(),
+            display_name: "Google Chrome".to_string(),
+            volume: 50.0,
+            is_muted: false,
+            session_count: 1,
+        },
+        AudioSession {
+            process_id: 5678,
+            process_name: "Spotify".to_string(),
+            display_name: "Spotify".to_string(),
+            volume: 65.0,
+            is_muted: false,
+            session_count: 1,
+        },
+        AudioSession {
+            process_id: 9012,
+            process_name: "Discord".to_string(),
+            display_name: "Discord".to_string(),
+            volume: 80.0,
+            is_muted: false,
+            session_count: 1,
+        },
+        AudioSession {
+            process_id: 3456,
+            process_name: "Firefox".to_string(),
+            display_name: "Mozilla Firefox".to_string(),
+            volume: 45.0,
+            is_muted: false,
+            session_count: 1,
+        },
+        AudioSession {
+            process_id: 7890,
+            process_name: "VLC".to_string(),
+            display_name: "VLC Media Player".to_string(),
+            volume: 90.0,
+            is_muted: false,
+            session_count: 1,
+        },
+    ]
+}
 
 impl StubAudioManager {
     pub fn new() -> Self {
-        Self
+        Self {
+            sessions: Mutex::new(mock_sessions()),
+        }
+    }
+
+    fn update_session(
+        &self,
+        process_id: u32,
+        update: impl FnOnce(&mut AudioSession),
+    ) -> Result<()> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .iter_mut()
+            .find(|s| s.process_id == process_id)
+            .ok_or_else(|| {
+                MixerError::SessionNotFound(format!("No audio session for process {}", process_id))
+            })?;
+        update(session);
+        Ok(())
+    }
+
+    fn session_state(&self, process_id: u32) -> Option<(f32, bool)> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|s| s.process_id == process_id)
+            .map(|s| (s.volume, s.is_muted))
     }
 }
 
 impl AudioManager for StubAudioManager {
     fn get_audio_sessions(&self) -> Result<Vec<AudioSession>> {
-        // Return mock data for testing on non-Windows platforms
-        Ok(vec![
-            // Master Volume as special entry with process_id: 0
-            AudioSession {
-                process_id: 0,
-                process_name: "Master".to_string(),
-                display_name: "Master Volume".to_string(),
-                volume: 75.0,
-                is_muted: false,
-            },
-            // Common applications - using macOS/Linux process names
-            AudioSession {
-                process_id: 1234,
-                process_name: "Google Chrome".to_string(),
-                display_name: "Google Chrome".to_string(),
-                volume: 50.0,
-                is_muted: false,
-            },
-            AudioSession {
-                process_id: 5678,
-                process_name: "Spotify".to_string(),
-                display_name: "Spotify".to_string(),
-                volume: 65.0,
-                is_muted: false,
-            },
-            AudioSession {
-                process_id: 9012,
-                process_name: "Discord".to_string(),
-                display_name: "Discord".to_string(),
-                volume: 80.0,
-                is_muted: false,
-            },
-            AudioSession {
-                process_id: 3456,
-                process_name: "Firefox".to_string(),
-                display_name: "Mozilla Firefox".to_string(),
-                volume: 45.0,
-                is_muted: false,
-            },
-            AudioSession {
-                process_id: 7890,
-                process_name: "VLC".to_string(),
-                display_name: "VLC Media Player".to_string(),
-                volume: 90.0,
-                is_muted: false,
-            },
-        ])
-    }
-
-    fn set_app_volume(&self, process_id: u32, volume: f32) -> Result<()> {
+        Ok(self.sessions.lock().unwrap().clone())
+    }
+
+    fn set_app_volume(&self, process_id: u32, volume: Volume) -> Result<()> {
         println!(
             "Stub: Setting volume for process {} to {}%",
-            process_id, volume
+            process_id,
+            volume.as_percent()
         );
+        self.update_session(process_id, |s| s.volume = volume.as_percent())
+    }
+
+    fn set_volume_by_process_name(&self, name: &str, volume: Volume) -> Result<usize> {
+        println!(
+            "Stub: Setting volume for {} to {}%",
+            name,
+            volume.as_percent()
+        );
+        Ok(0)
+    }
+
+    fn set_master_volume(&self, volume: Volume) -> Result<()> {
+        println!("Stub: Setting master volume to {}%", volume.as_percent());
+        self.update_session(0, |s| s.volume = volume.as_percent())
+    }
+
+    fn get_master_volume(&self) -> Result<f32> {
+        Ok(self.session_state(0).map_or(50.0, |(volume, _)| volume))
+    }
+
+    fn set_input_volume(&self, volume: Volume) -> Result<()> {
+        println!("Stub: Setting input volume to {}%", volume.as_percent());
+        Ok(())
+    }
+
+    fn get_input_volume(&self) -> Result<f32> {
+        Ok(50.0)
+    }
+
+    fn set_app_mute(&self, process_id: u32, muted: bool) -> Result<()> {
+        println!("Stub: Setting mute for process {} to {}", process_id, muted);
+        self.update_session(process_id, |s| s.is_muted = muted)
+    }
+
+    fn set_master_mute(&self, muted: bool) -> Result<()> {
+        println!("Stub: Setting master mute to {}", muted);
+        self.update_session(0, |s| s.is_muted = muted)
+    }
+
+    fn get_master_mute(&self) -> Result<bool> {
+        Ok(self.session_state(0).is_some_and(|(_, muted)| muted))
+    }
+
+    fn set_input_mute(&self, muted: bool) -> Result<()> {
+        println!("Stub: Setting input mute to {}", muted);
         Ok(())
     }
 
-    fn set_master_volume(&self, volume: f32) -> Result<()> {
-        println!("Stub: Setting master volume to {}%", volume);
+    fn get_input_mute(&self) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn list_output_devices(&self) -> Result<Vec<AudioDevice>> {
+        Ok(vec![AudioDevice {
+            id: "stub-speakers".to_string(),
+            name: "Speakers".to_string(),
+            is_default: true,
+        }])
+    }
+
+    fn set_default_output_device(&self, device_id: &str) -> Result<()> {
+        // The one mock device is already the default
+        if device_id != "stub-speakers" {
+            return Err(
+                MixerError::DeviceNotFound(format!("No audio device {}", device_id)).into(),
+            );
+        }
         Ok(())
     }
 
-    fn get_master_volume(&self) -> Result<f32> {
+    fn set_device_volume(&self, device_id: &str, volume: Volume) -> Result<()> {
+        println!(
+            "Stub: Setting volume for device {} to {}%",
+            device_id,
+            volume.as_percent()
+        );
+        Ok(())
+    }
+
+    fn get_device_volume(&self, _device_id: &str) -> Result<f32> {
         Ok(50.0)
     }
+
+    fn set_device_mute(&self, device_id: &str, muted: bool) -> Result<()> {
+        println!("Stub: Setting mute for device {} to {}", device_id, muted);
+        Ok(())
+    }
+
+    fn get_device_mute(&self, _device_id: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    fn supports_per_app_volume(&self) -> bool {
+        // Writes show up in the session list, so app faders demo properly
+        true
+    }
+
+    fn supports_mute(&self) -> bool {
+        true
+    }
+
+    fn supports_metering(&self) -> bool {
+        false
+    }
+
+    fn backend_name(&self) -> &'static str {
+        "stub"
+    }
+
+    fn is_fallback(&self) -> bool {
+        true
+    }
+
+    fn get_peak_levels(&self) -> Result<Vec<(u32, f32)>> {
+        // No metering without a real backend, so everything reads silent
+        Ok(self
+            .get_audio_sessions()?
+            .iter()
+            .map(|session| (session.process_id, 0.0))
+            .collect())
+    }
 }
 
 impl Default for StubAudioManager {
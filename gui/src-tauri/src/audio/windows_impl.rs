@@ -1,33 +1,58 @@
 use anyhow::{anyhow, Result};
 use std::sync::Once;
 
-use crate::audio::AudioManager;
-use crate::types::AudioSession;
+use crate::audio::{
+    AudioManager, DeviceChangeCallback, SessionChangeCallback, SYSTEM_SOUNDS_PROCESS_ID,
+};
+use crate::error::MixerError;
+use crate::types::{AudioDevice, AudioSession};
+use crate::volume::Volume;
 
 static INIT_COM: Once = Once::new();
 
+/// Percent for a scalar read back from Windows
+fn percent(scalar: f32) -> f32 {
+    Volume::from_scalar(scalar).unwrap_or_default().as_percent()
+}
+
 fn ensure_com_initialized() -> Result<()> {
     #[cfg(target_os = "windows")]
     {
-        use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
         use std::sync::atomic::{AtomicBool, Ordering};
+        use windows::Win32::Foundation::{RPC_E_CHANGED_MODE, S_FALSE, S_OK};
+        use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
 
         static COM_INIT_SUCCESS: AtomicBool = AtomicBool::new(false);
 
         INIT_COM.call_once(|| unsafe {
             let hr = CoInitializeEx(None, COINIT_MULTITHREADED);
-            if hr.is_ok() {
-                COM_INIT_SUCCESS.store(true, Ordering::SeqCst);
-                log::info!("COM initialized successfully");
-            } else {
-                log::error!("Failed to initialize COM: {:?}", hr);
-            }
+            let initialized = match hr {
+                S_OK => {
+                    log::info!("COM initialized successfully");
+                    true
+                }
+                S_FALSE => {
+                    log::debug!("COM was already initialized on this thread");
+                    true
+                }
+                // Someone (e.g. the webview) got there first with an STA; COM is
+                // still usable, just not in the mode we asked for
+                RPC_E_CHANGED_MODE => {
+                    log::warn!("COM already initialized in a different apartment mode");
+                    true
+                }
+                hr => {
+                    log::error!("Failed to initialize COM: {:?}", hr);
+                    false
+                }
+            };
+            COM_INIT_SUCCESS.store(initialized, Ordering::SeqCst);
         });
 
         if COM_INIT_SUCCESS.load(Ordering::SeqCst) {
             Ok(())
         } else {
-            Err(anyhow!("COM initialization failed"))
+            Err(MixerError::ComFailure("COM initialization failed".to_string()).into())
         }
     }
 
@@ -35,8 +60,85 @@ fn ensure_com_initialized() -> Result<()> {
     Ok(())
 }
 
+/// Longest path Windows supports, in UTF-16 units
+#[cfg(target_os = "windows")]
+const MAX_LONG_PATH: usize = 32_768;
+
+/// Calls a Win32 string getter with a growing buffer until the result fits.
+/// `read` returns the length written, 0 on failure.
+#[cfg(target_os = "windows")]
+fn read_wide_string(mut read: impl FnMut(&mut [u16]) -> u32) -> Option<Vec<u16>> {
+    use windows::Win32::Foundation::{GetLastError, ERROR_INSUFFICIENT_BUFFER};
+
+    let mut size = 260; // MAX_PATH, enough for nearly every path
+    loop {
+        let mut buffer = vec![0u16; size];
+        let len = read(&mut buffer) as usize;
+
+        // Depending on the API, a short buffer either fails with
+        // ERROR_INSUFFICIENT_BUFFER or gets filled with a truncated path
+        let truncated = if len == 0 {
+            if unsafe { GetLastError() } != ERROR_INSUFFICIENT_BUFFER {
+                return None;
+            }
+            true
+        } else {
+            len >= size - 1
+        };
+
+        if !truncated {
+            buffer.truncate(len);
+            return Some(buffer);
+        }
+        if size >= MAX_LONG_PATH {
+            return None;
+        }
+        size = (size * 2).min(MAX_LONG_PATH);
+    }
+}
+
+/// Turns a `\Device\HarddiskVolumeN\...` path into `C:\...` by finding the
+/// drive letter mapped to that device, or returns it unchanged if none is
+#[cfg(target_os = "windows")]
+fn device_path_to_dos(path: String) -> String {
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::{GetLogicalDriveStringsW, QueryDosDeviceW};
+
+    if !path.starts_with("\\Device\\") {
+        return path;
+    }
+
+    let Some(drives) = read_wide_string(|buffer| unsafe { GetLogicalDriveStringsW(Some(buffer)) })
+    else {
+        return path;
+    };
+
+    // `drives` holds `C:\`, `D:\`, ... separated by nuls
+    for drive in drives.split(|&c| c == 0).filter(|drive| drive.len() >= 2) {
+        let letter = &drive[..2];
+        let name: Vec<u16> = letter.iter().copied().chain(std::iter::once(0)).collect();
+        let mut target = [0u16; 260];
+        let len = unsafe { QueryDosDeviceW(PCWSTR(name.as_ptr()), Some(&mut target)) } as usize;
+        if len == 0 {
+            continue;
+        }
+
+        // The target is a list too; the first entry is the current mapping
+        let device_len = target[..len].iter().position(|&c| c == 0).unwrap_or(len);
+        let device = String::from_utf16_lossy(&target[..device_len]);
+        if let Some(rest) = path.strip_prefix(&device) {
+            // So `\Device\HarddiskVolume1` doesn't match HarddiskVolume10
+            if rest.starts_with('\\') {
+                return format!("{}{}", String::from_utf16_lossy(letter), rest);
+            }
+        }
+    }
+
+    path
+}
+
 #[cfg(target_os = "windows")]
-fn get_process_name_from_id(pid: u32) -> Option<String> {
+fn get_process_path_from_id(pid: u32) -> Option<String> {
     use std::ffi::OsString;
     use std::os::windows::ffi::OsStringExt;
     use windows::Win32::Foundation::CloseHandle;
@@ -56,79 +158,594 @@ fn get_process_name_from_id(pid: u32) -> Option<String> {
             let _ = CloseHandle(h);
         });
 
-        const MAX_PATH: usize = 260; // Windows MAX_PATH constant
-        let mut buffer = [0u16; MAX_PATH];
-
         // Try GetModuleFileNameExW first (requires more permissions)
-        let len = GetModuleFileNameExW(Some(process_handle), None, &mut buffer);
+        if let Some(buffer) =
+            read_wide_string(|buffer| GetModuleFileNameExW(Some(process_handle), None, buffer))
+        {
+            return Some(OsString::from_wide(&buffer).to_string_lossy().into_owned());
+        }
 
-        let final_len = if len == 0 {
-            // Fallback to GetProcessImageFileNameW (doesn't need Option wrapper)
-            let len = GetProcessImageFileNameW(process_handle, &mut buffer);
-            if len == 0 {
-                return None;
-            }
-            len.min(MAX_PATH as u32)
-        } else {
-            len.min(MAX_PATH as u32)
+        // Fallback to GetProcessImageFileNameW, which returns a device path
+        let buffer = read_wide_string(|buffer| GetProcessImageFileNameW(process_handle, buffer))?;
+        let path = OsString::from_wide(&buffer).to_string_lossy().into_owned();
+        Some(device_path_to_dos(path))
+    }
+}
+
+/// Executable name of a process, e.g. `chrome.exe`
+#[cfg(target_os = "windows")]
+pub fn get_process_name_from_id(pid: u32) -> Option<String> {
+    let path = get_process_path_from_id(pid)?;
+    path.rsplit('\\').next().map(|name| name.to_string())
+}
+
+/// Extracts the large icon of an executable as a base64-encoded PNG
+#[cfg(target_os = "windows")]
+fn extract_icon_base64(exe_path: &str) -> Option<String> {
+    use base64::Engine;
+    use std::ffi::OsStr;
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Graphics::Gdi::{
+        CreateCompatibleDC, DeleteDC, DeleteObject, GetDIBits, GetObjectW, BITMAP, BITMAPINFO,
+        BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+    };
+    use windows::Win32::UI::Shell::ExtractIconExW;
+    use windows::Win32::UI::WindowsAndMessaging::{DestroyIcon, GetIconInfo, HICON, ICONINFO};
+
+    let wide_path: Vec<u16> = OsStr::new(exe_path)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let (width, height, mut pixels) = unsafe {
+        let mut icon = HICON::default();
+        let extracted = ExtractIconExW(PCWSTR(wide_path.as_ptr()), 0, Some(&mut icon), None, 1);
+        if extracted == 0 || icon.is_invalid() {
+            return None;
+        }
+        let _icon_guard = scopeguard::guard(icon, |icon| {
+            let _ = DestroyIcon(icon);
+        });
+
+        let mut icon_info = ICONINFO::default();
+        GetIconInfo(icon, &mut icon_info).ok()?;
+        let _bitmap_guard = scopeguard::guard(icon_info, |info| {
+            let _ = DeleteObject(info.hbmColor.into());
+            let _ = DeleteObject(info.hbmMask.into());
+        });
+
+        let mut bitmap = BITMAP::default();
+        let size = GetObjectW(
+            icon_info.hbmColor.into(),
+            std::mem::size_of::<BITMAP>() as i32,
+            Some(&mut bitmap as *mut BITMAP as *mut _),
+        );
+        if size == 0 || bitmap.bmWidth <= 0 || bitmap.bmHeight <= 0 {
+            return None;
+        }
+        let (width, height) = (bitmap.bmWidth, bitmap.bmHeight);
+
+        let mut bitmap_info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                // Negative height requests top-down rows, as PNG expects
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
         };
 
-        let path = OsString::from_wide(&buffer[..final_len as usize]);
-        let path_str = path.to_string_lossy();
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        let dc = CreateCompatibleDC(None);
+        let lines = GetDIBits(
+            dc,
+            icon_info.hbmColor,
+            0,
+            height as u32,
+            Some(pixels.as_mut_ptr() as *mut _),
+            &mut bitmap_info,
+            DIB_RGB_COLORS,
+        );
+        let _ = DeleteDC(dc);
+        if lines == 0 {
+            return None;
+        }
+
+        (width as u32, height as u32, pixels)
+    };
+
+    // GDI hands back BGRA; icons without an alpha channel read as fully transparent
+    let has_alpha = pixels.chunks_exact(4).any(|px| px[3] != 0);
+    for px in pixels.chunks_exact_mut(4) {
+        px.swap(0, 2);
+        if !has_alpha {
+            px[3] = 255;
+        }
+    }
+
+    let mut png_bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut png_bytes, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().ok()?;
+        writer.write_image_data(&pixels).ok()?;
+    }
+
+    Some(base64::engine::general_purpose::STANDARD.encode(png_bytes))
+}
+
+/// Default device for the given direction
+#[cfg(target_os = "windows")]
+fn default_device(
+    data_flow: windows::Win32::Media::Audio::EDataFlow,
+) -> Result<windows::Win32::Media::Audio::IMMDevice> {
+    use windows::Win32::Media::Audio::{eConsole, IMMDeviceEnumerator, MMDeviceEnumerator};
+    use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_ALL};
+
+    ensure_com_initialized()?;
+
+    unsafe {
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+        Ok(enumerator.GetDefaultAudioEndpoint(data_flow, eConsole)?)
+    }
+}
+
+/// Device with the given endpoint ID, as returned by `device_id`
+#[cfg(target_os = "windows")]
+fn device_by_id(device_id: &str) -> Result<windows::Win32::Media::Audio::IMMDevice> {
+    use windows::core::HSTRING;
+    use windows::Win32::Media::Audio::{IMMDeviceEnumerator, MMDeviceEnumerator};
+    use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_ALL};
+
+    ensure_com_initialized()?;
+
+    unsafe {
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+        enumerator
+            .GetDevice(&HSTRING::from(device_id))
+            .map_err(|e| {
+                MixerError::DeviceNotFound(format!("No audio device {}: {}", device_id, e)).into()
+            })
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn device_id(device: &windows::Win32::Media::Audio::IMMDevice) -> Result<String> {
+    use windows::Win32::System::Com::CoTaskMemFree;
+
+    unsafe {
+        let raw = device.GetId()?;
+        let id = raw.to_string();
+        CoTaskMemFree(Some(raw.0 as *const _));
+        Ok(id?)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn device_friendly_name(device: &windows::Win32::Media::Audio::IMMDevice) -> Result<String> {
+    use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+    use windows::Win32::System::Com::STGM_READ;
+
+    unsafe {
+        let store = device.OpenPropertyStore(STGM_READ)?;
+        let name = store.GetValue(&PKEY_Device_FriendlyName)?;
+        Ok(name.to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn endpoint_volume(
+    device: &windows::Win32::Media::Audio::IMMDevice,
+) -> Result<windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume> {
+    use windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume;
+    use windows::Win32::System::Com::CLSCTX_ALL;
 
-        // Extract just the filename from the full path
-        path_str
-            .split('\\')
-            .last()
-            .map(|s| s.to_string())
+    unsafe {
+        let endpoint_volume = device.Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)?;
+        Ok(endpoint_volume)
     }
 }
 
-pub struct WindowsAudioManager;
+/// Endpoint volume of the default device for the given direction
+#[cfg(target_os = "windows")]
+fn default_endpoint_volume(
+    data_flow: windows::Win32::Media::Audio::EDataFlow,
+) -> Result<windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume> {
+    endpoint_volume(&default_device(data_flow)?)
+}
+
+/// Audio sessions on the default output device, paired with their process ID.
+/// System sounds come back as `SYSTEM_SOUNDS_PROCESS_ID`.
+#[cfg(target_os = "windows")]
+fn render_sessions() -> Result<Vec<(u32, windows::Win32::Media::Audio::IAudioSessionControl)>> {
+    use windows::core::Interface;
+    use windows::Win32::Media::Audio::{eRender, IAudioSessionControl2, IAudioSessionManager2};
+    use windows::Win32::System::Com::CLSCTX_ALL;
+
+    let device = default_device(eRender)?;
+    let mut sessions = Vec::new();
+
+    unsafe {
+        let session_manager = device.Activate::<IAudioSessionManager2>(CLSCTX_ALL, None)?;
+        let session_enumerator = session_manager.GetSessionEnumerator()?;
+
+        for i in 0..session_enumerator.GetCount()? {
+            let control = session_enumerator.GetSession(i)?;
+            let process_id = match control
+                .cast::<IAudioSessionControl2>()
+                .and_then(|control2| control2.GetProcessId())
+            {
+                // PID 0 is the system sounds session, which would shadow master
+                Ok(0) => SYSTEM_SOUNDS_PROCESS_ID,
+                Ok(process_id) => process_id,
+                Err(_) => continue,
+            };
+            sessions.push((process_id, control));
+        }
+    }
+
+    Ok(sessions)
+}
+
+/// Forwards default render device switches to a `DeviceChangeCallback`
+#[cfg(target_os = "windows")]
+#[windows::core::implement(windows::Win32::Media::Audio::IMMNotificationClient)]
+struct DefaultDeviceNotifier {
+    on_change: DeviceChangeCallback,
+}
+
+#[cfg(target_os = "windows")]
+#[allow(non_snake_case)]
+impl windows::Win32::Media::Audio::IMMNotificationClient_Impl for DefaultDeviceNotifier_Impl {
+    fn OnDeviceStateChanged(
+        &self,
+        _device_id: &windows::core::PCWSTR,
+        _new_state: windows::Win32::Media::Audio::DEVICE_STATE,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnDeviceAdded(&self, _device_id: &windows::core::PCWSTR) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnDeviceRemoved(&self, _device_id: &windows::core::PCWSTR) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnDefaultDeviceChanged(
+        &self,
+        flow: windows::Win32::Media::Audio::EDataFlow,
+        role: windows::Win32::Media::Audio::ERole,
+        default_device_id: &windows::core::PCWSTR,
+    ) -> windows::core::Result<()> {
+        use windows::Win32::Media::Audio::{eConsole, eRender};
+
+        // Fired once per role; master follows the console output device.
+        // A null ID means the last output device went away.
+        if flow != eRender || role != eConsole || default_device_id.is_null() {
+            return Ok(());
+        }
+
+        let id = unsafe { default_device_id.to_string() }.unwrap_or_default();
+        let name = device_by_id(&id)
+            .and_then(|device| device_friendly_name(&device))
+            .unwrap_or_else(|_| id.clone());
+
+        (self.on_change)(AudioDevice {
+            id,
+            name,
+            is_default: true,
+        });
+        Ok(())
+    }
+
+    fn OnPropertyValueChanged(
+        &self,
+        _device_id: &windows::core::PCWSTR,
+        _key: &windows::Win32::Foundation::PROPERTYKEY,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+}
+
+/// One session's event registration, kept until the session expires
+#[cfg(target_os = "windows")]
+struct WatchedSession {
+    control: windows::Win32::Media::Audio::IAudioSessionControl,
+    events: windows::Win32::Media::Audio::IAudioSessionEvents,
+    expired: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+#[cfg(target_os = "windows")]
+impl WatchedSession {
+    fn unregister(&self) {
+        if let Err(e) = unsafe {
+            self.control
+                .UnregisterAudioSessionNotification(&self.events)
+        } {
+            log::debug!("Failed to stop watching audio session: {}", e);
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+type WatchedSessions = std::sync::Arc<std::sync::Mutex<Vec<WatchedSession>>>;
+
+/// Unregisters sessions that have expired or disconnected. Runs on the
+/// watcher thread, since a registration can't be removed from inside its
+/// own callback.
+#[cfg(target_os = "windows")]
+fn prune_expired_sessions(watched: &WatchedSessions) {
+    watched.lock().unwrap().retain(|session| {
+        let expired = session.expired.load(std::sync::atomic::Ordering::Relaxed);
+        if expired {
+            session.unregister();
+        }
+        !expired
+    });
+}
+
+/// Reports sessions created on the default output device, and starts
+/// watching each for changes of its own
+#[cfg(target_os = "windows")]
+#[windows::core::implement(windows::Win32::Media::Audio::IAudioSessionNotification)]
+struct SessionNotifier {
+    on_change: SessionChangeCallback,
+    // Woken to prune sessions once they expire
+    watcher: std::thread::Thread,
+    watched: WatchedSessions,
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for SessionNotifier {
+    fn drop(&mut self) {
+        for session in self.watched.lock().unwrap().drain(..) {
+            session.unregister();
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[allow(non_snake_case)]
+impl windows::Win32::Media::Audio::IAudioSessionNotification_Impl for SessionNotifier_Impl {
+    fn OnSessionCreated(
+        &self,
+        new_session: windows::core::Ref<'_, windows::Win32::Media::Audio::IAudioSessionControl>,
+    ) -> windows::core::Result<()> {
+        let control = new_session.ok()?;
+        match watch_session_events(control, &self.on_change, &self.watcher) {
+            Ok(session) => self.watched.lock().unwrap().push(session),
+            Err(e) => log::debug!("Failed to watch new audio session: {}", e),
+        }
+
+        (self.on_change)();
+        Ok(())
+    }
+}
+
+/// Forwards volume, name and state changes of one session
+#[cfg(target_os = "windows")]
+#[windows::core::implement(windows::Win32::Media::Audio::IAudioSessionEvents)]
+struct SessionEventsNotifier {
+    on_change: SessionChangeCallback,
+    expired: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    watcher: std::thread::Thread,
+}
+
+#[cfg(target_os = "windows")]
+impl SessionEventsNotifier {
+    fn mark_expired(&self) {
+        self.expired
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        self.watcher.unpark();
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[allow(non_snake_case)]
+impl windows::Win32::Media::Audio::IAudioSessionEvents_Impl for SessionEventsNotifier_Impl {
+    fn OnDisplayNameChanged(
+        &self,
+        _new_display_name: &windows::core::PCWSTR,
+        _event_context: *const windows::core::GUID,
+    ) -> windows::core::Result<()> {
+        (self.on_change)();
+        Ok(())
+    }
+
+    fn OnIconPathChanged(
+        &self,
+        _new_icon_path: &windows::core::PCWSTR,
+        _event_context: *const windows::core::GUID,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnSimpleVolumeChanged(
+        &self,
+        _new_volume: f32,
+        _new_mute: windows::core::BOOL,
+        _event_context: *const windows::core::GUID,
+    ) -> windows::core::Result<()> {
+        (self.on_change)();
+        Ok(())
+    }
+
+    fn OnChannelVolumeChanged(
+        &self,
+        _channel_count: u32,
+        _new_channel_volumes: *const f32,
+        _changed_channel: u32,
+        _event_context: *const windows::core::GUID,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnGroupingParamChanged(
+        &self,
+        _new_grouping_param: *const windows::core::GUID,
+        _event_context: *const windows::core::GUID,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn OnStateChanged(
+        &self,
+        new_state: windows::Win32::Media::Audio::AudioSessionState,
+    ) -> windows::core::Result<()> {
+        if new_state == windows::Win32::Media::Audio::AudioSessionStateExpired {
+            self.mark_expired();
+        }
+        (self.on_change)();
+        Ok(())
+    }
+
+    fn OnSessionDisconnected(
+        &self,
+        _reason: windows::Win32::Media::Audio::AudioSessionDisconnectReason,
+    ) -> windows::core::Result<()> {
+        self.mark_expired();
+        (self.on_change)();
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn watch_session_events(
+    control: &windows::Win32::Media::Audio::IAudioSessionControl,
+    on_change: &SessionChangeCallback,
+    watcher: &std::thread::Thread,
+) -> windows::core::Result<WatchedSession> {
+    let expired = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let events: windows::Win32::Media::Audio::IAudioSessionEvents = SessionEventsNotifier {
+        on_change: on_change.clone(),
+        expired: expired.clone(),
+        watcher: watcher.clone(),
+    }
+    .into();
+    unsafe { control.RegisterAudioSessionNotification(&events)? };
+    Ok(WatchedSession {
+        control: control.clone(),
+        events,
+        expired,
+    })
+}
+
+pub struct WindowsAudioManager {
+    // Icons by executable path, so they are only extracted once
+    #[cfg(target_os = "windows")]
+    icon_cache: std::sync::Mutex<std::collections::HashMap<String, Option<String>>>,
+    #[cfg(target_os = "windows")]
+    expose_system_sounds: std::sync::atomic::AtomicBool,
+}
 
 impl WindowsAudioManager {
     pub fn new() -> Self {
         if let Err(e) = ensure_com_initialized() {
             log::error!("Failed to initialize COM for Windows Audio: {}", e);
         }
-        Self
+        Self {
+            #[cfg(target_os = "windows")]
+            icon_cache: std::sync::Mutex::new(std::collections::HashMap::new()),
+            #[cfg(target_os = "windows")]
+            expose_system_sounds: std::sync::atomic::AtomicBool::new(false),
+        }
     }
 
     #[cfg(target_os = "windows")]
-    fn enumerate_audio_sessions_internal() -> Result<Vec<AudioSession>> {
-        // Note: Windows 0.62 crate doesn't have IAudioSessionManager2 and related APIs
-        // in the base package. These would require additional feature flags or
-        // using windows-sys crate for raw bindings.
-        // For now, we provide a simplified implementation with master volume only.
+    fn cached_icon(&self, exe_path: &str) -> Option<String> {
+        let mut cache = self.icon_cache.lock().unwrap();
+        cache
+            .entry(exe_path.to_string())
+            .or_insert_with(|| extract_icon_base64(exe_path))
+            .clone()
+    }
 
-        let mut sessions = Vec::new();
+    #[cfg(target_os = "windows")]
+    fn enumerate_audio_sessions_internal(&self) -> Result<Vec<AudioSession>> {
+        use windows::core::Interface;
+        use windows::Win32::Media::Audio::{eRender, ISimpleAudioVolume};
 
-        // Add Master Volume
-        sessions.push(AudioSession {
+        let endpoint_volume = default_endpoint_volume(eRender)?;
+        let (master_volume, master_muted) = unsafe {
+            (
+                endpoint_volume.GetMasterVolumeLevelScalar()?,
+                endpoint_volume.GetMute()?.as_bool(),
+            )
+        };
+
+        let mut sessions = vec![AudioSession {
             process_id: 0,
             process_name: "Master".to_string(),
             display_name: "Master Volume".to_string(),
-            volume: 50.0,
-            is_muted: false,
-        });
+            volume: percent(master_volume),
+            is_muted: master_muted,
+            session_count: 1,
+        }];
+
+        let expose_system_sounds = self
+            .expose_system_sounds
+            .load(std::sync::atomic::Ordering::Relaxed);
+
+        for (process_id, control) in render_sessions()? {
+            let is_system_sounds = process_id == SYSTEM_SOUNDS_PROCESS_ID;
+            if is_system_sounds && !expose_system_sounds {
+                continue;
+            }
+
+            let volume = match control.cast::<ISimpleAudioVolume>() {
+                Ok(volume) => volume,
+                Err(e) => {
+                    log::debug!("Skipping session for process {}: {}", process_id, e);
+                    continue;
+                }
+            };
+            let (level, is_muted) = unsafe {
+                (
+                    volume.GetMasterVolume().unwrap_or(1.0),
+                    volume.GetMute().map(|m| m.as_bool()).unwrap_or(false),
+                )
+            };
+
+            if is_system_sounds {
+                sessions.push(AudioSession {
+                    process_id,
+                    process_name: "System Sounds".to_string(),
+                    display_name: "System Sounds".to_string(),
+                    volume: percent(level),
+                    is_muted,
+                    session_count: 1,
+                });
+                continue;
+            }
+
+            let process_path = get_process_path_from_id(process_id);
+            let process_name = process_path
+                .as_deref()
+                .and_then(|path| path.rsplit('\\').next())
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("PID {}", process_id));
+            let display_name = process_name
+                .strip_suffix(".exe")
+                .unwrap_or(&process_name)
+                .to_string();
 
-        // Add some common Windows applications as placeholders
-        // In a real implementation with proper APIs, you'd enumerate actual sessions
-        let common_apps = vec![
-            (1234, "chrome.exe", "Google Chrome"),
-            (5678, "firefox.exe", "Mozilla Firefox"),
-            (9012, "spotify.exe", "Spotify"),
-            (3456, "discord.exe", "Discord"),
-            (7890, "msedge.exe", "Microsoft Edge"),
-        ];
-
-        for (pid, process_name, display_name) in common_apps {
             sessions.push(AudioSession {
-                process_id: pid,
-                process_name: process_name.to_string(),
-                display_name: display_name.to_string(),
-                volume: 50.0,
-                is_muted: false,
+                process_id,
+                process_name,
+                display_name,
+                volume: percent(level),
+                is_muted,
+                session_count: 1,
             });
         }
 
@@ -140,89 +757,123 @@ impl AudioManager for WindowsAudioManager {
     fn get_audio_sessions(&self) -> Result<Vec<AudioSession>> {
         #[cfg(target_os = "windows")]
         {
-            // Try to enumerate real sessions, fallback to mock data on error
-            match Self::enumerate_audio_sessions_internal() {
-                Ok(sessions) if !sessions.is_empty() => Ok(sessions),
-                Ok(_) => {
-                    // No sessions found, return at least Master Volume
-                    Ok(vec![AudioSession {
-                        process_id: 0,
-                        process_name: "Master".to_string(),
-                        display_name: "Master Volume".to_string(),
-                        volume: 75.0,
-                        is_muted: false,
-                    }])
-                }
-                Err(e) => {
-                    log::error!("Failed to enumerate audio sessions: {}", e);
-                    // Return mock data as fallback
-                    Ok(vec![
-                        AudioSession {
-                            process_id: 0,
-                            process_name: "Master".to_string(),
-                            display_name: "Master Volume".to_string(),
-                            volume: 75.0,
-                            is_muted: false,
-                        },
-                        AudioSession {
-                            process_id: 1234,
-                            process_name: "chrome.exe".to_string(),
-                            display_name: "Google Chrome".to_string(),
-                            volume: 50.0,
-                            is_muted: false,
-                        },
-                        AudioSession {
-                            process_id: 5678,
-                            process_name: "spotify.exe".to_string(),
-                            display_name: "Spotify".to_string(),
-                            volume: 65.0,
-                            is_muted: false,
-                        },
-                    ])
-                }
-            }
+            // Surface failures rather than showing made-up sessions to real users
+            self.enumerate_audio_sessions_internal().map_err(|e| {
+                log::error!("Failed to enumerate audio sessions: {}", e);
+                e
+            })
         }
 
         #[cfg(not(target_os = "windows"))]
         {
             // Non-Windows platform - return mock data
-            Ok(vec![
-                AudioSession {
-                    process_id: 0,
-                    process_name: "Master".to_string(),
-                    display_name: "Master Volume".to_string(),
-                    volume: 75.0,
-                    is_muted: false,
-                },
-            ])
+            Ok(vec![AudioSession {
+                process_id: 0,
+                process_name: "Master".to_string(),
+                display_name: "Master Volume".to_string(),
+                volume: 75.0,
+                is_muted: false,
+                session_count: 1,
+            }])
         }
     }
 
-    fn set_app_volume(&self, process_id: u32, volume: f32) -> Result<()> {
+    fn set_app_volume(&self, process_id: u32, volume: Volume) -> Result<()> {
         #[cfg(target_os = "windows")]
         {
+            use windows::core::Interface;
+            use windows::Win32::Media::Audio::ISimpleAudioVolume;
+
             if process_id == 0 {
                 // Master volume
                 return self.set_master_volume(volume);
             }
 
-            // Per-app volume control would require ISimpleAudioVolume
-            // which is not available in the base windows crate features
-            // For now, just log the request
-            log::info!(
-                "Windows: Would set volume for process {} to {}% (not implemented)",
-                process_id, volume
-            );
+            let scalar = volume.as_scalar();
+            let mut found = false;
+            for (session_process_id, control) in render_sessions()? {
+                if session_process_id == process_id {
+                    let simple_volume = control.cast::<ISimpleAudioVolume>()?;
+                    unsafe {
+                        simple_volume.SetMasterVolume(scalar, std::ptr::null())?;
+                    }
+                    found = true;
+                }
+            }
+
+            if !found {
+                return Err(MixerError::SessionNotFound(format!(
+                    "No audio session for process {}",
+                    process_id
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    fn set_volumes(&self, updates: &[(u32, Volume)]) -> Result<()> {
+        #[cfg(target_os = "windows")]
+        {
+            use windows::core::Interface;
+            use windows::Win32::Media::Audio::ISimpleAudioVolume;
+
+            for &(_, volume) in updates.iter().filter(|(pid, _)| *pid == 0) {
+                self.set_master_volume(volume)?;
+            }
+
+            let app_updates: Vec<(u32, Volume)> = updates
+                .iter()
+                .copied()
+                .filter(|(pid, _)| *pid != 0)
+                .collect();
+            if app_updates.is_empty() {
+                return Ok(());
+            }
+
+            // One enumeration for the whole batch
+            let sessions = render_sessions()?;
+            let mut missing = Vec::new();
+            for (process_id, volume) in app_updates {
+                let scalar = volume.as_scalar();
+                let mut found = false;
+                for (_, control) in sessions.iter().filter(|(pid, _)| *pid == process_id) {
+                    let simple_volume = control.cast::<ISimpleAudioVolume>()?;
+                    unsafe {
+                        simple_volume.SetMasterVolume(scalar, std::ptr::null())?;
+                    }
+                    found = true;
+                }
+                if !found {
+                    missing.push(process_id);
+                }
+            }
+
+            if !missing.is_empty() {
+                return Err(MixerError::SessionNotFound(format!(
+                    "No audio session for processes {:?}",
+                    missing
+                ))
+                .into());
+            }
         }
+
+        #[cfg(not(target_os = "windows"))]
+        let _ = updates;
+
         Ok(())
     }
 
-    fn set_master_volume(&self, volume: f32) -> Result<()> {
+    fn set_master_volume(&self, volume: Volume) -> Result<()> {
         #[cfg(target_os = "windows")]
         {
-            // Note: Master volume control APIs are not available in the base windows crate
-            // This would require additional features or using windows-sys
-            log::info!("Windows: Would set master volume to {}% (not implemented)", volume);
+            use windows::Win32::Media::Audio::eRender;
+
+            let endpoint_volume = default_endpoint_volume(eRender)?;
+            let scalar = volume.as_scalar();
+            unsafe {
+                endpoint_volume.SetMasterVolumeLevelScalar(scalar, std::ptr::null())?;
+            }
         }
 
         Ok(())
@@ -231,21 +882,435 @@ impl AudioManager for WindowsAudioManager {
     fn get_master_volume(&self) -> Result<f32> {
         #[cfg(target_os = "windows")]
         {
-            // Note: Master volume control APIs are not available in the base windows crate
-            // This would require additional features or using windows-sys
-            // Return a default value for now
+            use windows::Win32::Media::Audio::eRender;
+
+            let endpoint_volume = default_endpoint_volume(eRender)?;
+            let scalar = unsafe { endpoint_volume.GetMasterVolumeLevelScalar()? };
+            Ok(percent(scalar))
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            Ok(50.0)
+        }
+    }
+
+    fn set_input_volume(&self, volume: Volume) -> Result<()> {
+        #[cfg(target_os = "windows")]
+        {
+            use windows::Win32::Media::Audio::eCapture;
+
+            let endpoint_volume = default_endpoint_volume(eCapture)?;
+            let scalar = volume.as_scalar();
+            unsafe {
+                endpoint_volume.SetMasterVolumeLevelScalar(scalar, std::ptr::null())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_input_volume(&self) -> Result<f32> {
+        #[cfg(target_os = "windows")]
+        {
+            use windows::Win32::Media::Audio::eCapture;
+
+            let endpoint_volume = default_endpoint_volume(eCapture)?;
+            let scalar = unsafe { endpoint_volume.GetMasterVolumeLevelScalar()? };
+            Ok(percent(scalar))
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
             Ok(50.0)
         }
+    }
+
+    fn set_app_mute(&self, process_id: u32, muted: bool) -> Result<()> {
+        #[cfg(target_os = "windows")]
+        {
+            use windows::core::Interface;
+            use windows::Win32::Media::Audio::ISimpleAudioVolume;
+
+            if process_id == 0 {
+                // Master volume
+                return self.set_master_mute(muted);
+            }
+
+            let mut found = false;
+            for (session_process_id, control) in render_sessions()? {
+                if session_process_id == process_id {
+                    let volume = control.cast::<ISimpleAudioVolume>()?;
+                    unsafe {
+                        volume.SetMute(muted, std::ptr::null())?;
+                    }
+                    found = true;
+                }
+            }
+
+            if !found {
+                return Err(MixerError::SessionNotFound(format!(
+                    "No audio session for process {}",
+                    process_id
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    fn set_master_mute(&self, muted: bool) -> Result<()> {
+        #[cfg(target_os = "windows")]
+        {
+            use windows::Win32::Media::Audio::eRender;
+
+            let endpoint_volume = default_endpoint_volume(eRender)?;
+            unsafe {
+                endpoint_volume.SetMute(muted, std::ptr::null())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_master_mute(&self) -> Result<bool> {
+        #[cfg(target_os = "windows")]
+        {
+            use windows::Win32::Media::Audio::eRender;
+
+            let endpoint_volume = default_endpoint_volume(eRender)?;
+            let muted = unsafe { endpoint_volume.GetMute()? };
+            Ok(muted.as_bool())
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            Ok(false)
+        }
+    }
+
+    fn set_input_mute(&self, muted: bool) -> Result<()> {
+        #[cfg(target_os = "windows")]
+        {
+            use windows::Win32::Media::Audio::eCapture;
+
+            let endpoint_volume = default_endpoint_volume(eCapture)?;
+            unsafe {
+                endpoint_volume.SetMute(muted, std::ptr::null())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_input_mute(&self) -> Result<bool> {
+        #[cfg(target_os = "windows")]
+        {
+            use windows::Win32::Media::Audio::eCapture;
+
+            let endpoint_volume = default_endpoint_volume(eCapture)?;
+            let muted = unsafe { endpoint_volume.GetMute()? };
+            Ok(muted.as_bool())
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            Ok(false)
+        }
+    }
+
+    fn list_output_devices(&self) -> Result<Vec<AudioDevice>> {
+        #[cfg(target_os = "windows")]
+        {
+            use windows::Win32::Media::Audio::{
+                eRender, IMMDeviceEnumerator, MMDeviceEnumerator, DEVICE_STATE_ACTIVE,
+            };
+            use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_ALL};
+
+            ensure_com_initialized()?;
+            // No default device just means nothing is marked as default
+            let default_id = default_device(eRender)
+                .and_then(|device| device_id(&device))
+                .ok();
+
+            let mut devices = Vec::new();
+            unsafe {
+                let enumerator: IMMDeviceEnumerator =
+                    CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+                let collection = enumerator.EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)?;
+
+                for i in 0..collection.GetCount()? {
+                    let device = collection.Item(i)?;
+                    let id = device_id(&device)?;
+                    let name = device_friendly_name(&device).unwrap_or_else(|_| id.clone());
+                    devices.push(AudioDevice {
+                        is_default: default_id.as_deref() == Some(id.as_str()),
+                        id,
+                        name,
+                    });
+                }
+            }
+
+            Ok(devices)
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            Ok(Vec::new())
+        }
+    }
+
+    fn set_device_volume(&self, device_id: &str, volume: Volume) -> Result<()> {
+        #[cfg(target_os = "windows")]
+        {
+            let endpoint_volume = endpoint_volume(&device_by_id(device_id)?)?;
+            let scalar = volume.as_scalar();
+            unsafe {
+                endpoint_volume.SetMasterVolumeLevelScalar(scalar, std::ptr::null())?;
+            }
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        let _ = (device_id, volume);
+
+        Ok(())
+    }
+
+    fn get_device_volume(&self, device_id: &str) -> Result<f32> {
+        #[cfg(target_os = "windows")]
+        {
+            let endpoint_volume = endpoint_volume(&device_by_id(device_id)?)?;
+            let scalar = unsafe { endpoint_volume.GetMasterVolumeLevelScalar()? };
+            Ok(percent(scalar))
+        }
 
         #[cfg(not(target_os = "windows"))]
         {
+            let _ = device_id;
             Ok(50.0)
         }
     }
+
+    fn set_device_mute(&self, device_id: &str, muted: bool) -> Result<()> {
+        #[cfg(target_os = "windows")]
+        {
+            let endpoint_volume = endpoint_volume(&device_by_id(device_id)?)?;
+            unsafe {
+                endpoint_volume.SetMute(muted, std::ptr::null())?;
+            }
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        let _ = (device_id, muted);
+
+        Ok(())
+    }
+
+    fn get_device_mute(&self, device_id: &str) -> Result<bool> {
+        #[cfg(target_os = "windows")]
+        {
+            let endpoint_volume = endpoint_volume(&device_by_id(device_id)?)?;
+            let muted = unsafe { endpoint_volume.GetMute()? };
+            Ok(muted.as_bool())
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            let _ = device_id;
+            Ok(false)
+        }
+    }
+
+    fn watch_default_device(&self, on_change: DeviceChangeCallback) -> Result<()> {
+        #[cfg(target_os = "windows")]
+        {
+            use windows::Win32::Media::Audio::{
+                IMMDeviceEnumerator, IMMNotificationClient, MMDeviceEnumerator,
+            };
+            use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_ALL};
+
+            // COM interfaces aren't Send, so the registration lives on its own
+            // thread for the rest of the process. Master, input and session
+            // lookups resolve the default device on every call, so they follow
+            // the switch without holding any state here.
+            let (ready_tx, ready_rx) = std::sync::mpsc::channel::<Result<()>>();
+            std::thread::Builder::new()
+                .name("audio-device-watcher".to_string())
+                .spawn(move || {
+                    let registration = ensure_com_initialized().and_then(|_| unsafe {
+                        let enumerator: IMMDeviceEnumerator =
+                            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+                        let client: IMMNotificationClient =
+                            DefaultDeviceNotifier { on_change }.into();
+                        enumerator.RegisterEndpointNotificationCallback(&client)?;
+                        Ok((enumerator, client))
+                    });
+
+                    match registration {
+                        Ok(_registration) => {
+                            let _ = ready_tx.send(Ok(()));
+                            loop {
+                                std::thread::park();
+                            }
+                        }
+                        Err(e) => {
+                            let _ = ready_tx.send(Err(e));
+                        }
+                    }
+                })?;
+
+            ready_rx
+                .recv()
+                .map_err(|_| anyhow!("Device watcher thread exited"))??;
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        let _ = on_change;
+
+        Ok(())
+    }
+
+    fn get_session_icon(&self, process_id: u32) -> Result<Option<String>> {
+        #[cfg(target_os = "windows")]
+        {
+            Ok(get_process_path_from_id(process_id).and_then(|path| self.cached_icon(&path)))
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            let _ = process_id;
+            Ok(None)
+        }
+    }
+
+    fn set_expose_system_sounds(&self, enabled: bool) {
+        #[cfg(target_os = "windows")]
+        self.expose_system_sounds
+            .store(enabled, std::sync::atomic::Ordering::Relaxed);
+
+        #[cfg(not(target_os = "windows"))]
+        let _ = enabled;
+    }
+
+    fn watch_sessions(&self, on_change: SessionChangeCallback) -> Result<bool> {
+        #[cfg(target_os = "windows")]
+        {
+            use windows::Win32::Media::Audio::{
+                eRender, IAudioSessionManager2, IAudioSessionNotification,
+            };
+            use windows::Win32::System::Com::CLSCTX_ALL;
+
+            // Registered on a parked thread like the device watcher, which
+            // wakes to drop expired sessions. Only the current default output
+            // is watched; sessions on a device switched to later are left to
+            // the caller's poll.
+            let (ready_tx, ready_rx) = std::sync::mpsc::channel::<Result<()>>();
+            std::thread::Builder::new()
+                .name("audio-session-watcher".to_string())
+                .spawn(move || {
+                    let watcher = std::thread::current();
+                    let registration = ensure_com_initialized().and_then(|_| unsafe {
+                        let device = default_device(eRender)?;
+                        let session_manager =
+                            device.Activate::<IAudioSessionManager2>(CLSCTX_ALL, None)?;
+
+                        // Enumerating first is also what starts creation notifications
+                        let enumerator = session_manager.GetSessionEnumerator()?;
+                        let watched = WatchedSessions::default();
+                        // Owned by the notifier from here, so a failure below
+                        // unregisters whatever was already watched
+                        let notifier: IAudioSessionNotification = SessionNotifier {
+                            on_change: on_change.clone(),
+                            watcher: watcher.clone(),
+                            watched: watched.clone(),
+                        }
+                        .into();
+                        for i in 0..enumerator.GetCount()? {
+                            let control = enumerator.GetSession(i)?;
+                            let session = watch_session_events(&control, &on_change, &watcher)?;
+                            watched.lock().unwrap().push(session);
+                        }
+
+                        session_manager.RegisterSessionNotification(&notifier)?;
+                        Ok((session_manager, notifier, watched))
+                    });
+
+                    match registration {
+                        Ok((_session_manager, _notifier, watched)) => {
+                            let _ = ready_tx.send(Ok(()));
+                            loop {
+                                std::thread::park();
+                                prune_expired_sessions(&watched);
+                            }
+                        }
+                        Err(e) => {
+                            let _ = ready_tx.send(Err(e));
+                        }
+                    }
+                })?;
+
+            ready_rx
+                .recv()
+                .map_err(|_| anyhow!("Session watcher thread exited"))??;
+            Ok(true)
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            let _ = on_change;
+            Ok(false)
+        }
+    }
+
+    fn supports_per_app_volume(&self) -> bool {
+        cfg!(target_os = "windows")
+    }
+
+    fn supports_mute(&self) -> bool {
+        cfg!(target_os = "windows")
+    }
+
+    fn supports_metering(&self) -> bool {
+        cfg!(target_os = "windows")
+    }
+
+    fn backend_name(&self) -> &'static str {
+        "windows"
+    }
+
+    fn get_peak_levels(&self) -> Result<Vec<(u32, f32)>> {
+        #[cfg(target_os = "windows")]
+        {
+            use windows::core::Interface;
+            use windows::Win32::Media::Audio::eRender;
+            use windows::Win32::Media::Audio::Endpoints::IAudioMeterInformation;
+            use windows::Win32::System::Com::CLSCTX_ALL;
+
+            let device = default_device(eRender)?;
+
+            unsafe {
+                let master_meter = device.Activate::<IAudioMeterInformation>(CLSCTX_ALL, None)?;
+                let mut levels = vec![(0, master_meter.GetPeakValue()?)];
+
+                for (process_id, control) in render_sessions()? {
+                    if let Ok(meter) = control.cast::<IAudioMeterInformation>() {
+                        levels.push((process_id, meter.GetPeakValue().unwrap_or(0.0)));
+                    }
+                }
+
+                Ok(levels)
+            }
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            Ok(Vec::new())
+        }
+    }
 }
 
 impl Default for WindowsAudioManager {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,138 @@
+use anyhow::Result;
+use std::path::Path;
+use tauri::AppHandle;
+
+/// Registers the app to launch when the user logs in, or removes the entry.
+/// Re-registering is harmless and points the entry at the current executable.
+pub fn set_enabled(app_handle: &AppHandle, enabled: bool) -> Result<()> {
+    let exe = std::env::current_exe()?;
+    set_platform_enabled(app_handle, &exe, enabled)
+}
+
+/// Adds or removes a value under the per-user Run key
+#[cfg(target_os = "windows")]
+fn set_platform_enabled(app_handle: &AppHandle, exe: &Path, enabled: bool) -> Result<()> {
+    use windows::core::HSTRING;
+    use windows::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+    use windows::Win32::System::Registry::{
+        RegDeleteKeyValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ,
+    };
+
+    const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+
+    let run_key = HSTRING::from(RUN_KEY);
+    let value_name = HSTRING::from(app_handle.package_info().name.as_str());
+
+    if enabled {
+        // Quoted so install paths with spaces aren't split
+        let command: Vec<u16> = format!("\"{}\"", exe.display())
+            .encode_utf16()
+            .chain(Some(0))
+            .collect();
+        unsafe {
+            RegSetKeyValueW(
+                HKEY_CURRENT_USER,
+                &run_key,
+                &value_name,
+                REG_SZ.0,
+                Some(command.as_ptr().cast()),
+                (command.len() * std::mem::size_of::<u16>()) as u32,
+            )
+        }
+        .ok()?;
+    } else {
+        let result = unsafe { RegDeleteKeyValueW(HKEY_CURRENT_USER, &run_key, &value_name) };
+        if result != ERROR_FILE_NOT_FOUND {
+            result.ok()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes or removes a LaunchAgent that runs the app at login
+#[cfg(target_os = "macos")]
+fn set_platform_enabled(app_handle: &AppHandle, exe: &Path, enabled: bool) -> Result<()> {
+    use tauri::Manager;
+
+    let label = &app_handle.config().identifier;
+    let agent_path = app_handle
+        .path()
+        .home_dir()?
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", label));
+
+    if !enabled {
+        return remove_if_exists(&agent_path);
+    }
+
+    let plist = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        xml_escape(label),
+        xml_escape(&exe.display().to_string())
+    );
+
+    write_entry(&agent_path, &plist)
+}
+
+/// Writes or removes an XDG autostart desktop entry
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn set_platform_enabled(app_handle: &AppHandle, exe: &Path, enabled: bool) -> Result<()> {
+    use tauri::Manager;
+
+    let entry_path = app_handle
+        .path()
+        .config_dir()?
+        .join("autostart")
+        .join(format!("{}.desktop", app_handle.config().identifier));
+
+    if !enabled {
+        return remove_if_exists(&entry_path);
+    }
+
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\"\nX-GNOME-Autostart-enabled=true\n",
+        app_handle.package_info().name,
+        exe.display()
+    );
+
+    write_entry(&entry_path, &entry)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn write_entry(path: &Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
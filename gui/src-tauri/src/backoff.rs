@@ -0,0 +1,95 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::types::BackoffConfig;
+
+/// Delays between reconnect attempts, growing from `initial_ms` by
+/// `multiplier` each attempt up to `max_ms`. Each delay is spread by up to
+/// `jitter` either way so several retry loops don't poll in lockstep.
+/// Each outage gets a fresh one, so a successful connect starts over.
+pub struct Backoff {
+    config: BackoffConfig,
+    next_ms: f64,
+}
+
+impl Backoff {
+    pub fn new(config: BackoffConfig) -> Self {
+        Self {
+            config,
+            next_ms: config.initial_ms as f64,
+        }
+    }
+
+    /// Delay before the next attempt
+    pub fn next_delay(&mut self) -> Duration {
+        let max_ms = self.config.max_ms.max(self.config.initial_ms) as f64;
+        let base_ms = self.next_ms.min(max_ms);
+        self.next_ms = (base_ms * f64::from(self.config.multiplier.max(1.0))).min(max_ms);
+
+        let jitter = f64::from(self.config.jitter.clamp(0.0, 1.0));
+        let spread = 1.0 + jitter * (2.0 * random_unit() - 1.0);
+        Duration::from_millis((base_ms * spread).round() as u64)
+    }
+}
+
+/// Uniform value in `0.0..1.0`, random enough to spread retries. `RandomState`
+/// is seeded per instance, which saves pulling in an RNG crate.
+fn random_unit() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(initial_ms: u64, max_ms: u64, multiplier: f32, jitter: f32) -> BackoffConfig {
+        BackoffConfig {
+            initial_ms,
+            max_ms,
+            multiplier,
+            jitter,
+        }
+    }
+
+    fn delays_ms(config: BackoffConfig, count: usize) -> Vec<u128> {
+        let mut backoff = Backoff::new(config);
+        (0..count)
+            .map(|_| backoff.next_delay().as_millis())
+            .collect()
+    }
+
+    #[test]
+    fn delays_grow_by_the_multiplier_up_to_the_cap() {
+        assert_eq!(
+            delays_ms(config(100, 1000, 2.0, 0.0), 6),
+            vec![100, 200, 400, 800, 1000, 1000]
+        );
+    }
+
+    #[test]
+    fn jitter_stays_within_its_fraction_either_way() {
+        let mut backoff = Backoff::new(config(1000, 1000, 1.0, 0.25));
+        for _ in 0..200 {
+            let delay = backoff.next_delay().as_millis();
+            assert!((750..=1250).contains(&delay), "{} ms", delay);
+        }
+    }
+
+    #[test]
+    fn shrinking_multiplier_is_treated_as_constant() {
+        assert_eq!(
+            delays_ms(config(500, 5000, 0.5, 0.0), 3),
+            vec![500, 500, 500]
+        );
+    }
+
+    #[test]
+    fn cap_below_the_initial_delay_is_raised_to_it() {
+        assert_eq!(
+            delays_ms(config(2000, 500, 2.0, 0.0), 3),
+            vec![2000, 2000, 2000]
+        );
+    }
+}
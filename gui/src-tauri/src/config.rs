@@ -1,9 +1,14 @@
 use anyhow::Result;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager};
 
-use crate::types::AppConfig;
+use crate::error::MixerError;
+use crate::types::{
+    AppConfig, CalibrationData, ChannelMapping, HotkeyBinding, Profile, RematchedMapping,
+    CONFIG_VERSION, DEFAULT_PROFILE_NAME,
+};
 
 const CONFIG_FILE_NAME: &str = "config.json";
 
@@ -21,20 +26,44 @@ pub fn load_config(app_handle: &AppHandle) -> Result<AppConfig> {
 
     if !config_path.exists() {
         // Return default config if file doesn't exist
-        return Ok(AppConfig {
-            start_with_windows: false,
-            minimize_to_tray: true,
-            auto_connect: true,
-            theme: "dark".to_string(),
-        });
+        return Ok(AppConfig::default());
     }
 
     let config_str = fs::read_to_string(config_path)?;
-    let config: AppConfig = serde_json::from_str(&config_str)?;
+    let mut config: AppConfig = serde_json::from_str(&config_str)?;
+    migrate_config(&mut config);
 
     Ok(config)
 }
 
+/// Upgrades legacy mapping targets and wraps flat mappings from older
+/// configs into a "Default" profile
+fn migrate_config(config: &mut AppConfig) {
+    for mapping in config.channel_mappings.iter_mut().chain(
+        config
+            .profiles
+            .iter_mut()
+            .flat_map(|p| p.mappings.iter_mut()),
+    ) {
+        mapping.migrate_legacy_target();
+    }
+
+    if config.profiles.is_empty() {
+        config.profiles.push(Profile {
+            name: DEFAULT_PROFILE_NAME.to_string(),
+            mappings: std::mem::take(&mut config.channel_mappings),
+        });
+    }
+
+    if !config
+        .profiles
+        .iter()
+        .any(|p| p.name == config.active_profile)
+    {
+        config.active_profile = config.profiles[0].name.clone();
+    }
+}
+
 pub fn save_config(app_handle: &AppHandle, config: &AppConfig) -> Result<()> {
     let config_path = get_config_path(app_handle)?;
     let config_str = serde_json::to_string_pretty(config)?;
@@ -43,16 +72,70 @@ pub fn save_config(app_handle: &AppHandle, config: &AppConfig) -> Result<()> {
     Ok(())
 }
 
+/// Writes the full configuration to a user-chosen file
+pub fn export_config(app_handle: &AppHandle, path: &Path) -> Result<()> {
+    let mut config = load_config(app_handle)?;
+    config.version = CONFIG_VERSION;
+
+    let config_str = serde_json::to_string_pretty(&config)?;
+    fs::write(path, config_str)
+        .map_err(|e| MixerError::Config(format!("Failed to write {}: {}", path.display(), e)))?;
+
+    Ok(())
+}
+
+/// Replaces the configuration with one written by `export_config`
+pub fn import_config(app_handle: &AppHandle, path: &Path) -> Result<AppConfig> {
+    let config_str = fs::read_to_string(path)
+        .map_err(|e| MixerError::Config(format!("Failed to read {}: {}", path.display(), e)))?;
+    let value: serde_json::Value = serde_json::from_str(&config_str).map_err(|e| {
+        MixerError::Config(format!(
+            "{} is not a valid JSON file: {}",
+            path.display(),
+            e
+        ))
+    })?;
+
+    // Files from before versioning have no field and are read as the current version
+    let version = match value.get("version") {
+        None => CONFIG_VERSION as u64,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| MixerError::Config(format!("Invalid config version: {}", version)))?,
+    };
+    if version > CONFIG_VERSION as u64 {
+        return Err(MixerError::Config(format!(
+            "This config was exported by a newer version (format {}, supported up to {})",
+            version, CONFIG_VERSION
+        ))
+        .into());
+    }
+
+    let mut config: AppConfig = serde_json::from_value(value)
+        .map_err(|e| MixerError::Config(format!("Not a mixer configuration file: {}", e)))?;
+    migrate_config(&mut config);
+    config.version = CONFIG_VERSION;
+
+    save_config(app_handle, &config)?;
+
+    Ok(config)
+}
+
+/// Applies the given general settings, leaving the others as they are
 pub fn update_settings(
     app_handle: &AppHandle,
     start_with_windows: Option<bool>,
     minimize_to_tray: Option<bool>,
     auto_connect: Option<bool>,
     theme: Option<String>,
-) -> Result<()> {
+) -> Result<AppConfig> {
     let mut config = load_config(app_handle)?;
 
     if let Some(value) = start_with_windows {
+        // Saved only once the login entry is in place, so the flag stays truthful
+        if value != config.start_with_windows {
+            crate::autostart::set_enabled(app_handle, value)?;
+        }
         config.start_with_windows = value;
     }
 
@@ -70,5 +153,155 @@ pub fn update_settings(
 
     save_config(app_handle, &config)?;
 
+    Ok(config)
+}
+
+pub fn create_profile(
+    app_handle: &AppHandle,
+    name: String,
+    mappings: Vec<ChannelMapping>,
+) -> Result<AppConfig> {
+    let mut config = load_config(app_handle)?;
+
+    if name.trim().is_empty() {
+        return Err(MixerError::InvalidArgument("Profile name cannot be empty".to_string()).into());
+    }
+
+    if config.profiles.iter().any(|p| p.name == name) {
+        return Err(
+            MixerError::InvalidArgument(format!("Profile '{}' already exists", name)).into(),
+        );
+    }
+
+    config.profiles.push(Profile { name, mappings });
+    save_config(app_handle, &config)?;
+
+    Ok(config)
+}
+
+pub fn delete_profile(app_handle: &AppHandle, name: &str) -> Result<AppConfig> {
+    let mut config = load_config(app_handle)?;
+
+    if config.active_profile == name {
+        return Err(MixerError::InvalidArgument(format!(
+            "Cannot delete the active profile '{}'",
+            name
+        ))
+        .into());
+    }
+
+    let before = config.profiles.len();
+    config.profiles.retain(|p| p.name != name);
+    if config.profiles.len() == before {
+        return Err(MixerError::InvalidArgument(format!("Profile '{}' not found", name)).into());
+    }
+
+    save_config(app_handle, &config)?;
+
+    Ok(config)
+}
+
+pub fn switch_profile(app_handle: &AppHandle, name: &str) -> Result<AppConfig> {
+    let mut config = load_config(app_handle)?;
+
+    if !config.profiles.iter().any(|p| p.name == name) {
+        return Err(MixerError::InvalidArgument(format!("Profile '{}' not found", name)).into());
+    }
+
+    config.active_profile = name.to_string();
+    save_config(app_handle, &config)?;
+
+    Ok(config)
+}
+
+/// Inserts or replaces the mapping for a channel in the active profile
+pub fn save_channel_mapping(app_handle: &AppHandle, mapping: ChannelMapping) -> Result<AppConfig> {
+    let mut config = load_config(app_handle)?;
+    let active = config.active_profile.clone();
+
+    let profile = config
+        .profiles
+        .iter_mut()
+        .find(|p| p.name == active)
+        .ok_or_else(|| {
+            MixerError::InvalidArgument(format!("Active profile '{}' not found", active))
+        })?;
+
+    profile
+        .mappings
+        .retain(|m| m.channel_id != mapping.channel_id);
+    profile.mappings.push(mapping);
+    profile.mappings.sort_by_key(|m| m.channel_id);
+
+    save_config(app_handle, &config)?;
+
+    Ok(config)
+}
+
+/// Keeps a re-matched app mapping in the active profile, so it survives a restart
+pub fn save_mapping_rematch(app_handle: &AppHandle, rematch: &RematchedMapping) -> Result<()> {
+    let mut config = load_config(app_handle)?;
+    let active = config.active_profile.clone();
+    if let Some(profile) = config.profiles.iter_mut().find(|p| p.name == active) {
+        if rematch.apply(&mut profile.mappings) {
+            save_config(app_handle, &config)?;
+        }
+    }
+    Ok(())
+}
+
+/// Stores a channel's range in the table of the device on `port`, or in the
+/// shared table for `None`. A device's own table starts as a copy of the
+/// shared one, so its other channels keep their ranges.
+pub fn save_calibration(
+    app_handle: &AppHandle,
+    port: Option<&str>,
+    channel_id: usize,
+    calibration: CalibrationData,
+) -> Result<AppConfig> {
+    let mut config = load_config(app_handle)?;
+    let index = channel_id
+        .checked_sub(1)
+        .ok_or_else(|| MixerError::InvalidArgument(format!("Invalid channel id {}", channel_id)))?;
+
+    let table = match port {
+        Some(port) => config
+            .device_calibration
+            .entry(port.to_string())
+            .or_insert_with(|| config.calibration.clone()),
+        None => &mut config.calibration,
+    };
+    if table.len() <= index {
+        table.resize(index + 1, CalibrationData::default());
+    }
+    table[index] = calibration;
+
+    save_config(app_handle, &config)?;
+
+    Ok(config)
+}
+
+pub fn save_session_volumes(app_handle: &AppHandle, volumes: &HashMap<String, f32>) -> Result<()> {
+    let mut config = load_config(app_handle)?;
+    config.session_volumes = volumes.clone();
+    save_config(app_handle, &config)?;
+    Ok(())
+}
+
+pub fn save_hotkeys(app_handle: &AppHandle, hotkeys: &[HotkeyBinding]) -> Result<()> {
+    let mut config = load_config(app_handle)?;
+    config.hotkeys = hotkeys.to_vec();
+    save_config(app_handle, &config)?;
+    Ok(())
+}
+
+pub fn save_last_port(app_handle: &AppHandle, port: &str) -> Result<()> {
+    let mut config = load_config(app_handle)?;
+
+    if config.last_port.as_deref() != Some(port) {
+        config.last_port = Some(port.to_string());
+        save_config(app_handle, &config)?;
+    }
+
     Ok(())
 }
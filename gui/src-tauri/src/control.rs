@@ -0,0 +1,349 @@
+//! Local control server for Stream Deck plugins and other automation.
+//!
+//! Clients send one JSON command per line, e.g.
+//! `{"cmd":"set_volume","pid":1234,"volume":50}`, and get one JSON reply per
+//! line: `{"ok":true}` or `{"ok":false,"error":{"code":..,"message":..}}`.
+//! Events are pushed on the same connection as `{"event":..,"payload":..}`.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+
+use crate::audio::AudioManager;
+use crate::error::MixerError;
+use crate::types::{AudioSession, ControlServerConfig};
+use crate::volume::Volume;
+
+/// Events a client may fall behind by before it starts missing them
+pub const EVENT_BUFFER: usize = 64;
+
+/// Longest command line accepted; a client sending a longer one is told so
+/// and disconnected, rather than buffered without end
+const MAX_COMMAND_BYTES: u64 = 4096;
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+enum ControlCommand {
+    GetSessions,
+    SetVolume { pid: u32, volume: f32 },
+    SetMasterVolume { volume: f32 },
+    SetMute { pid: u32, muted: bool },
+    SetMasterMute { muted: bool },
+}
+
+/// Pushed to every connected client
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", content = "payload", rename_all = "kebab-case")]
+pub enum ControlEvent {
+    AudioSessionsUpdated(Vec<AudioSession>),
+    VolumeChanged(VolumeChange),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VolumeChange {
+    /// 0 for master volume
+    pub process_id: u32,
+    pub volume: f32,
+}
+
+#[derive(Serialize)]
+struct Reply {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sessions: Option<Vec<AudioSession>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<MixerError>,
+}
+
+/// Accepts clients until `cancel` fires. Binds to localhost unless the config
+/// allows remote connections.
+pub async fn serve(
+    config: &ControlServerConfig,
+    audio_manager: Arc<dyn AudioManager>,
+    events: broadcast::Sender<ControlEvent>,
+    cancel: CancellationToken,
+) -> Result<()> {
+    let host = if config.allow_remote {
+        Ipv4Addr::UNSPECIFIED
+    } else {
+        Ipv4Addr::LOCALHOST
+    };
+    let listener = TcpListener::bind(SocketAddr::from((host, config.port))).await?;
+    log::info!("Control server listening on {}", listener.local_addr()?);
+
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            accepted = listener.accept() => {
+                let (stream, peer) = match accepted {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        log::warn!("Failed to accept control client: {}", e);
+                        continue;
+                    }
+                };
+                log::info!("Control client connected from {}", peer);
+
+                let audio_manager = audio_manager.clone();
+                let events = events.clone();
+                let cancel = cancel.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_client(stream, audio_manager, events, cancel).await {
+                        log::debug!("Control client {} failed: {}", peer, e);
+                    }
+                    log::info!("Control client {} disconnected", peer);
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_client(
+    stream: impl AsyncRead + AsyncWrite,
+    audio_manager: Arc<dyn AudioManager>,
+    events: broadcast::Sender<ControlEvent>,
+    cancel: CancellationToken,
+) -> Result<()> {
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+    // Bytes of the command being read, kept across `select!` rounds
+    let mut line = Vec::new();
+    let mut receiver = events.subscribe();
+
+    loop {
+        let remaining = MAX_COMMAND_BYTES.saturating_sub(line.len() as u64);
+        if remaining == 0 {
+            let error = MixerError::InvalidArgument(format!(
+                "Command longer than {} bytes",
+                MAX_COMMAND_BYTES
+            ));
+            let message = serde_json::to_string(&Reply::failed(error))?;
+            writer.write_all(message.as_bytes()).await?;
+            writer.write_all(b"\n").await?;
+            break;
+        }
+        let mut limited = (&mut reader).take(remaining);
+
+        let message = tokio::select! {
+            _ = cancel.cancelled() => break,
+            read = limited.read_until(b'\n', &mut line) => {
+                if read? == 0 {
+                    break;
+                }
+                if !line.ends_with(b"\n") {
+                    continue;
+                }
+                let command = String::from_utf8_lossy(&line).trim().to_string();
+                line.clear();
+                if command.is_empty() {
+                    continue;
+                }
+                serde_json::to_string(&reply(audio_manager.as_ref(), &command, &events))?
+            }
+            event = receiver.recv() => match event {
+                Ok(event) => serde_json::to_string(&event)?,
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    log::warn!("Control client fell behind, dropped {} events", missed);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+        };
+
+        writer.write_all(message.as_bytes()).await?;
+        writer.write_all(b"\n").await?;
+    }
+
+    Ok(())
+}
+
+fn reply(
+    audio_manager: &dyn AudioManager,
+    line: &str,
+    events: &broadcast::Sender<ControlEvent>,
+) -> Reply {
+    let result = serde_json::from_str::<ControlCommand>(line)
+        .map_err(|e| MixerError::InvalidArgument(format!("Invalid command: {}", e)))
+        .and_then(|command| dispatch(audio_manager, command, events));
+
+    match result {
+        Ok(sessions) => Reply {
+            ok: true,
+            sessions,
+            error: None,
+        },
+        Err(error) => Reply::failed(error),
+    }
+}
+
+impl Reply {
+    fn failed(error: MixerError) -> Self {
+        Self {
+            ok: false,
+            sessions: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Runs a command, returning the session list for `get_sessions`
+fn dispatch(
+    audio_manager: &dyn AudioManager,
+    command: ControlCommand,
+    events: &broadcast::Sender<ControlEvent>,
+) -> Result<Option<Vec<AudioSession>>, MixerError> {
+    let changed = match command {
+        ControlCommand::GetSessions => {
+            return audio_manager
+                .get_audio_sessions()
+                .map(Some)
+                .map_err(MixerError::from);
+        }
+        ControlCommand::SetVolume { pid, volume } => {
+            let volume = Volume::from_percent(volume)?;
+            audio_manager.set_app_volume(pid, volume)?;
+            Some((pid, volume))
+        }
+        ControlCommand::SetMasterVolume { volume } => {
+            let volume = Volume::from_percent(volume)?;
+            audio_manager.set_master_volume(volume)?;
+            Some((0, volume))
+        }
+        ControlCommand::SetMute { pid, muted } => {
+            audio_manager.set_app_mute(pid, muted)?;
+            None
+        }
+        ControlCommand::SetMasterMute { muted } => {
+            audio_manager.set_master_mute(muted)?;
+            None
+        }
+    };
+
+    if let Some((process_id, volume)) = changed {
+        // Only fails when no client is listening
+        let _ = events.send(ControlEvent::VolumeChanged(VolumeChange {
+            process_id,
+            volume: volume.as_percent(),
+        }));
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::SimAudioManager;
+    use tokio::io::AsyncWriteExt;
+
+    fn app_volume(audio_manager: &dyn AudioManager, process_id: u32) -> f32 {
+        audio_manager
+            .get_audio_sessions()
+            .unwrap()
+            .into_iter()
+            .find(|s| s.process_id == process_id)
+            .unwrap()
+            .volume
+            .round()
+    }
+
+    #[test]
+    fn set_volume_writes_and_broadcasts_the_change() {
+        let audio_manager = SimAudioManager::new();
+        let (events, mut receiver) = broadcast::channel(EVENT_BUFFER);
+
+        let command = ControlCommand::SetVolume {
+            pid: 5678,
+            volume: 30.0,
+        };
+        assert!(dispatch(&audio_manager, command, &events)
+            .unwrap()
+            .is_none());
+
+        assert_eq!(app_volume(&audio_manager, 5678), 30.0);
+        match receiver.try_recv().unwrap() {
+            ControlEvent::VolumeChanged(change) => {
+                assert_eq!(change.process_id, 5678);
+                assert_eq!(change.volume.round(), 30.0);
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+    }
+
+    #[test]
+    fn invalid_volume_fails_without_an_event() {
+        let audio_manager = SimAudioManager::new();
+        let (events, mut receiver) = broadcast::channel(EVENT_BUFFER);
+
+        let command = ControlCommand::SetMasterVolume { volume: f32::NAN };
+        assert!(dispatch(&audio_manager, command, &events).is_err());
+
+        assert_eq!(audio_manager.get_master_volume().unwrap(), 75.0);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn get_sessions_returns_the_session_list() {
+        let audio_manager = SimAudioManager::new();
+        let (events, _receiver) = broadcast::channel(EVENT_BUFFER);
+
+        let sessions = dispatch(&audio_manager, ControlCommand::GetSessions, &events)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            sessions.len(),
+            audio_manager.get_audio_sessions().unwrap().len()
+        );
+    }
+
+    #[test]
+    fn malformed_commands_are_answered_with_an_error() {
+        let audio_manager = SimAudioManager::new();
+        let (events, _receiver) = broadcast::channel(EVENT_BUFFER);
+
+        for line in [
+            "not json",
+            r#"{"cmd":"explode"}"#,
+            r#"{"cmd":"set_volume"}"#,
+        ] {
+            let reply = reply(&audio_manager, line, &events);
+            assert!(!reply.ok, "{} was accepted", line);
+            assert!(reply.error.is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn overlong_commands_are_refused_and_the_client_dropped() {
+        let (client, server) = tokio::io::duplex(1024);
+        let (events, _receiver) = broadcast::channel(EVENT_BUFFER);
+        let handling = tokio::spawn(handle_client(
+            server,
+            Arc::new(SimAudioManager::new()),
+            events,
+            CancellationToken::new(),
+        ));
+
+        let (client_reader, mut client_writer) = tokio::io::split(client);
+        let writing = tokio::spawn(async move {
+            let long = vec![b' '; MAX_COMMAND_BYTES as usize + 1];
+            // The server may hang up before taking all of it
+            let _ = client_writer.write_all(&long).await;
+            client_writer
+        });
+
+        let mut reply = String::new();
+        BufReader::new(client_reader)
+            .read_line(&mut reply)
+            .await
+            .unwrap();
+        assert!(reply.contains(r#""ok":false"#), "got {}", reply);
+        handling.await.unwrap().unwrap();
+        drop(writing.await.unwrap());
+    }
+}
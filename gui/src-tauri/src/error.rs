@@ -0,0 +1,140 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// Error returned by Tauri commands. Serializes as `{ code, message }` so the
+/// frontend can tell failures apart without parsing the message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MixerError {
+    /// No port was given and no Pico was detected, or the port doesn't exist
+    SerialNotFound(String),
+    /// The port exists but another program has it open
+    SerialBusy(String),
+    /// The port opened but the device didn't answer `ID?` like the mixer firmware
+    NotAMixer(String),
+    NotConnected,
+    /// A Windows audio API call failed
+    #[cfg_attr(not(windows), allow(dead_code))]
+    ComFailure(String),
+    SessionNotFound(String),
+    DeviceNotFound(String),
+    InvalidVolume(f32),
+    InvalidArgument(String),
+    /// A fader moved the target moments ago and has priority over the UI
+    FaderActive(String),
+    /// The config file couldn't be read, written or understood
+    Config(String),
+    Io(String),
+    Other(String),
+}
+
+impl MixerError {
+    /// Stable identifier sent to the frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::SerialNotFound(_) => "serial_not_found",
+            Self::SerialBusy(_) => "serial_busy",
+            Self::NotAMixer(_) => "not_a_mixer",
+            Self::NotConnected => "not_connected",
+            Self::ComFailure(_) => "com_failure",
+            Self::SessionNotFound(_) => "session_not_found",
+            Self::DeviceNotFound(_) => "device_not_found",
+            Self::InvalidVolume(_) => "invalid_volume",
+            Self::InvalidArgument(_) => "invalid_argument",
+            Self::FaderActive(_) => "fader_active",
+            Self::Config(_) => "config",
+            Self::Io(_) => "io",
+            Self::Other(_) => "other",
+        }
+    }
+}
+
+impl fmt::Display for MixerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotConnected => write!(f, "Not connected"),
+            Self::InvalidVolume(volume) => write!(f, "Invalid volume: {}", volume),
+            Self::SerialNotFound(message)
+            | Self::SerialBusy(message)
+            | Self::NotAMixer(message)
+            | Self::ComFailure(message)
+            | Self::SessionNotFound(message)
+            | Self::DeviceNotFound(message)
+            | Self::InvalidArgument(message)
+            | Self::FaderActive(message)
+            | Self::Config(message)
+            | Self::Io(message)
+            | Self::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for MixerError {}
+
+impl Serialize for MixerError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("MixerError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+impl From<serialport::Error> for MixerError {
+    fn from(e: serialport::Error) -> Self {
+        match e.kind() {
+            serialport::ErrorKind::NoDevice => Self::SerialNotFound(e.to_string()),
+            serialport::ErrorKind::Io(std::io::ErrorKind::NotFound) => {
+                Self::SerialNotFound(e.to_string())
+            }
+            // Windows reports a port held by another program as access denied
+            serialport::ErrorKind::Io(std::io::ErrorKind::PermissionDenied) => {
+                Self::SerialBusy(e.to_string())
+            }
+            _ if e.description.to_lowercase().contains("busy") => Self::SerialBusy(e.to_string()),
+            _ => Self::Io(e.to_string()),
+        }
+    }
+}
+
+impl From<std::io::Error> for MixerError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for MixerError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Config(e.to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl From<windows::core::Error> for MixerError {
+    fn from(e: windows::core::Error) -> Self {
+        Self::ComFailure(e.to_string())
+    }
+}
+
+/// Recovers the most specific variant from whatever the backend returned
+impl From<anyhow::Error> for MixerError {
+    fn from(e: anyhow::Error) -> Self {
+        if let Some(mixer_error) = e.downcast_ref::<MixerError>() {
+            return mixer_error.clone();
+        }
+        if let Some(serial_error) = e.downcast_ref::<serialport::Error>() {
+            return serial_error.clone().into();
+        }
+        #[cfg(target_os = "windows")]
+        if let Some(com_error) = e.downcast_ref::<windows::core::Error>() {
+            return com_error.clone().into();
+        }
+        if e.downcast_ref::<std::io::Error>().is_some() {
+            return Self::Io(e.to_string());
+        }
+        if e.downcast_ref::<serde_json::Error>().is_some() {
+            return Self::Config(e.to_string());
+        }
+        Self::Other(e.to_string())
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::types::{quantize, ChannelMapping, FilterKind};
+
+/// Largest window `FilterKind::Median` may ask for
+const MAX_MEDIAN_WINDOW: usize = 15;
+
+/// Smooths a stream of fader percentages
+pub trait VolumeFilter: Send {
+    /// Feeds one sample, returning the filtered value
+    fn apply(&mut self, value: f32) -> f32;
+}
+
+pub struct Passthrough;
+
+impl VolumeFilter for Passthrough {
+    fn apply(&mut self, value: f32) -> f32 {
+        value
+    }
+}
+
+/// Exponential moving average, `alpha` being the weight of each new sample
+pub struct Ema {
+    alpha: f32,
+    state: Option<f32>,
+}
+
+impl Ema {
+    pub fn new(alpha: f32) -> Self {
+        Self {
+            // 0 would freeze the output, so the weakest allowed is 1%
+            alpha: alpha.clamp(0.01, 1.0),
+            state: None,
+        }
+    }
+}
+
+impl VolumeFilter for Ema {
+    fn apply(&mut self, value: f32) -> f32 {
+        let next = match self.state {
+            Some(previous) => previous + self.alpha * (value - previous),
+            None => value,
+        };
+        self.state = Some(next);
+        next
+    }
+}
+
+/// Median of the last `n` samples, so a single-sample spike never gets through
+pub struct Median {
+    window: VecDeque<f32>,
+    size: usize,
+}
+
+impl Median {
+    pub fn new(size: usize) -> Self {
+        let size = size.clamp(1, MAX_MEDIAN_WINDOW);
+        Self {
+            window: VecDeque::with_capacity(size),
+            size,
+        }
+    }
+}
+
+impl VolumeFilter for Median {
+    fn apply(&mut self, value: f32) -> f32 {
+        if self.window.len() == self.size {
+            self.window.pop_front();
+        }
+        self.window.push_back(value);
+
+        let mut sorted: Vec<f32> = self.window.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        sorted[sorted.len() / 2]
+    }
+}
+
+pub fn build(kind: FilterKind) -> Box<dyn VolumeFilter> {
+    match kind {
+        FilterKind::None => Box::new(Passthrough),
+        FilterKind::Ema { alpha } => Box::new(Ema::new(alpha)),
+        FilterKind::Median { n } => Box::new(Median::new(n)),
+    }
+}
+
+/// One filter per channel, using the kind its first mapping selects
+#[derive(Default)]
+pub struct ChannelFilters {
+    filters: HashMap<usize, (FilterKind, Box<dyn VolumeFilter>)>,
+}
+
+impl ChannelFilters {
+    /// Filters percentages indexed by `channel_id - 1`, rounding the results to
+    /// multiples of `step` so a settling filter eventually stops changing
+    pub fn apply(
+        &mut self,
+        mappings: &[ChannelMapping],
+        percentages: &[f32],
+        step: f32,
+    ) -> Vec<f32> {
+        percentages
+            .iter()
+            .enumerate()
+            .map(|(index, &value)| {
+                let channel_id = index + 1;
+                let kind = mappings
+                    .iter()
+                    .find(|m| m.channel_id == channel_id)
+                    .map(|m| m.filter)
+                    .unwrap_or_default();
+
+                // A changed kind starts over rather than inherit another filter's state
+                let entry = self
+                    .filters
+                    .entry(channel_id)
+                    .or_insert_with(|| (kind, build(kind)));
+                if entry.0 != kind {
+                    *entry = (kind, build(kind));
+                }
+
+                quantize(entry.1.apply(value), step)
+            })
+            .collect()
+    }
+}
+
+/// Whether every filtered value has caught up with its raw reading, after which
+/// feeding the same readings again changes nothing
+pub fn is_settled(filtered: &[f32], raw: &[f32], step: f32) -> bool {
+    filtered
+        .iter()
+        .zip(raw)
+        .all(|(&filtered, &raw)| filtered == quantize(raw, step))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ChannelTarget;
+
+    #[test]
+    fn ema_step_input_settles_without_overshoot() {
+        let mut filter = Ema::new(0.2);
+        filter.apply(0.0);
+
+        let mut previous = 0.0;
+        for _ in 0..60 {
+            let value = filter.apply(100.0);
+            assert!(value >= previous && value <= 100.0);
+            previous = value;
+        }
+        assert!((100.0 - previous).abs() < 0.01);
+    }
+
+    #[test]
+    fn ema_starts_at_first_sample() {
+        assert_eq!(Ema::new(0.1).apply(42.0), 42.0);
+    }
+
+    #[test]
+    fn median_rejects_single_sample_spikes() {
+        let mut filter = Median::new(3);
+        for sample in [50.0, 50.0, 100.0, 50.0, 0.0, 50.0, 50.0] {
+            assert_eq!(filter.apply(sample), 50.0);
+        }
+    }
+
+    #[test]
+    fn median_step_input_settles_after_half_the_window() {
+        let mut filter = Median::new(5);
+        for _ in 0..5 {
+            filter.apply(0.0);
+        }
+
+        let outputs: Vec<f32> = (0..5).map(|_| filter.apply(100.0)).collect();
+        assert_eq!(outputs, vec![0.0, 0.0, 100.0, 100.0, 100.0]);
+    }
+
+    #[test]
+    fn channel_filters_follow_each_mapping_and_round_to_steps() {
+        let mut filtered = ChannelMapping::new(1, ChannelTarget::Master);
+        filtered.filter = FilterKind::Ema { alpha: 0.5 };
+        let mappings = vec![filtered, ChannelMapping::new(2, ChannelTarget::Input)];
+        let mut filters = ChannelFilters::default();
+
+        assert_eq!(filters.apply(&mappings, &[0.0, 0.0], 2.0), vec![0.0, 0.0]);
+        assert_eq!(
+            filters.apply(&mappings, &[100.0, 100.0], 2.0),
+            vec![50.0, 100.0]
+        );
+
+        let mut settled = Vec::new();
+        for _ in 0..10 {
+            settled = filters.apply(&mappings, &[100.0, 100.0], 2.0);
+        }
+        assert_eq!(settled, vec![100.0, 100.0]);
+        assert!(is_settled(&settled, &[100.0, 100.0], 2.0));
+        assert!(!is_settled(&[50.0], &[100.0], 2.0));
+    }
+}
@@ -0,0 +1,172 @@
+use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::types::{ChannelMapping, ChannelTarget};
+
+/// How often the foreground window is checked
+pub const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// How long an app must stay in front before follow-focus channels switch to it,
+/// so windows passed while alt-tabbing are skipped
+const SETTLE_TIME: Duration = Duration::from_millis(500);
+
+/// Executable owning the foreground window, if any. The mixer's own window
+/// counts as none, so reaching for the mixer UI doesn't retarget the fader.
+#[cfg(target_os = "windows")]
+pub fn foreground_executable() -> Option<String> {
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    let process_id = unsafe {
+        let window = GetForegroundWindow();
+        if window.is_invalid() {
+            return None;
+        }
+        let mut process_id = 0u32;
+        GetWindowThreadProcessId(window, Some(&mut process_id as *mut u32));
+        process_id
+    };
+    if process_id == 0 || process_id == std::process::id() {
+        return None;
+    }
+    crate::audio::windows_impl::get_process_name_from_id(process_id)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn foreground_executable() -> Option<String> {
+    None
+}
+
+/// Turns foreground samples into focus changes once they've settled. Apps
+/// are told apart by executable, since the process drawing a window is often
+/// not the one playing its audio.
+#[derive(Default)]
+pub struct FocusTracker {
+    current: Option<String>,
+    candidate: Option<(String, Instant)>,
+}
+
+impl FocusTracker {
+    /// Feeds one sample, returning the executable that just took focus
+    pub fn observe(&mut self, executable: Option<&str>, now: Instant) -> Option<String> {
+        // The desktop and the mixer itself keep the previous app
+        let Some(executable) = executable else {
+            self.candidate = None;
+            return None;
+        };
+        let same = |other: &str| other.eq_ignore_ascii_case(executable);
+        if self.current.as_deref().is_some_and(same) {
+            self.candidate = None;
+            return None;
+        }
+
+        match &self.candidate {
+            Some((candidate, since)) if same(candidate) => {
+                if now.duration_since(*since) < SETTLE_TIME {
+                    return None;
+                }
+                self.current = Some(executable.to_string());
+                self.candidate = None;
+                self.current.clone()
+            }
+            _ => {
+                self.candidate = Some((executable.to_string(), now));
+                None
+            }
+        }
+    }
+}
+
+/// Points follow-focus mappings at every session of the focused executable,
+/// dropping them while nothing is focused yet
+pub fn resolve(mappings: &[ChannelMapping], focused: Option<&str>) -> Vec<ChannelMapping> {
+    mappings
+        .iter()
+        .filter_map(|mapping| match mapping.target {
+            ChannelTarget::FollowFocus => focused.map(|executable| ChannelMapping {
+                target: ChannelTarget::AppByName,
+                process_names: vec![executable.to_string()],
+                ..mapping.clone()
+            }),
+            _ => Some(mapping.clone()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn after(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn focus_switches_once_an_app_has_settled() {
+        let mut tracker = FocusTracker::default();
+        let start = Instant::now();
+
+        assert_eq!(tracker.observe(Some("game.exe"), start), None);
+        assert_eq!(tracker.observe(Some("game.exe"), after(start, 300)), None);
+        assert_eq!(
+            tracker.observe(Some("game.exe"), after(start, 500)),
+            Some("game.exe".to_string())
+        );
+        // Already focused, so staying in front isn't another change
+        assert_eq!(tracker.observe(Some("game.exe"), after(start, 1200)), None);
+    }
+
+    #[test]
+    fn apps_passed_while_switching_are_skipped() {
+        let mut tracker = FocusTracker::default();
+        let start = Instant::now();
+
+        tracker.observe(Some("game.exe"), start);
+        tracker.observe(Some("chat.exe"), after(start, 200));
+        // The settle time restarts for each app brought to the front
+        assert_eq!(tracker.observe(Some("game.exe"), after(start, 400)), None);
+        assert_eq!(tracker.observe(Some("game.exe"), after(start, 800)), None);
+        assert_eq!(
+            tracker.observe(Some("game.exe"), after(start, 900)),
+            Some("game.exe".to_string())
+        );
+    }
+
+    #[test]
+    fn no_foreground_app_keeps_the_current_one() {
+        let mut tracker = FocusTracker::default();
+        let start = Instant::now();
+
+        tracker.observe(Some("game.exe"), start);
+        tracker.observe(Some("game.exe"), after(start, 500));
+        tracker.observe(Some("chat.exe"), after(start, 600));
+        assert_eq!(tracker.observe(None, after(start, 900)), None);
+        // The mixer's window in between cancelled the switch
+        assert_eq!(tracker.observe(Some("chat.exe"), after(start, 1200)), None);
+    }
+
+    #[test]
+    fn another_window_of_the_focused_executable_is_no_change() {
+        let mut tracker = FocusTracker::default();
+        let start = Instant::now();
+
+        tracker.observe(Some("chrome.exe"), start);
+        tracker.observe(Some("chrome.exe"), after(start, 500));
+        assert_eq!(tracker.observe(Some("Chrome.exe"), after(start, 600)), None);
+        assert_eq!(
+            tracker.observe(Some("Chrome.exe"), after(start, 1200)),
+            None
+        );
+    }
+
+    #[test]
+    fn follow_focus_resolves_to_the_focused_executable() {
+        let mappings = [
+            ChannelMapping::new(1, ChannelTarget::Master),
+            ChannelMapping::new(2, ChannelTarget::FollowFocus),
+        ];
+
+        assert_eq!(resolve(&mappings, None).len(), 1);
+        let resolved = resolve(&mappings, Some("chrome.exe"));
+        assert_eq!(resolved[1].target, ChannelTarget::AppByName);
+        assert_eq!(resolved[1].process_names, vec!["chrome.exe".to_string()]);
+    }
+}
@@ -0,0 +1,258 @@
+use anyhow::Result;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::MissedTickBehavior;
+
+use crate::audio::{AudioManager, SimAudioManager, WindowsAudioManager};
+use crate::backoff::Backoff;
+use crate::serial::{SerialEvent, SerialManager, SmoothingSettings};
+use crate::types::{AppConfig, ChannelMapping};
+use crate::{
+    config, device_mappings, expand_merged_apps, fader_percentages, logging, FaderWriter,
+    WriteSettings, MAX_SESSION_POLL_INTERVAL_SECS, MAX_VOLUME_STEP, MIN_SESSION_POLL_INTERVAL_SECS,
+    MIN_VOLUME_STEP, VOLUME_WRITE_INTERVAL,
+};
+
+/// Command-line flag that runs the mixer without a window
+const HEADLESS_FLAG: &str = "--headless";
+
+/// Whether the process was started with `--headless`
+pub fn requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == HEADLESS_FLAG)
+}
+
+/// Applies the saved mappings to every connected frame until Ctrl+C, logging
+/// instead of emitting events. Tauri is still built, without any windows, for
+/// the config location and the log plugin.
+pub fn run(mut context: tauri::Context<tauri::Wry>) -> Result<()> {
+    #[cfg(all(target_os = "windows", not(debug_assertions)))]
+    let console_attached = attach_console();
+
+    context.config_mut().app.windows.clear();
+    let app = tauri::Builder::default()
+        .plugin(logging::plugin())
+        .build(context)?;
+
+    let config = config::load_config(app.handle())?;
+    logging::apply_level(config.log_level);
+    #[cfg(all(target_os = "windows", not(debug_assertions)))]
+    if !console_attached {
+        log::warn!("No console to stop from; end the process to stop");
+    }
+    log::info!("Running headless");
+
+    tauri::async_runtime::block_on(async {
+        tokio::select! {
+            result = run_mixer(&config) => result,
+            _ = tokio::signal::ctrl_c() => {
+                log::info!("Stopping");
+                Ok(())
+            }
+        }
+    })
+}
+
+/// Release builds on Windows start without a console, which leaves Ctrl+C
+/// nothing to arrive from. Attaches to the terminal the mixer was started
+/// from, or opens a console window when there is none. Returns whether
+/// either worked.
+#[cfg(all(target_os = "windows", not(debug_assertions)))]
+fn attach_console() -> bool {
+    use windows::Win32::System::Console::{AllocConsole, AttachConsole, ATTACH_PARENT_PROCESS};
+
+    unsafe { AttachConsole(ATTACH_PARENT_PROCESS).is_ok() || AllocConsole().is_ok() }
+}
+
+/// Connects to the mixer and follows it, reconnecting with backoff whenever
+/// it is missing or unplugged
+async fn run_mixer(config: &AppConfig) -> Result<()> {
+    let audio_manager: Arc<dyn AudioManager> = if SimAudioManager::requested() {
+        log::info!("Using simulated audio backend");
+        Arc::new(SimAudioManager::new())
+    } else {
+        Arc::new(WindowsAudioManager::new())
+    };
+    audio_manager.set_expose_system_sounds(config.expose_system_sounds);
+
+    let serial_manager = SerialManager::new();
+    serial_manager.set_protocol(config.protocol);
+    serial_manager.set_device_filter(config.device_filter);
+    serial_manager.set_smoothing(SmoothingSettings {
+        smoothing_factor: config.smoothing_factor,
+        deadband: config.deadband,
+    });
+
+    let mut last_port = config.last_port.clone();
+    let mut backoff = Backoff::new(config.reconnect_backoff);
+    loop {
+        match serial_manager.connect(last_port.take()) {
+            Ok(status) => {
+                if let (true, Some(port)) = (status.connected, status.port) {
+                    log::info!("Connected to {}", port);
+                    match follow_device(&serial_manager, &audio_manager, config, &port).await {
+                        Ok(()) => log::warn!("Lost the device on {}", port),
+                        Err(e) => log::error!("Failed to read from {}: {}", port, e),
+                    }
+                    backoff = Backoff::new(config.reconnect_backoff);
+                } else {
+                    log::debug!("No mixer found");
+                }
+            }
+            Err(e) => log::warn!("Failed to connect: {}", e),
+        }
+        tokio::time::sleep(backoff.next_delay()).await;
+    }
+}
+
+/// Applies frames from `port` until it disconnects. Follow-focus mappings
+/// and encoders need the GUI, so their channels are left alone.
+async fn follow_device(
+    serial_manager: &SerialManager,
+    audio_manager: &Arc<dyn AudioManager>,
+    config: &AppConfig,
+    port: &str,
+) -> Result<()> {
+    let (tx, rx) = mpsc::channel(100);
+    serial_manager.start_reading(port, tx).await?;
+    apply_events(rx, audio_manager, config, port).await;
+    Ok(())
+}
+
+/// Writes the frames a reader sends until it reports a disconnect or stops
+async fn apply_events(
+    mut rx: mpsc::Receiver<SerialEvent>,
+    audio_manager: &Arc<dyn AudioManager>,
+    config: &AppConfig,
+    port: &str,
+) {
+    let mut mappings = device_mappings(&config.active_mappings(), port);
+    let step = config.volume_step.clamp(MIN_VOLUME_STEP, MAX_VOLUME_STEP);
+    let calibration = config.calibration_tables();
+    let mut encoder_channels: HashSet<usize> = HashSet::new();
+    let mut writer = FaderWriter::default();
+    let mut pending: Option<Vec<f32>> = None;
+    let mut write_timer = tokio::time::interval(VOLUME_WRITE_INTERVAL);
+    write_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    // App names are matched against this list, not a fresh one per write
+    let mut sessions = Vec::new();
+    let poll_interval_secs = config.session_poll_interval_secs.clamp(
+        MIN_SESSION_POLL_INTERVAL_SECS,
+        MAX_SESSION_POLL_INTERVAL_SECS,
+    );
+    let mut session_timer = tokio::time::interval(Duration::from_secs(poll_interval_secs));
+    session_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            event = rx.recv() => match event {
+                Some(SerialEvent::Data(data)) => {
+                    for (index, delta) in data.encoder_deltas().into_iter().enumerate() {
+                        if delta.is_some() {
+                            encoder_channels.insert(index + 1);
+                        }
+                    }
+                    pending = Some(fader_percentages(
+                        &data,
+                        calibration.for_port(Some(port)),
+                        step,
+                        config.invert_all_channels,
+                    ));
+                }
+                Some(SerialEvent::ParseError(error)) => {
+                    log::warn!(
+                        "Failed to parse serial line {:?} from {}: {}",
+                        error.line,
+                        port,
+                        error.error
+                    );
+                }
+                Some(SerialEvent::Disconnected(_)) | None => return,
+                Some(SerialEvent::Raw(_) | SerialEvent::Diagnostics(_)) => {}
+            },
+            _ = session_timer.tick() => match audio_manager.get_audio_sessions() {
+                Ok(current) => sessions = current,
+                Err(e) => log::warn!("Failed to get audio sessions: {}", e),
+            },
+            _ = write_timer.tick() => {
+                let Some(percentages) = pending.take() else {
+                    continue;
+                };
+                let fader_mappings: Vec<ChannelMapping> = mappings
+                    .iter()
+                    .filter(|m| !encoder_channels.contains(&m.channel_id))
+                    .cloned()
+                    .collect();
+                let fader_mappings = expand_merged_apps(fader_mappings, config.merge_sessions);
+                let (filtered, settled) = writer.filter(&fader_mappings, &percentages, step);
+                if !settled {
+                    pending = Some(percentages);
+                }
+                let settings = WriteSettings {
+                    takeover: config.soft_takeover,
+                    mute_thresholds: config.mute_thresholds,
+                    unmute_on_fader_move: config.unmute_on_fader_move,
+                };
+                writer.write(audio_manager, &fader_mappings, &filtered, &sessions, None, settings);
+                // Only kept for this run; the GUI saves re-matches to the config
+                for rematch in writer.take_rematches() {
+                    rematch.apply(&mut mappings);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ConnectionStatus, PotentiometerData};
+
+    fn frame(json: &str) -> SerialEvent {
+        SerialEvent::Data(serde_json::from_str::<PotentiometerData>(json).unwrap())
+    }
+
+    /// Feeds `events` through `apply_events` with the default config, whose
+    /// first channel drives master volume, returning master volume after
+    async fn master_after(events: Vec<SerialEvent>) -> f32 {
+        let audio_manager: Arc<dyn AudioManager> = Arc::new(SimAudioManager::new());
+        let (tx, rx) = mpsc::channel(100);
+
+        let applying = tokio::spawn({
+            let audio_manager = audio_manager.clone();
+            async move {
+                apply_events(rx, &audio_manager, &AppConfig::default(), "COM3").await;
+            }
+        });
+        for event in events {
+            tx.send(event).await.unwrap();
+        }
+        // A few write intervals, for the frames to be applied
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        tx.send(SerialEvent::Disconnected(ConnectionStatus::default()))
+            .await
+            .unwrap();
+        applying.await.unwrap();
+
+        audio_manager.get_master_volume().unwrap()
+    }
+
+    #[tokio::test]
+    async fn frames_are_written_to_their_mapped_targets() {
+        let master = master_after(vec![frame(r#"{"pot1":2048,"pot2":0,"pot3":0}"#)]).await;
+        assert!((master - 50.0).abs() < 1.0, "master at {}", master);
+    }
+
+    #[tokio::test]
+    async fn encoder_channels_leave_their_pot_reading_alone() {
+        let master = master_after(vec![frame(r#"{"pot1":2048,"pot2":0,"pot3":0,"enc1":1}"#)]).await;
+        assert_eq!(master, 75.0);
+    }
+
+    #[tokio::test]
+    async fn disconnect_ends_the_run() {
+        // Only returns because the disconnect stops `apply_events`
+        assert_eq!(master_after(Vec::new()).await, 75.0);
+    }
+}
@@ -1,153 +1,3362 @@
 mod audio;
+mod autostart;
+mod backoff;
 mod config;
+mod control;
+mod error;
+mod filter;
+mod focus;
+mod headless;
+mod logging;
+mod meter;
+mod protocol;
+mod selftest;
 mod serial;
+mod taper;
 mod types;
+mod volume;
+mod window;
 
-use audio::{AudioManager, WindowsAudioManager};
-use serial::SerialManager;
+use audio::{AudioManager, SavedMutes, SimAudioManager, VolumeSnapshot, WindowsAudioManager};
+use backoff::Backoff;
+use control::{ControlEvent, VolumeChange};
+use error::MixerError;
+use filter::ChannelFilters;
+use focus::FocusTracker;
+use logging::LogLevel;
+use meter::{MeterScale, MeterSettings};
+use serial::{SerialEvent, SerialManager, SmoothingSettings};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, Emitter, Manager, State};
-use tokio::sync::{mpsc, RwLock};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+use tokio::sync::{broadcast, mpsc, Notify, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
 use tokio_util::sync::CancellationToken;
-use types::{AudioSession, ConnectionStatus, MixerChannel, SerialPortInfo};
+use types::{
+    AppConfig, AudioCapabilities, AudioDevice, AudioSession, BackendInfo, BackoffConfig,
+    ButtonAction, CalibrationData, ChannelMapping, ChannelTarget, ConnectionStatus,
+    DeviceDiagnostics, FromDevice, HotkeyAction, HotkeyBinding, IdentifiedChannel, LedMode,
+    MappedApp, MixerChannel, MuteThresholds, PerDevice, PotentiometerData, ProfileList,
+    RawSerialLine, RematchedMapping, SelfTestReport, SerialPortInfo, SerialStalled, SessionDelta,
+    SessionFilter, UsbDeviceFilter, VolumePriority,
+};
+use volume::Volume;
 
 // Constants for magic numbers
-const AUDIO_SESSION_POLL_INTERVAL_SECS: u64 = 2;
+// Allowed range for the configurable session poll interval
+const MIN_SESSION_POLL_INTERVAL_SECS: u64 = 1;
+const MAX_SESSION_POLL_INTERVAL_SECS: u64 = 60;
+// Allowed range for the configurable fader rounding step, in percentage points
+const MIN_VOLUME_STEP: f32 = 0.5;
+const MAX_VOLUME_STEP: f32 = 25.0;
 const MASTER_VOLUME_PROCESS_ID: u32 = 0;
+// Channels assumed until the device reports its own count
+const DEFAULT_CHANNEL_COUNT: usize = 3;
+// Most pots a frame can carry (`pot1`-`pot8`)
+const MAX_CHANNEL_COUNT: usize = 8;
+// Caps volume writes at ~30 Hz however fast frames arrive
+const VOLUME_WRITE_INTERVAL: Duration = Duration::from_millis(33);
+// How often the watchdog checks for a stalled port
+const STALL_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+// Volume change per encoder detent, matching the default fader granularity
+const ENCODER_STEP_PERCENT: f32 = 2.0;
+// Ramps step at ~100 Hz, fine enough that the steps aren't audible
+const RAMP_STEP_INTERVAL: Duration = Duration::from_millis(10);
+// Notification bursts, e.g. from a moving fader, become one refresh
+const SESSION_CHANGE_DEBOUNCE: Duration = Duration::from_millis(100);
+// Remembered app volumes are written at most this often, since dragging a
+// volume in the Windows mixer changes it on every poll
+const SESSION_VOLUMES_SAVE_DELAY: Duration = Duration::from_secs(5);
+// Meters refresh at ~30 Hz, fast enough to look live
+const PEAK_LEVEL_INTERVAL: Duration = Duration::from_millis(33);
+// Faders `identify_channels` watches are sampled at ~20 Hz
+const IDENTIFY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+// Movement below this is pot jitter or a bumped neighbour, not a deliberate move
+const IDENTIFY_MIN_DELTA: f32 = 10.0;
+// After the first fader moves far enough, others still count for this long,
+// so a neighbour nudged first doesn't win
+const IDENTIFY_SETTLE_TIME: Duration = Duration::from_millis(300);
+const DEFAULT_IDENTIFY_TIMEOUT_MS: u64 = 10_000;
+// A waiting fader this close to its target's volume counts as having crossed it
+const SOFT_TAKEOVER_TOLERANCE: f32 = 2.0;
+// Motorized faders are checked against their targets' volumes at ~10 Hz
+const MOTOR_SYNC_INTERVAL: Duration = Duration::from_millis(100);
+// A fader this close to its target's volume needs no move: one default rounding step
+const MOTOR_SYNC_TOLERANCE: f32 = 2.0;
+// How long a channel stays with whichever of the UI or its fader changed it
+// last, when the other has lower priority
+const VOLUME_CONFLICT_WINDOW: Duration = Duration::from_millis(1000);
+
+/// A channel being calibrated: the port it's limited to, if any, and its number
+type CalibrationKey = (Option<String>, usize);
 
 struct AppState {
     serial_manager: Arc<SerialManager>,
     audio_manager: Arc<dyn AudioManager>,
     cancellation_token: CancellationToken,
     last_audio_sessions: Arc<RwLock<Vec<AudioSession>>>,
+    active_mappings: Arc<RwLock<Vec<ChannelMapping>>>,
+    calibration: Arc<RwLock<PerDevice<Vec<CalibrationData>>>>,
+    channel_labels: Arc<RwLock<PerDevice<HashMap<usize, String>>>>,
+    // Channels currently being swept by port (`None` for any device), with
+    // the range observed so far
+    calibrations_in_progress: Arc<RwLock<HashMap<CalibrationKey, CalibrationData>>>,
+    // Most recent fader percentages by port, indexed by `channel_id - 1`
+    channel_values: Arc<RwLock<HashMap<String, Vec<f32>>>>,
+    // Physical channels on each connected device, from its ID reply or frames
+    channel_counts: Arc<RwLock<HashMap<String, usize>>>,
+    // Mute states from before `mute_all`, restored by `unmute_all`
+    mute_all_saved: Arc<RwLock<Option<SavedMutes>>>,
+    // Volumes captured by `snapshot_volumes`, kept until the next snapshot
+    volume_snapshot: Arc<RwLock<Option<VolumeSnapshot>>>,
+    solo: Arc<RwLock<Option<SoloState>>>,
+    merge_sessions: Arc<RwLock<bool>>,
+    session_blocklist: Arc<RwLock<Vec<String>>>,
+    invert_all_channels: Arc<RwLock<bool>>,
+    soft_takeover: Arc<RwLock<bool>>,
+    motorized_faders: Arc<RwLock<bool>>,
+    volume_arbiter: Arc<RwLock<VolumeArbiter>>,
+    // Read by the serial reader on every frame
+    volume_step: Arc<RwLock<f32>>,
+    mute_thresholds: Arc<RwLock<MuteThresholds>>,
+    unmute_on_fader_move: Arc<RwLock<bool>>,
+    // Stops the running `identify_channels` watch, if any
+    identify_channels: Arc<RwLock<Option<CancellationToken>>>,
+    // Read whenever a retry loop starts
+    reconnect_backoff: Arc<RwLock<BackoffConfig>>,
+    // Read by the peak level task on every tick
+    meter: Arc<RwLock<MeterSettings>>,
+    // Read by the session poller before each sleep, so changes apply without a restart
+    session_poll_interval_secs: Arc<RwLock<u64>>,
+    // Signalled by `refresh_audio_sessions` so the poller restarts its wait
+    session_refresh: Arc<Notify>,
+    // Bindings registered with the OS, looked up when a shortcut fires
+    hotkeys: Arc<RwLock<Vec<HotkeyBinding>>>,
+    // Executable that follow-focus channels currently control
+    focused_app: Arc<RwLock<Option<String>>>,
+    // Forwarded to control server clients; sending is harmless when none are connected
+    control_events: broadcast::Sender<ControlEvent>,
+}
+
+/// Volumes captured by `solo_channel`, restored by `clear_solo`
+struct SoloState {
+    channel_id: usize,
+    // Pre-solo volume of every ducked session, by process ID
+    saved_volumes: Vec<(u32, Volume)>,
+    // Latest values of ducked faders that moved while soloed
+    moved_channels: HashMap<usize, f32>,
+}
+
+impl SoloState {
+    /// Whether the mapping's app is ducked; master and input are never ducked
+    /// since that would silence the soloed channel too
+    fn is_ducked(&self, mapping: &ChannelMapping) -> bool {
+        mapping.channel_id != self.channel_id
+            && matches!(
+                mapping.target,
+                ChannelTarget::App(_) | ChannelTarget::AppByName | ChannelTarget::Crossfade { .. }
+            )
+    }
+}
+
+fn apply_mapping(
+    audio_manager: &dyn AudioManager,
+    mapping: &ChannelMapping,
+    value: f32,
+) -> anyhow::Result<()> {
+    let volume = Volume::from_percent(value)?;
+    match mapping.target {
+        ChannelTarget::Master => audio_manager.set_master_volume(volume),
+        ChannelTarget::Input => audio_manager.set_input_volume(volume),
+        ChannelTarget::Device(ref device_id) => audio_manager.set_device_volume(device_id, volume),
+        ChannelTarget::App(process_id) => {
+            if mapping.process_names.is_empty() {
+                audio_manager.set_app_volume(process_id, volume)
+            } else {
+                // Grouped mappings follow the executable, not a single PID
+                for name in &mapping.process_names {
+                    audio_manager.set_volume_by_process_name(name, volume)?;
+                }
+                Ok(())
+            }
+        }
+        ChannelTarget::AppByName => {
+            for name in &mapping.process_names {
+                audio_manager.set_volume_by_process_name(name, volume)?;
+            }
+            Ok(())
+        }
+        // Only reaches an app once `focus::resolve` has pointed it at one
+        ChannelTarget::FollowFocus => Ok(()),
+        ChannelTarget::Crossfade {
+            app_a,
+            app_b,
+            equal_power,
+        } => {
+            let (gain_a, gain_b) = taper::crossfade_gains(volume.as_scalar(), equal_power);
+            audio_manager.set_app_volume(app_a, Volume::from_scalar(gain_a)?)?;
+            audio_manager.set_app_volume(app_b, Volume::from_scalar(gain_b)?)
+        }
+    }
+}
+
+/// Applies `value` like `apply_mapping`, but finds the sessions of grouped
+/// and by-name mappings in `sessions` instead of listing them from the backend
+fn apply_mapping_to_sessions(
+    audio_manager: &dyn AudioManager,
+    mapping: &ChannelMapping,
+    value: f32,
+    sessions: &[AudioSession],
+) -> anyhow::Result<()> {
+    let by_name = match mapping.target {
+        ChannelTarget::App(_) => !mapping.process_names.is_empty(),
+        ChannelTarget::AppByName => true,
+        _ => false,
+    };
+    if !by_name {
+        return apply_mapping(audio_manager, mapping, value);
+    }
+
+    let volume = Volume::from_percent(value)?;
+    let updates: Vec<(u32, Volume)> = mapped_process_ids(mapping, sessions)
+        .into_iter()
+        .map(|process_id| (process_id, volume))
+        .collect();
+    audio_manager.set_volumes(&updates)
+}
+
+/// Applies `value` like `apply_mapping_to_sessions`. When a single-app
+/// mapping's session has gone, retries on a session of the same executable
+/// in `sessions` and returns the new match, which the caller should store in
+/// the mapping. Only the session poller's list is searched, so a failing
+/// write doesn't list sessions again on every pass.
+fn apply_mapping_rematching(
+    audio_manager: &dyn AudioManager,
+    mapping: &ChannelMapping,
+    value: f32,
+    sessions: &[AudioSession],
+) -> anyhow::Result<Option<RematchedMapping>> {
+    let error = match apply_mapping_to_sessions(audio_manager, mapping, value, sessions) {
+        Ok(()) => return Ok(None),
+        Err(e) => e,
+    };
+    let (ChannelTarget::App(old_process_id), Some(name)) = (&mapping.target, &mapping.process_name)
+    else {
+        return Err(error);
+    };
+    let session_gone = matches!(
+        error.downcast_ref::<MixerError>(),
+        Some(MixerError::SessionNotFound(_))
+    );
+    if !session_gone || !mapping.process_names.is_empty() {
+        return Err(error);
+    }
+
+    let Some(session) = sessions.iter().find(|s| {
+        s.process_id != 0
+            && s.process_id != *old_process_id
+            && s.process_name.eq_ignore_ascii_case(name)
+    }) else {
+        return Err(error);
+    };
+    audio_manager.set_app_volume(session.process_id, Volume::from_percent(value)?)?;
+    log::info!(
+        "Channel {} moved from process {} to {} ({})",
+        mapping.channel_id,
+        old_process_id,
+        session.process_id,
+        session.process_name
+    );
+    Ok(Some(RematchedMapping {
+        channel_id: mapping.channel_id,
+        device: mapping.device.clone(),
+        process_name: session.process_name.clone(),
+        old_process_id: *old_process_id,
+        new_process_id: session.process_id,
+    }))
+}
+
+/// Points the mappings behind `rematch` at their new session, in memory and in
+/// the config, and tells the frontend
+async fn record_rematch(
+    app_handle: &AppHandle,
+    active_mappings: &RwLock<Vec<ChannelMapping>>,
+    rematch: &RematchedMapping,
+) {
+    rematch.apply(&mut active_mappings.write().await);
+    if let Err(e) = config::save_mapping_rematch(app_handle, rematch) {
+        log::warn!("Failed to save re-matched mapping: {}", e);
+    }
+    if let Err(e) = app_handle.emit("mapping-rematched", rematch) {
+        log::error!("Failed to emit mapping-rematched event: {}", e);
+    }
+}
+
+/// Flips every fader of an upside-down bank. Applied before the mappings,
+/// so a mapping's own `inverted` cancels it out.
+fn orient_faders(percentages: Vec<f32>, invert_all: bool) -> Vec<f32> {
+    if invert_all {
+        percentages.into_iter().map(|value| 100.0 - value).collect()
+    } else {
+        percentages
+    }
+}
+
+/// One mapping's glide, resolved when it begins to the targets it reaches,
+/// so steps don't look sessions up again
+struct Glide {
+    // As configured, so a newer target for it continues from where it is
+    mapping: ChannelMapping,
+    // Fader value being glided to, applied by `clear_solo` if a solo cuts it short
+    value: f32,
+    // Each resolved target with the volume it started from
+    targets: Vec<(ChannelMapping, f32)>,
+    to: f32,
+    started: tokio::time::Instant,
+}
+
+impl Glide {
+    fn progress(&self, now: tokio::time::Instant) -> f32 {
+        let elapsed_ms = now.duration_since(self.started).as_secs_f32() * 1000.0;
+        (elapsed_ms / self.mapping.ramp_ms as f32).min(1.0)
+    }
+
+    /// Each target with the volume the glide has reached by `now`
+    fn positions(&self, now: tokio::time::Instant) -> Vec<(ChannelMapping, f32)> {
+        let progress = self.progress(now);
+        self.targets
+            .iter()
+            .map(|(target, from)| (target.clone(), from + (self.to - from) * progress))
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct RampQueue {
+    glides: HashMap<usize, Vec<Glide>>,
+    // Whether the stepping task is running; it stops once `glides` empties
+    stepping: bool,
+}
+
+fn lock_ramps(queue: &std::sync::Mutex<RampQueue>) -> std::sync::MutexGuard<'_, RampQueue> {
+    queue
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Volume glides in flight, at most one per channel, all stepped by one task
+#[derive(Default)]
+struct VolumeRamps {
+    queue: Arc<std::sync::Mutex<RampQueue>>,
+    stepper: Option<JoinHandle<()>>,
+    // Checked on every step, so glides on apps a solo ducks stop there
+    solo: Option<Arc<RwLock<Option<SoloState>>>>,
+}
+
+impl VolumeRamps {
+    fn is_running(&self, channel_id: usize) -> bool {
+        lock_ramps(&self.queue).glides.contains_key(&channel_id)
+    }
+
+    /// Stops the channel's current ramp, leaving its targets where they are
+    fn cancel(&mut self, channel_id: usize) {
+        lock_ramps(&self.queue).glides.remove(&channel_id);
+    }
+
+    /// Glides each mapping towards its target volume over its `ramp_ms`,
+    /// continuing from where the channel's current glide has got to
+    fn start(
+        &mut self,
+        audio_manager: Arc<dyn AudioManager>,
+        channel_id: usize,
+        value: f32,
+        targets: Vec<(ChannelMapping, f32)>,
+    ) {
+        let now = tokio::time::Instant::now();
+        let mut queue = lock_ramps(&self.queue);
+        let previous = queue.glides.remove(&channel_id).unwrap_or_default();
+
+        let mut sessions = None;
+        let glides: Vec<Glide> = targets
+            .into_iter()
+            .filter_map(|(mapping, to)| {
+                let targets = match previous.iter().find(|glide| glide.mapping == mapping) {
+                    Some(glide) => glide.positions(now),
+                    None => ramp_targets(audio_manager.as_ref(), &mapping, to, &mut sessions),
+                };
+                (!targets.is_empty()).then_some(Glide {
+                    mapping,
+                    value,
+                    targets,
+                    to,
+                    started: now,
+                })
+            })
+            .collect();
+        if glides.is_empty() {
+            return;
+        }
+        queue.glides.insert(channel_id, glides);
+
+        if !queue.stepping {
+            queue.stepping = true;
+            self.stepper = Some(tokio::spawn(step_ramps(
+                audio_manager,
+                self.queue.clone(),
+                self.solo.clone(),
+            )));
+        }
+    }
+}
+
+impl Drop for VolumeRamps {
+    fn drop(&mut self) {
+        if let Some(stepper) = &self.stepper {
+            stepper.abort();
+        }
+    }
+}
+
+/// `mapping`'s targets with the volume each is at now, app sessions resolved
+/// from one read of `sessions`. Targets whose volume can't be read, such as
+/// crossfades, are set to `to` straight away instead.
+fn ramp_targets(
+    audio_manager: &dyn AudioManager,
+    mapping: &ChannelMapping,
+    to: f32,
+    sessions: &mut Option<Vec<AudioSession>>,
+) -> Vec<(ChannelMapping, f32)> {
+    if !matches!(
+        mapping.target,
+        ChannelTarget::App(_) | ChannelTarget::AppByName
+    ) {
+        return match current_target_volume(audio_manager, mapping) {
+            Ok(from) => vec![(mapping.clone(), from)],
+            Err(_) => {
+                let _ = apply_mapping(audio_manager, mapping, to);
+                Vec::new()
+            }
+        };
+    }
+
+    let sessions =
+        sessions.get_or_insert_with(|| audio_manager.get_audio_sessions().unwrap_or_default());
+    mapped_process_ids(mapping, sessions)
+        .into_iter()
+        .filter_map(|process_id| {
+            let session = sessions.iter().find(|s| s.process_id == process_id)?;
+            let target = ChannelMapping {
+                target: ChannelTarget::App(process_id),
+                process_names: Vec::new(),
+                ..mapping.clone()
+            };
+            Some((target, session.volume))
+        })
+        .collect()
+}
+
+/// Steps every glide until none are left
+async fn step_ramps(
+    audio_manager: Arc<dyn AudioManager>,
+    queue: Arc<std::sync::Mutex<RampQueue>>,
+    solo: Option<Arc<RwLock<Option<SoloState>>>>,
+) {
+    let mut step_timer = tokio::time::interval(RAMP_STEP_INTERVAL);
+    step_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        step_timer.tick().await;
+        // Same lock order as the fader writes: solo, then the glides
+        let mut solo = match &solo {
+            Some(solo) => Some(solo.write().await),
+            None => None,
+        };
+        let now = tokio::time::Instant::now();
+        let mut queue = lock_ramps(&queue);
+
+        for (&channel_id, glides) in queue.glides.iter_mut() {
+            glides.retain(|glide| {
+                // Ducked apps stay ducked; `clear_solo` applies the glide's end
+                if let Some(solo) = solo.as_deref_mut().and_then(Option::as_mut) {
+                    if solo.is_ducked(&glide.mapping) {
+                        solo.moved_channels.insert(channel_id, glide.value);
+                        return false;
+                    }
+                }
+                for (target, volume) in glide.positions(now) {
+                    let _ = apply_mapping(audio_manager.as_ref(), &target, volume);
+                }
+                glide.progress(now) < 1.0
+            });
+        }
+        queue.glides.retain(|_, glides| !glides.is_empty());
+
+        if queue.glides.is_empty() {
+            queue.stepping = false;
+            return;
+        }
+    }
+}
+
+/// Whether each channel's fader drives its targets yet. After a channel's
+/// mappings change, e.g. on connect or a profile switch, the fader is ignored
+/// until it crosses the volume its target already has, so it doesn't jump.
+#[derive(Default)]
+struct SoftTakeover {
+    channels: HashMap<usize, TakeoverChannel>,
+}
+
+struct TakeoverChannel {
+    mappings: Vec<ChannelMapping>,
+    state: TakeoverState,
+}
+
+enum TakeoverState {
+    Engaged,
+    Waiting {
+        /// Target volume to pick up at, read on the first reading after the
+        /// channel was armed
+        pickup: Option<f32>,
+        /// Side of `pickup` the fader was on at its last reading
+        above: Option<bool>,
+    },
+}
+
+impl TakeoverState {
+    fn armed() -> Self {
+        Self::Waiting {
+            pickup: None,
+            above: None,
+        }
+    }
+}
+
+impl SoftTakeover {
+    /// Whether `value` should be applied to the channel's `mappings`, engaging
+    /// the channel once the fader reaches its target's current volume
+    fn engage(
+        &mut self,
+        audio_manager: &dyn AudioManager,
+        channel_id: usize,
+        mappings: &[ChannelMapping],
+        value: f32,
+    ) -> bool {
+        let channel = self
+            .channels
+            .entry(channel_id)
+            .or_insert_with(|| TakeoverChannel {
+                mappings: Vec::new(),
+                state: TakeoverState::Engaged,
+            });
+        if channel.mappings != mappings {
+            channel.mappings = mappings.to_vec();
+            channel.state = TakeoverState::armed();
+        }
+
+        let TakeoverState::Waiting { pickup, above } = &mut channel.state else {
+            return true;
+        };
+        // Grouped channels follow their first mapping; crossfades have no
+        // single volume to pick up at, so they engage right away
+        let current = match *pickup {
+            Some(current) => current,
+            None => match mappings
+                .first()
+                .and_then(|mapping| current_target_volume(audio_manager, mapping).ok())
+            {
+                Some(current) => *pickup.insert(current),
+                None => {
+                    channel.state = TakeoverState::Engaged;
+                    return true;
+                }
+            },
+        };
+
+        let target = mappings[0].target_volume(value);
+        let now_above = target > current;
+        if (target - current).abs() <= SOFT_TAKEOVER_TOLERANCE
+            || above.is_some_and(|was_above| was_above != now_above)
+        {
+            log::debug!("Channel {} picked up at {:.0}%", channel_id, current);
+            channel.state = TakeoverState::Engaged;
+            true
+        } else {
+            *above = Some(now_above);
+            false
+        }
+    }
+
+    /// Ignores the channel's fader until it reaches its target's volume
+    /// again, e.g. while a motor drives it there
+    fn hold(&mut self, channel_id: usize) {
+        if let Some(channel) = self.channels.get_mut(&channel_id) {
+            channel.state = TakeoverState::armed();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VolumeSource {
+    Ui,
+    Fader,
+}
+
+/// Which source last changed each channel's volume, so a UI slider and a
+/// fader don't fight over the same target. The lower-priority source is
+/// ignored until `VOLUME_CONFLICT_WINDOW` after the other's last change.
+struct VolumeArbiter {
+    priority: VolumePriority,
+    last_change: HashMap<usize, (VolumeSource, tokio::time::Instant)>,
+}
+
+impl VolumeArbiter {
+    fn new(priority: VolumePriority) -> Self {
+        Self {
+            priority,
+            last_change: HashMap::new(),
+        }
+    }
+
+    /// Whether `source` may change every one of `channels` now, recording
+    /// the change if so
+    fn claim(
+        &mut self,
+        channels: &[usize],
+        source: VolumeSource,
+        now: tokio::time::Instant,
+    ) -> bool {
+        let preferred = match self.priority {
+            VolumePriority::Physical => VolumeSource::Fader,
+            VolumePriority::Ui => VolumeSource::Ui,
+        };
+        let blocked = source != preferred
+            && channels.iter().any(|channel_id| {
+                self.last_change.get(channel_id).is_some_and(|&(last, at)| {
+                    last == preferred && now.duration_since(at) < VOLUME_CONFLICT_WINDOW
+                })
+            });
+        if blocked {
+            return false;
+        }
+
+        for &channel_id in channels {
+            self.last_change.insert(channel_id, (source, now));
+        }
+        true
+    }
+}
+
+/// Debounced mute buttons, so a bouncing contact toggles the mute once
+#[derive(Default)]
+struct ButtonDebouncer {
+    // Accepted state of each button and when it last changed
+    states: Vec<(bool, Option<tokio::time::Instant>)>,
+}
+
+impl ButtonDebouncer {
+    /// Feeds one frame of button states, returning the channels whose button
+    /// was just pressed. A change within `min_interval` of the button's last
+    /// accepted one is ignored. The first frame, or one with a different
+    /// number of buttons, only records the states.
+    fn presses(
+        &mut self,
+        buttons: &[bool],
+        min_interval: Duration,
+        now: tokio::time::Instant,
+    ) -> Vec<usize> {
+        if self.states.len() != buttons.len() {
+            self.states = buttons.iter().map(|&pressed| (pressed, None)).collect();
+            return Vec::new();
+        }
+
+        let mut pressed = Vec::new();
+        for (index, (state, &down)) in self.states.iter_mut().zip(buttons).enumerate() {
+            let (accepted, changed_at) = *state;
+            if down == accepted || changed_at.is_some_and(|at| now - at < min_interval) {
+                continue;
+            }
+            *state = (down, Some(now));
+            if down {
+                pressed.push(index + 1);
+            }
+        }
+        pressed
+    }
+}
+
+/// Stops every background task and closes the serial port, so nothing is
+/// left blocked on a read and the port is free for the next launch. Safe to
+/// call more than once.
+fn shutdown(app_handle: &AppHandle) {
+    let Some(state) = app_handle.try_state::<AppState>() else {
+        return;
+    };
+    if state.cancellation_token.is_cancelled() {
+        return;
+    }
+
+    log::info!("Shutting down");
+    state.cancellation_token.cancel();
+    state.serial_manager.disconnect_all();
+}
+
+/// What the write path last did to each channel's targets
+#[derive(Default)]
+struct ChannelOutputs {
+    values: HashMap<usize, f32>,
+    // Mappings each channel was last written through; `values` only holds
+    // while these stay the same
+    mappings: HashMap<usize, Vec<ChannelMapping>>,
+    // `mute_at_zero` mappings muted by their fader, keyed by channel and the
+    // mapping's position among that channel's mappings
+    muted_at_zero: HashSet<(usize, usize)>,
+    // Refreshed from the config before each write
+    mute_thresholds: MuteThresholds,
+    unmute_on_fader_move: bool,
+    // App mappings re-matched to a new session, until taken by the caller
+    rematches: Vec<RematchedMapping>,
+    // Volumes sent to each mapping by the last write, ramps by their target
+    written: Vec<(ChannelMapping, f32)>,
+    // Sessions app names are matched against, refreshed before each write
+    sessions: Vec<AudioSession>,
+}
+
+/// Settings the write path reads from the config on every pass
+#[derive(Debug, Clone, Copy, Default)]
+struct WriteSettings {
+    /// Soft takeover, also needed while motorized faders move
+    takeover: bool,
+    mute_thresholds: MuteThresholds,
+    unmute_on_fader_move: bool,
+}
+
+/// Applies each channel to its mapped targets in the active profile, skipping
+/// channels whose value hasn't changed since the last write. Mappings with a
+/// `ramp_ms` glide there instead, taking over any glide already on the channel.
+/// App mappings re-matched by process name are queued in `outputs.rematches`.
+/// With `takeover`, channels that haven't picked up their targets are skipped.
+fn write_changed_volumes(
+    audio_manager: &Arc<dyn AudioManager>,
+    mappings: &[ChannelMapping],
+    percentages: &[f32],
+    outputs: &mut ChannelOutputs,
+    ramps: &mut VolumeRamps,
+    mut solo: Option<&mut SoloState>,
+    mut takeover: Option<&mut SoftTakeover>,
+) {
+    for (index, &value) in percentages.iter().enumerate() {
+        let channel_id = index + 1;
+        let channel_mappings: Vec<ChannelMapping> = mappings
+            .iter()
+            .filter(|m| m.channel_id == channel_id)
+            .cloned()
+            .collect();
+        // A profile switch or edited mapping points the fader at new targets,
+        // which need its value even if it hasn't moved
+        if outputs.mappings.get(&channel_id) != Some(&channel_mappings) {
+            outputs.values.remove(&channel_id);
+            // Targets the old mappings muted at zero would otherwise stay muted
+            if let Some(previous) = outputs.mappings.get(&channel_id) {
+                for &(_, nth) in outputs
+                    .muted_at_zero
+                    .iter()
+                    .filter(|&&(id, _)| id == channel_id)
+                {
+                    if let Some(mapping) = previous.get(nth) {
+                        let _ = set_mapping_mute(audio_manager.as_ref(), mapping, false);
+                    }
+                }
+            }
+            outputs.muted_at_zero.retain(|&(id, _)| id != channel_id);
+            outputs
+                .mappings
+                .insert(channel_id, channel_mappings.clone());
+        }
+        if outputs.values.get(&channel_id) == Some(&value) {
+            continue;
+        }
+        outputs.values.insert(channel_id, value);
+
+        if let Some(takeover) = takeover.as_deref_mut() {
+            if !takeover.engage(audio_manager.as_ref(), channel_id, &channel_mappings, value) {
+                continue;
+            }
+        }
+
+        let mut ramped = Vec::new();
+        for (nth, mapping) in mappings
+            .iter()
+            .filter(|m| m.channel_id == channel_id)
+            .enumerate()
+        {
+            // Ducked channels stay ducked; the new value is applied on `clear_solo`
+            if let Some(solo) = solo.as_deref_mut() {
+                if solo.is_ducked(mapping) {
+                    solo.moved_channels.insert(channel_id, value);
+                    continue;
+                }
+            }
+            if mapping.mute_at_zero {
+                let key = (channel_id, nth);
+                let muted = outputs.muted_at_zero.contains(&key);
+                let position = mapping.fader_position(value);
+                if outputs.mute_thresholds.should_mute(position, muted) {
+                    if outputs.muted_at_zero.insert(key) {
+                        let _ = set_mapping_mute(audio_manager.as_ref(), mapping, true);
+                    }
+                    continue;
+                }
+                // Volume first, so the target doesn't come back at its old level
+                if outputs.muted_at_zero.remove(&key) {
+                    let _ = apply_mapping_to_sessions(
+                        audio_manager.as_ref(),
+                        mapping,
+                        mapping.target_volume(value),
+                        &outputs.sessions,
+                    );
+                    let _ = set_mapping_mute(audio_manager.as_ref(), mapping, false);
+                    continue;
+                }
+            }
+            let volume = mapping.target_volume(value);
+            if mapping.ramp_ms > 0 {
+                ramped.push((mapping.clone(), volume));
+                outputs.written.push((mapping.clone(), volume));
+            } else if let Ok(rematch) =
+                apply_mapping_rematching(audio_manager.as_ref(), mapping, volume, &outputs.sessions)
+            {
+                let mut written = mapping.clone();
+                if let Some(rematch) = rematch {
+                    rematch.apply(std::slice::from_mut(&mut written));
+                    outputs.rematches.push(rematch);
+                }
+                outputs.written.push((written, volume));
+            }
+            // After the volume, so the target doesn't come back at its old level
+            if outputs.unmute_on_fader_move {
+                unmute_if_muted(audio_manager.as_ref(), mapping, &outputs.sessions);
+            }
+        }
+
+        if ramped.is_empty() {
+            ramps.cancel(channel_id);
+        } else {
+            ramps.start(audio_manager.clone(), channel_id, value, ramped);
+        }
+    }
+}
+
+/// Fader percentages of one frame, calibrated, rounded to `step` and oriented
+fn fader_percentages(
+    data: &PotentiometerData,
+    calibration: &[CalibrationData],
+    step: f32,
+    invert_all: bool,
+) -> Vec<f32> {
+    orient_faders(
+        data.to_percentages_calibrated(calibration, step),
+        invert_all,
+    )
+}
+
+/// Turns one device's fader readings into volume writes. Holds no Tauri
+/// state, so the GUI's serial consumer and headless mode share it.
+#[derive(Default)]
+struct FaderWriter {
+    outputs: ChannelOutputs,
+    ramps: VolumeRamps,
+    takeover: SoftTakeover,
+    filters: ChannelFilters,
+}
+
+impl FaderWriter {
+    /// A writer whose glides stop on apps ducked by `solo`
+    fn with_solo(solo: Arc<RwLock<Option<SoloState>>>) -> Self {
+        let mut writer = Self::default();
+        writer.ramps.solo = Some(solo);
+        writer
+    }
+
+    /// Runs `percentages` through each channel's filter. Also returns whether
+    /// the filters have caught up; until then the same readings need feeding
+    /// again, since idle faders stop sending frames.
+    fn filter(
+        &mut self,
+        mappings: &[ChannelMapping],
+        percentages: &[f32],
+        step: f32,
+    ) -> (Vec<f32>, bool) {
+        let filtered = self.filters.apply(mappings, percentages, step);
+        let settled = filter::is_settled(&filtered, percentages, step);
+        (filtered, settled)
+    }
+
+    /// Marks faders losing to a recent UI change as applied, so only their
+    /// next move takes the target back
+    fn yield_to_ui(&mut self, filtered: &[f32], arbiter: &mut VolumeArbiter) {
+        let now = tokio::time::Instant::now();
+        for (index, &value) in filtered.iter().enumerate() {
+            let channel_id = index + 1;
+            let changed = self.outputs.values.get(&channel_id) != Some(&value);
+            if changed && !arbiter.claim(&[channel_id], VolumeSource::Fader, now) {
+                self.outputs.values.insert(channel_id, value);
+            }
+        }
+    }
+
+    /// Writes the channels whose filtered value changed, matching app names
+    /// against `sessions`
+    fn write(
+        &mut self,
+        audio_manager: &Arc<dyn AudioManager>,
+        mappings: &[ChannelMapping],
+        filtered: &[f32],
+        sessions: &[AudioSession],
+        solo: Option<&mut SoloState>,
+        settings: WriteSettings,
+    ) {
+        self.outputs.sessions = sessions.to_vec();
+        self.outputs.mute_thresholds = settings.mute_thresholds;
+        self.outputs.unmute_on_fader_move = settings.unmute_on_fader_move;
+        self.outputs.written.clear();
+        write_changed_volumes(
+            audio_manager,
+            mappings,
+            filtered,
+            &mut self.outputs,
+            &mut self.ramps,
+            solo,
+            settings.takeover.then_some(&mut self.takeover),
+        );
+    }
+
+    /// App mappings re-matched since the last call, for the caller to store
+    fn take_rematches(&mut self) -> Vec<RematchedMapping> {
+        std::mem::take(&mut self.outputs.rematches)
+    }
+
+    /// Mappings the last `write` changed, with the volume each was sent
+    fn written(&self) -> &[(ChannelMapping, f32)] {
+        &self.outputs.written
+    }
+}
+
+/// Control server events for the volumes the faders just wrote. Crossfades
+/// send each side a different level, so they aren't reported.
+fn fader_volume_changes(
+    written: &[(ChannelMapping, f32)],
+    sessions: &[AudioSession],
+) -> Vec<VolumeChange> {
+    written
+        .iter()
+        .flat_map(|(mapping, volume)| {
+            let process_ids = match mapping.target {
+                ChannelTarget::Master => vec![MASTER_VOLUME_PROCESS_ID],
+                ChannelTarget::Crossfade { .. } => Vec::new(),
+                _ => mapped_process_ids(mapping, sessions),
+            };
+            process_ids.into_iter().map(|process_id| VolumeChange {
+                process_id,
+                volume: *volume,
+            })
+        })
+        .collect()
+}
+
+/// Fader positions motorized faders should move to, for channels whose
+/// target volume was changed by something other than the fader. Grouped
+/// channels follow their first mapping. `requested` holds the moves already
+/// sent, so each change is sent once; a channel drops out of it once its
+/// fader matches again.
+fn motor_moves(
+    audio_manager: &dyn AudioManager,
+    mappings: &[ChannelMapping],
+    outputs: &ChannelOutputs,
+    ramps: &VolumeRamps,
+    solo: Option<&SoloState>,
+    requested: &mut HashMap<usize, f32>,
+) -> Vec<(usize, f32)> {
+    let mut moves = Vec::new();
+    for (&channel_id, &fader) in &outputs.values {
+        let Some(mapping) = mappings.iter().find(|m| m.channel_id == channel_id) else {
+            continue;
+        };
+        // These targets lag or leave the fader on purpose, so following them
+        // back would fight the hand on it
+        if ramps.is_running(channel_id)
+            || outputs.muted_at_zero.contains(&(channel_id, 0))
+            || solo.is_some_and(|solo| solo.is_ducked(mapping))
+        {
+            continue;
+        }
+        // Crossfades have no single volume to follow
+        let Ok(volume) = current_target_volume(audio_manager, mapping) else {
+            continue;
+        };
+
+        let position = mapping.fader_for_volume(volume);
+        if (position - fader).abs() <= MOTOR_SYNC_TOLERANCE {
+            requested.remove(&channel_id);
+            continue;
+        }
+        if requested
+            .get(&channel_id)
+            .is_some_and(|&sent| (sent - position).abs() <= MOTOR_SYNC_TOLERANCE)
+        {
+            continue;
+        }
+        requested.insert(channel_id, position);
+        moves.push((channel_id, position));
+    }
+    moves.sort_by_key(|&(channel_id, _)| channel_id);
+    moves
+}
+
+/// Channels mapped to the session with `process_id`, or to master output for
+/// `MASTER_VOLUME_PROCESS_ID`
+fn channels_controlling(
+    mappings: &[ChannelMapping],
+    sessions: &[AudioSession],
+    process_id: u32,
+) -> Vec<usize> {
+    let mut channels: Vec<usize> = mappings
+        .iter()
+        .filter(|mapping| {
+            if process_id == MASTER_VOLUME_PROCESS_ID {
+                mapping.target == ChannelTarget::Master
+            } else {
+                mapped_process_ids(mapping, sessions).contains(&process_id)
+            }
+        })
+        .map(|mapping| mapping.channel_id)
+        .collect();
+    channels.sort_unstable();
+    channels.dedup();
+    channels
+}
+
+/// Records a UI change to the channels controlling `process_id`, or refuses
+/// it while one of their faders has priority
+async fn claim_for_ui(state: &AppState, process_id: u32) -> Result<(), MixerError> {
+    let focused = state.focused_app.read().await.clone();
+    let mappings = focus::resolve(&state.active_mappings.read().await, focused.as_deref());
+    let channels = channels_controlling(
+        &mappings,
+        &state.last_audio_sessions.read().await,
+        process_id,
+    );
+
+    let claimed = state.volume_arbiter.write().await.claim(
+        &channels,
+        VolumeSource::Ui,
+        tokio::time::Instant::now(),
+    );
+    if claimed {
+        Ok(())
+    } else {
+        Err(MixerError::FaderActive(
+            "A fader is moving this target; try again in a moment".to_string(),
+        ))
+    }
+}
+
+/// Process IDs of the sessions an app mapping controls
+fn mapped_process_ids(mapping: &ChannelMapping, sessions: &[AudioSession]) -> Vec<u32> {
+    match mapping.target {
+        ChannelTarget::App(process_id) if mapping.process_names.is_empty() => vec![process_id],
+        ChannelTarget::App(_) | ChannelTarget::AppByName => sessions
+            .iter()
+            .filter(|s| {
+                s.process_id != 0
+                    && mapping
+                        .process_names
+                        .iter()
+                        .any(|name| s.process_name.eq_ignore_ascii_case(name))
+            })
+            .map(|s| s.process_id)
+            .collect(),
+        ChannelTarget::Crossfade { app_a, app_b, .. } => vec![app_a, app_b],
+        ChannelTarget::Master
+        | ChannelTarget::Input
+        | ChannelTarget::Device(_)
+        | ChannelTarget::FollowFocus => Vec::new(),
+    }
+}
+
+/// Current volume of a mapping's target; grouped apps report their first session
+fn current_target_volume(
+    audio_manager: &dyn AudioManager,
+    mapping: &ChannelMapping,
+) -> anyhow::Result<f32> {
+    match mapping.target {
+        ChannelTarget::Master => audio_manager.get_master_volume(),
+        ChannelTarget::Input => audio_manager.get_input_volume(),
+        ChannelTarget::Device(ref device_id) => audio_manager.get_device_volume(device_id),
+        ChannelTarget::App(_) | ChannelTarget::AppByName | ChannelTarget::FollowFocus => {
+            let sessions = audio_manager.get_audio_sessions()?;
+            let process_ids = mapped_process_ids(mapping, &sessions);
+            sessions
+                .iter()
+                .find(|s| process_ids.contains(&s.process_id))
+                .map(|s| s.volume)
+                .ok_or_else(|| {
+                    MixerError::SessionNotFound(format!(
+                        "No audio session for channel {}",
+                        mapping.channel_id
+                    ))
+                    .into()
+                })
+        }
+        ChannelTarget::Crossfade { .. } => Err(MixerError::InvalidArgument(format!(
+            "Channel {} crossfades two apps and has no single volume",
+            mapping.channel_id
+        ))
+        .into()),
+    }
+}
+
+/// Moves a mapping's target volume by `delta` percentage points, clamped to
+/// 0-100, returning the new volume. Works on the volume itself, so taper
+/// doesn't apply.
+fn adjust_mapping(
+    audio_manager: &dyn AudioManager,
+    mapping: &ChannelMapping,
+    delta: f32,
+) -> anyhow::Result<f32> {
+    let volume = (current_target_volume(audio_manager, mapping)? + delta).clamp(0.0, 100.0);
+    apply_mapping(audio_manager, mapping, volume)?;
+    Ok(volume)
+}
+
+/// Moves a mapping's target by `detents` encoder steps, returning the new volume.
+/// Inverting reverses the turning direction.
+fn nudge_mapping(
+    audio_manager: &dyn AudioManager,
+    mapping: &ChannelMapping,
+    detents: i32,
+) -> anyhow::Result<f32> {
+    let direction = if mapping.inverted { -1.0 } else { 1.0 };
+    adjust_mapping(
+        audio_manager,
+        mapping,
+        detents as f32 * ENCODER_STEP_PERCENT * direction,
+    )
+}
+
+/// Puts ducked sessions back at their pre-solo volume, or at their fader's
+/// new position if it moved while soloed
+fn restore_solo(audio_manager: &dyn AudioManager, mappings: &[ChannelMapping], solo: SoloState) {
+    for (process_id, volume) in solo.saved_volumes {
+        if let Err(e) = audio_manager.set_app_volume(process_id, volume) {
+            log::warn!("Failed to restore volume for process {}: {}", process_id, e);
+        }
+    }
+
+    for (channel_id, value) in solo.moved_channels {
+        for mapping in mappings.iter().filter(|m| m.channel_id == channel_id) {
+            let _ = apply_mapping(audio_manager, mapping, mapping.target_volume(value));
+        }
+    }
+}
+
+/// Whether a mapping's target is muted; a group counts as muted when any of
+/// its sessions is, and a crossfade when either side is
+fn mapping_muted(
+    audio_manager: &dyn AudioManager,
+    mapping: &ChannelMapping,
+) -> anyhow::Result<bool> {
+    match mapping.target {
+        ChannelTarget::Master => audio_manager.get_master_mute(),
+        ChannelTarget::Input => audio_manager.get_input_mute(),
+        ChannelTarget::Device(ref device_id) => audio_manager.get_device_mute(device_id),
+        ChannelTarget::App(_) | ChannelTarget::AppByName => {
+            let sessions = audio_manager.get_audio_sessions()?;
+            let process_ids = mapped_process_ids(mapping, &sessions);
+            Ok(sessions
+                .iter()
+                .any(|s| process_ids.contains(&s.process_id) && s.is_muted))
+        }
+        ChannelTarget::FollowFocus => Err(MixerError::SessionNotFound(format!(
+            "No focused app for channel {}",
+            mapping.channel_id
+        ))
+        .into()),
+        ChannelTarget::Crossfade { app_a, app_b, .. } => {
+            let sessions = audio_manager.get_audio_sessions()?;
+            Ok(sessions
+                .iter()
+                .any(|s| (s.process_id == app_a || s.process_id == app_b) && s.is_muted))
+        }
+    }
+}
+
+/// Flips the mute state of a mapping's target, returning the new state. Both
+/// sides of a crossfade mute and unmute together.
+fn toggle_mapping_mute(
+    audio_manager: &dyn AudioManager,
+    mapping: &ChannelMapping,
+) -> anyhow::Result<bool> {
+    let muted = !mapping_muted(audio_manager, mapping)?;
+    set_mapping_mute(audio_manager, mapping, muted)?;
+    Ok(muted)
+}
+
+/// Unmutes a target muted elsewhere, e.g. in the Windows mixer, so moving its
+/// fader is heard. Apps are looked up in `sessions`.
+fn unmute_if_muted(
+    audio_manager: &dyn AudioManager,
+    mapping: &ChannelMapping,
+    sessions: &[AudioSession],
+) {
+    let muted = match mapping.target {
+        ChannelTarget::App(_) | ChannelTarget::AppByName | ChannelTarget::Crossfade { .. } => {
+            let process_ids = mapped_process_ids(mapping, sessions);
+            Ok(sessions
+                .iter()
+                .any(|s| process_ids.contains(&s.process_id) && s.is_muted))
+        }
+        _ => mapping_muted(audio_manager, mapping),
+    };
+    match muted {
+        Ok(true) => {
+            if let Err(e) = set_mapping_mute(audio_manager, mapping, false) {
+                log::warn!("Failed to unmute channel {}: {}", mapping.channel_id, e);
+            }
+        }
+        Ok(false) => {}
+        Err(e) => log::debug!(
+            "Failed to read mute state of channel {}: {}",
+            mapping.channel_id,
+            e
+        ),
+    }
+}
+
+/// Mutes or unmutes a mapping's target; grouped apps follow every session of
+/// their executables and crossfades both sides
+fn set_mapping_mute(
+    audio_manager: &dyn AudioManager,
+    mapping: &ChannelMapping,
+    muted: bool,
+) -> anyhow::Result<()> {
+    match mapping.target {
+        ChannelTarget::Master => audio_manager.set_master_mute(muted),
+        ChannelTarget::Input => audio_manager.set_input_mute(muted),
+        ChannelTarget::Device(ref device_id) => audio_manager.set_device_mute(device_id, muted),
+        ChannelTarget::App(process_id) => {
+            if mapping.process_names.is_empty() {
+                audio_manager.set_app_mute(process_id, muted)
+            } else {
+                for name in &mapping.process_names {
+                    audio_manager.set_mute_by_process_name(name, muted)?;
+                }
+                Ok(())
+            }
+        }
+        ChannelTarget::AppByName => {
+            for name in &mapping.process_names {
+                audio_manager.set_mute_by_process_name(name, muted)?;
+            }
+            Ok(())
+        }
+        // Only reaches an app once `focus::resolve` has pointed it at one
+        ChannelTarget::FollowFocus => Ok(()),
+        ChannelTarget::Crossfade { app_a, app_b, .. } => {
+            audio_manager.set_app_mute(app_a, muted)?;
+            audio_manager.set_app_mute(app_b, muted)
+        }
+    }
+}
+
+/// Mutes everything, unless already muted so the original states aren't lost
+async fn apply_mute_all(
+    audio_manager: &dyn AudioManager,
+    mute_all_saved: &RwLock<Option<SavedMutes>>,
+) -> anyhow::Result<()> {
+    let mut saved = mute_all_saved.write().await;
+    if saved.is_none() {
+        *saved = Some(audio_manager.mute_all()?);
+    }
+    Ok(())
+}
+
+async fn restore_mute_all(
+    audio_manager: &dyn AudioManager,
+    mute_all_saved: &RwLock<Option<SavedMutes>>,
+) -> anyhow::Result<()> {
+    if let Some(saved) = mute_all_saved.write().await.take() {
+        audio_manager.unmute_all(&saved)?;
+    }
+    Ok(())
+}
+
+async fn press_mute_button(
+    audio_manager: &dyn AudioManager,
+    mapping: &ChannelMapping,
+    mute_all_saved: &RwLock<Option<SavedMutes>>,
+) -> anyhow::Result<()> {
+    match mapping.button_action {
+        ButtonAction::ToggleMute => toggle_mapping_mute(audio_manager, mapping).map(|_| ()),
+        ButtonAction::ToggleMuteAll => {
+            if mute_all_saved.read().await.is_some() {
+                restore_mute_all(audio_manager, mute_all_saved).await
+            } else {
+                apply_mute_all(audio_manager, mute_all_saved).await
+            }
+        }
+    }
+}
+
+/// Current sessions, merged per executable unless raw mode is selected
+fn session_list(
+    audio_manager: &dyn AudioManager,
+    merge: bool,
+) -> anyhow::Result<Vec<AudioSession>> {
+    let sessions = audio_manager.get_audio_sessions()?;
+    Ok(if merge {
+        audio::merge_sessions_by_name(sessions)
+    } else {
+        sessions
+    })
+}
+
+/// With merging on, a single-app mapping was picked from a merged entry and
+/// stands for every process of its executable, so it is written by name
+fn expand_merged_apps(mut mappings: Vec<ChannelMapping>, merge: bool) -> Vec<ChannelMapping> {
+    if !merge {
+        return mappings;
+    }
+    for mapping in &mut mappings {
+        if let (ChannelTarget::App(_), Some(name)) = (&mapping.target, &mapping.process_name) {
+            if mapping.process_names.is_empty() {
+                mapping.process_names = vec![name.clone()];
+            }
+        }
+    }
+    mappings
+}
+
+/// Re-enumerates sessions into the poller's cache and sends them to the
+/// frontend, so a change of shape shows up without waiting for a poll
+async fn refresh_session_cache(
+    state: &AppState,
+    app_handle: &AppHandle,
+) -> Result<Vec<AudioSession>, MixerError> {
+    let merge = *state.merge_sessions.read().await;
+    let sessions = session_list(state.audio_manager.as_ref(), merge).map_err(MixerError::from)?;
+
+    *state.last_audio_sessions.write().await = sessions.clone();
+    if let Err(e) = app_handle.emit("audio-sessions-updated", &sessions) {
+        log::error!("Failed to emit audio-sessions-updated event: {}", e);
+    }
+    Ok(sessions)
+}
+
+/// Whether a physical fader drives this session, so its volume is left alone
+fn is_fader_mapped(session: &AudioSession, mappings: &[ChannelMapping]) -> bool {
+    mappings.iter().any(|mapping| match mapping.target {
+        ChannelTarget::App(process_id) => {
+            process_id == session.process_id
+                || mapping
+                    .process_names
+                    .iter()
+                    .any(|name| session.process_name.eq_ignore_ascii_case(name))
+        }
+        ChannelTarget::AppByName => mapping
+            .process_names
+            .iter()
+            .any(|name| session.process_name.eq_ignore_ascii_case(name)),
+        ChannelTarget::Crossfade { app_a, app_b, .. } => {
+            session.process_id == app_a || session.process_id == app_b
+        }
+        ChannelTarget::Master
+        | ChannelTarget::Input
+        | ChannelTarget::Device(_)
+        | ChannelTarget::FollowFocus => false,
+    })
+}
+
+/// Re-applies the saved volume of every session not seen before, then records
+/// the volume each session is at. Sessions a fader drives are left to it.
+/// Returns whether the saved volumes changed and should be written back to
+/// the config.
+fn restore_volumes(
+    audio_manager: &dyn AudioManager,
+    sessions: &[AudioSession],
+    mappings: &[ChannelMapping],
+    saved_volumes: &mut HashMap<String, f32>,
+    seen: &mut HashSet<u32>,
+) -> bool {
+    // Forgotten once gone, so an app reusing the PID is restored too
+    seen.retain(|&process_id| sessions.iter().any(|s| s.process_id == process_id));
+    // Several sessions of one executable would otherwise overwrite each
+    // other's volume on every poll; the first one listed is kept
+    let mut recorded = HashSet::new();
+    let mut changed = false;
+
+    for session in sessions.iter().filter(|s| s.process_id != 0) {
+        let first_sighting = seen.insert(session.process_id);
+        if is_fader_mapped(session, mappings) {
+            continue;
+        }
+        let key = session.process_name.to_lowercase();
+        let mut volume = session.volume;
+
+        if first_sighting {
+            // The OS may have reset it. Recorded from the OS, so only a
+            // hand-edited config holds anything invalid.
+            let saved = saved_volumes
+                .get(&key)
+                .and_then(|&volume| Volume::from_percent(volume).ok())
+                .filter(|saved| saved.as_percent() != session.volume);
+            if let Some(saved) = saved {
+                // A merged entry stands for every process of its executable
+                let restored = if session.session_count > 1 {
+                    audio_manager
+                        .set_volume_by_process_name(&session.process_name, saved)
+                        .map(|_| ())
+                } else {
+                    audio_manager.set_app_volume(session.process_id, saved)
+                };
+                match restored {
+                    Ok(()) => volume = saved.as_percent(),
+                    Err(e) => log::warn!(
+                        "Failed to restore volume for {}: {}",
+                        session.process_name,
+                        e
+                    ),
+                }
+            }
+        }
+
+        if recorded.insert(key.clone()) && saved_volumes.get(&key) != Some(&volume) {
+            saved_volumes.insert(key, volume);
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+/// Mapped sessions that started or stopped between two polls, each paired
+/// with the mapping controlling it
+struct MappedAppChanges<'a> {
+    started: Vec<(&'a ChannelMapping, MappedApp)>,
+    stopped: Vec<(&'a ChannelMapping, MappedApp)>,
+}
+
+fn mapped_app_changes<'a>(
+    previous: &[AudioSession],
+    delta: &SessionDelta,
+    mappings: &'a [ChannelMapping],
+) -> MappedAppChanges<'a> {
+    let mapped = |session: &AudioSession| -> Vec<(&'a ChannelMapping, MappedApp)> {
+        mappings
+            .iter()
+            .filter(|mapping| is_fader_mapped(session, std::slice::from_ref(*mapping)))
+            .map(|mapping| {
+                let app = MappedApp {
+                    channel_id: mapping.channel_id,
+                    device: mapping.device.clone(),
+                    process_id: session.process_id,
+                    process_name: session.process_name.clone(),
+                };
+                (mapping, app)
+            })
+            .collect()
+    };
+
+    MappedAppChanges {
+        started: delta.added.iter().flat_map(&mapped).collect(),
+        stopped: previous
+            .iter()
+            .filter(|session| delta.removed.contains(&session.process_id))
+            .flat_map(&mapped)
+            .collect(),
+    }
+}
+
+/// Last reading of `mapping`'s fader from a device it listens to
+fn last_fader_value(values: &HashMap<String, Vec<f32>>, mapping: &ChannelMapping) -> Option<f32> {
+    let index = mapping.channel_id.checked_sub(1)?;
+    let mut ports: Vec<&String> = values
+        .keys()
+        .filter(|port| mapping.applies_to_device(port))
+        .collect();
+    ports.sort();
+    ports
+        .into_iter()
+        .find_map(|port| values[port].get(index).copied())
+}
+
+/// Puts a mapped app that just started at its fader's level, since it comes
+/// up at whatever volume the OS remembered
+fn reapply_fader(
+    audio_manager: &dyn AudioManager,
+    mapping: &ChannelMapping,
+    value: f32,
+    mute_thresholds: MuteThresholds,
+) -> anyhow::Result<()> {
+    if mapping.mute_at_zero && mute_thresholds.should_mute(mapping.fader_position(value), false) {
+        return set_mapping_mute(audio_manager, mapping, true);
+    }
+    apply_mapping(audio_manager, mapping, mapping.target_volume(value))
+}
+
+/// Records how many channels the device on `port` has, telling the frontend
+/// when that changes so it can rebuild its faders
+async fn update_channel_count(
+    app_handle: &AppHandle,
+    channel_counts: &RwLock<HashMap<String, usize>>,
+    port: &str,
+    count: usize,
+) {
+    let count = count.clamp(1, MAX_CHANNEL_COUNT);
+    // Checked under the read lock first since this runs for every frame
+    if channel_counts.read().await.get(port) == Some(&count) {
+        return;
+    }
+    channel_counts.write().await.insert(port.to_string(), count);
+
+    log::info!("Device on {} has {} channels", port, count);
+    emit_channels_updated(app_handle, channel_counts).await;
+}
+
+/// Drops the channels of a device that went away
+async fn forget_device_channels(
+    app_handle: &AppHandle,
+    channel_counts: &RwLock<HashMap<String, usize>>,
+    channel_values: &RwLock<HashMap<String, Vec<f32>>>,
+    port: &str,
+) {
+    channel_values.write().await.remove(port);
+    if channel_counts.write().await.remove(port).is_some() {
+        emit_channels_updated(app_handle, channel_counts).await;
+    }
+}
+
+/// Sends the number of channels `get_mixer_channels` now lists
+async fn emit_channels_updated(
+    app_handle: &AppHandle,
+    channel_counts: &RwLock<HashMap<String, usize>>,
+) {
+    let counts = channel_counts.read().await;
+    let total = if counts.is_empty() {
+        DEFAULT_CHANNEL_COUNT
+    } else {
+        counts.values().sum()
+    };
+    if let Err(e) = app_handle.emit("channels-updated", total) {
+        log::error!("Failed to emit channels-updated event: {}", e);
+    }
+}
+
+/// Mappings that listen to the device on `port`
+fn device_mappings(mappings: &[ChannelMapping], port: &str) -> Vec<ChannelMapping> {
+    mappings
+        .iter()
+        .filter(|mapping| mapping.applies_to_device(port))
+        .cloned()
+        .collect()
+}
+
+fn parse_accelerator(accelerator: &str) -> Result<Shortcut, MixerError> {
+    accelerator.parse().map_err(|e| {
+        MixerError::InvalidArgument(format!("Invalid hotkey {:?}: {}", accelerator, e))
+    })
+}
+
+/// Whether `binding` is for the same key combination as `shortcut`, however
+/// its accelerator was spelled
+fn is_bound_to(binding: &HotkeyBinding, shortcut: &Shortcut) -> bool {
+    parse_accelerator(&binding.accelerator).is_ok_and(|bound| bound == *shortcut)
+}
+
+/// Registers `shortcut` system-wide; fails if another app already owns the
+/// combination
+fn register_hotkey(
+    app_handle: &AppHandle,
+    shortcut: Shortcut,
+    accelerator: &str,
+) -> Result<(), MixerError> {
+    app_handle
+        .global_shortcut()
+        .register(shortcut)
+        .map_err(|e| {
+            MixerError::InvalidArgument(format!("Couldn't register {}: {}", accelerator, e))
+        })
+}
+
+/// Registers every binding, so a shortcut taken by another app only loses
+/// its own binding
+fn register_hotkeys(app_handle: &AppHandle, bindings: &[HotkeyBinding]) {
+    for binding in bindings {
+        let registered = parse_accelerator(&binding.accelerator)
+            .and_then(|shortcut| register_hotkey(app_handle, shortcut, &binding.accelerator));
+        if let Err(e) = registered {
+            log::warn!("Failed to register hotkey {}: {}", binding.accelerator, e);
+        }
+    }
+}
+
+async fn run_hotkey_action(state: &AppState, action: &HotkeyAction) -> anyhow::Result<()> {
+    let audio_manager = state.audio_manager.as_ref();
+    match *action {
+        HotkeyAction::ToggleMasterMute => {
+            let muted = !audio_manager.get_master_mute()?;
+            audio_manager.set_master_mute(muted)
+        }
+        HotkeyAction::ToggleChannelMute { channel_id } => {
+            let mappings = state.active_mappings.read().await;
+            for mapping in mappings.iter().filter(|m| m.channel_id == channel_id) {
+                toggle_mapping_mute(audio_manager, mapping)?;
+            }
+            Ok(())
+        }
+        HotkeyAction::AdjustChannelVolume { channel_id, delta } => {
+            let mappings = state.active_mappings.read().await;
+            for mapping in mappings.iter().filter(|m| m.channel_id == channel_id) {
+                adjust_mapping(audio_manager, mapping, delta)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Runs the action bound to a global shortcut when it's pressed
+fn handle_hotkey(app_handle: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+
+    let app_handle = app_handle.clone();
+    let shortcut = *shortcut;
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        let action = state
+            .hotkeys
+            .read()
+            .await
+            .iter()
+            .find(|binding| is_bound_to(binding, &shortcut))
+            .map(|binding| binding.action.clone());
+
+        if let Some(action) = action {
+            if let Err(e) = run_hotkey_action(&state, &action).await {
+                log::warn!("Failed to run hotkey action {:?}: {}", action, e);
+            }
+        }
+    });
+}
+
+fn remember_port(app_handle: &AppHandle, status: &ConnectionStatus) {
+    if let Some(port) = &status.port {
+        if let Err(e) = config::save_last_port(app_handle, port) {
+            log::error!("Failed to save last used port: {}", e);
+        }
+    }
+}
+
+fn profile_list(config: &AppConfig) -> ProfileList {
+    ProfileList {
+        active_profile: config.active_profile.clone(),
+        profiles: config.profiles.clone(),
+    }
+}
+
+/// Connects the last used port, then scans until no more fader banks turn up.
+/// Returns whether anything connected; with `report_failure` a scan that
+/// found nothing or failed outright is sent as a `connection-status` event.
+async fn connect_all(
+    app_handle: &AppHandle,
+    last_port: Option<String>,
+    report_failure: bool,
+) -> bool {
+    let mut port = last_port;
+    let mut connected_any = false;
+    loop {
+        let requested = port.take();
+        let tried_last_port = requested.is_some();
+        let status = match open_serial(app_handle, requested.clone()).await {
+            Ok(status) => status,
+            // Reported like a device that didn't answer, so the UI can say why
+            Err(e) => {
+                log::error!("Failed to auto-connect: {}", e);
+                ConnectionStatus {
+                    connected: false,
+                    port: requested,
+                    error: Some(e.to_string()),
+                    error_code: Some(e.code().to_string()),
+                    ..Default::default()
+                }
+            }
+        };
+
+        if status.connected {
+            connected_any = true;
+        } else if tried_last_port {
+            // The scan may still find the device on another port
+            continue;
+        } else if connected_any || !report_failure {
+            // Just the end of the scan, not worth reporting
+            return connected_any;
+        }
+
+        if let Err(e) = app_handle.emit("connection-status", &status) {
+            log::error!("Failed to emit connection-status event: {}", e);
+        }
+        if !status.connected {
+            return connected_any;
+        }
+    }
+}
+
+/// Keeps trying to reopen `port` with backoff until it connects, something
+/// else reconnects it, or the app shuts down
+async fn reconnect_with_backoff(app_handle: AppHandle, port: String) {
+    let state = app_handle.state::<AppState>();
+    let serial_manager = state.serial_manager.clone();
+    let cancellation_token = state.cancellation_token.clone();
+    let mut backoff = Backoff::new(*state.reconnect_backoff.read().await);
+
+    loop {
+        let delay = backoff.next_delay();
+        log::debug!("Retrying {} in {}ms", port, delay.as_millis());
+        tokio::select! {
+            _ = cancellation_token.cancelled() => return,
+            _ = tokio::time::sleep(delay) => {}
+        }
+        if serial_manager.is_connected(&port) {
+            return;
+        }
+
+        match open_serial(&app_handle, Some(port.clone())).await {
+            Ok(status) if status.connected => {
+                if let Err(e) = app_handle.emit("connection-status", &status) {
+                    log::error!("Failed to emit connection-status event: {}", e);
+                }
+                return;
+            }
+            Ok(_) => {}
+            Err(e) => log::debug!("Failed to reconnect {}: {}", port, e),
+        }
+    }
+}
+
+#[tauri::command]
+async fn list_serial_ports() -> Result<Vec<SerialPortInfo>, MixerError> {
+    SerialManager::list_ports().map_err(MixerError::from)
+}
+
+/// Opens `port` next to any devices already connected. Without a port it
+/// opens the first likely mixer that isn't connected yet, so once every mixer
+/// is open it reports `SerialNotFound` rather than reconnecting one; pass the
+/// port to reconnect it.
+#[tauri::command]
+async fn connect_serial(
+    port: Option<String>,
+    app_handle: AppHandle,
+) -> Result<ConnectionStatus, MixerError> {
+    open_serial(&app_handle, port).await
+}
+
+/// Opens the port and starts the reader plus the task consuming its events;
+/// shared by `connect_serial`, auto-connect and the stall watchdog's reconnect.
+/// Devices already connected on other ports stay open.
+async fn open_serial(
+    app_handle: &AppHandle,
+    port: Option<String>,
+) -> Result<ConnectionStatus, MixerError> {
+    let state = app_handle.state::<AppState>();
+    let status = state
+        .serial_manager
+        .connect(port)
+        .map_err(MixerError::from)?;
+
+    if let (true, Some(port)) = (status.connected, status.port.clone()) {
+        remember_port(app_handle, &status);
+        if let Some(device) = &status.device {
+            update_channel_count(app_handle, &state.channel_counts, &port, device.channels).await;
+        }
+
+        // Start reading data and emitting events
+        let (tx, mut rx) = mpsc::channel(100);
+
+        let serial_manager = state.serial_manager.clone();
+        serial_manager
+            .start_reading(&port, tx)
+            .await
+            .map_err(MixerError::from)?;
+
+        // Spawn task to emit pot data events
+        let app_handle_clone = app_handle.clone();
+        let audio_manager = state.audio_manager.clone();
+        let active_mappings = state.active_mappings.clone();
+        let calibration = state.calibration.clone();
+        let calibrations_in_progress = state.calibrations_in_progress.clone();
+        let channel_values = state.channel_values.clone();
+        let channel_counts = state.channel_counts.clone();
+        let mute_all_saved = state.mute_all_saved.clone();
+        let solo = state.solo.clone();
+        let focused_app = state.focused_app.clone();
+        let invert_all_channels = state.invert_all_channels.clone();
+        let soft_takeover = state.soft_takeover.clone();
+        let motorized_faders = state.motorized_faders.clone();
+        let volume_arbiter = state.volume_arbiter.clone();
+        let volume_step = state.volume_step.clone();
+        let mute_thresholds = state.mute_thresholds.clone();
+        let unmute_on_fader_move = state.unmute_on_fader_move.clone();
+        let merge_sessions = state.merge_sessions.clone();
+        let last_audio_sessions = state.last_audio_sessions.clone();
+        let control_events = state.control_events.clone();
+        let cancellation_token = state.cancellation_token.clone();
+
+        tokio::spawn(async move {
+            // Volume writes are coalesced to the latest frame and flushed at a fixed rate
+            let mut pending: Option<Vec<f32>> = None;
+            let mut writer = FaderWriter::with_solo(solo.clone());
+            let mut buttons = ButtonDebouncer::default();
+            // Channels reporting encoder deltas, whose pot readings are ignored
+            let mut encoder_channels: HashSet<usize> = HashSet::new();
+            let mut write_timer = tokio::time::interval(VOLUME_WRITE_INTERVAL);
+            write_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            // Fader positions sent to the motors and not yet reached
+            let mut motor_requested: HashMap<usize, f32> = HashMap::new();
+            let mut motor_timer = tokio::time::interval(MOTOR_SYNC_INTERVAL);
+            motor_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+            loop {
+                let event = tokio::select! {
+                    _ = cancellation_token.cancelled() => break,
+                    event = rx.recv() => match event {
+                        Some(event) => event,
+                        None => break,
+                    },
+                    _ = write_timer.tick() => {
+                        if let Some(percentages) = pending.take() {
+                            let focused = focused_app.read().await.clone();
+                            let mappings: Vec<ChannelMapping> =
+                                focus::resolve(&device_mappings(&active_mappings.read().await, &port), focused.as_deref())
+                                    .into_iter()
+                                    .filter(|m| !encoder_channels.contains(&m.channel_id))
+                                    .collect();
+                            let mappings = expand_merged_apps(mappings, *merge_sessions.read().await);
+                            let (filtered, settled) =
+                                writer.filter(&mappings, &percentages, *volume_step.read().await);
+                            if !settled {
+                                pending = Some(percentages);
+                            }
+                            writer.yield_to_ui(&filtered, &mut *volume_arbiter.write().await);
+                            let settings = WriteSettings {
+                                // Motor moves rely on takeover to ignore the fader on its way
+                                takeover: *soft_takeover.read().await || *motorized_faders.read().await,
+                                mute_thresholds: *mute_thresholds.read().await,
+                                unmute_on_fader_move: *unmute_on_fader_move.read().await,
+                            };
+                            let sessions = last_audio_sessions.read().await;
+                            writer.write(&audio_manager, &mappings, &filtered, &sessions, solo.write().await.as_mut(), settings);
+                            let changes = fader_volume_changes(writer.written(), &sessions);
+                            drop(sessions);
+                            for change in changes {
+                                // Only fails when no client is listening
+                                let _ = control_events.send(ControlEvent::VolumeChanged(change));
+                            }
+                            for rematch in writer.take_rematches() {
+                                record_rematch(&app_handle_clone, &active_mappings, &rematch).await;
+                            }
+                        }
+                        continue;
+                    }
+                    _ = motor_timer.tick() => {
+                        if !*motorized_faders.read().await {
+                            continue;
+                        }
+                        let focused = focused_app.read().await.clone();
+                        let mappings: Vec<ChannelMapping> =
+                            focus::resolve(&device_mappings(&active_mappings.read().await, &port), focused.as_deref())
+                                .into_iter()
+                                .filter(|m| !encoder_channels.contains(&m.channel_id))
+                                .collect();
+                        let mappings = expand_merged_apps(mappings, *merge_sessions.read().await);
+                        let moves = motor_moves(
+                            audio_manager.as_ref(),
+                            &mappings,
+                            &writer.outputs,
+                            &writer.ramps,
+                            solo.read().await.as_ref(),
+                            &mut motor_requested,
+                        );
+                        let calibration = calibration.read().await;
+                        let invert_all = *invert_all_channels.read().await;
+                        for (channel_id, position) in moves {
+                            let position = if invert_all { 100.0 - position } else { position };
+                            let raw = calibration
+                                .for_port(Some(&port))
+                                .get(channel_id - 1)
+                                .copied()
+                                .unwrap_or_default()
+                                .to_raw(position);
+                            let command = format!("SET {} {}", channel_id, raw);
+                            match serial_manager.send_command_to(&port, &command) {
+                                // Readings on the way there would drag the volume along
+                                Ok(()) => writer.takeover.hold(channel_id),
+                                Err(e) => log::warn!(
+                                    "Failed to move fader {} on {}: {}",
+                                    channel_id,
+                                    port,
+                                    e
+                                ),
+                            }
+                        }
+                        continue;
+                    }
+                };
+
+                let data = match event {
+                    SerialEvent::Data(data) => data,
+                    SerialEvent::ParseError(error) => {
+                        log::warn!(
+                            "Failed to parse serial line {:?} from {}: {}",
+                            error.line,
+                            port,
+                            error.error
+                        );
+                        let event = FromDevice {
+                            port: port.clone(),
+                            payload: error,
+                        };
+                        if let Err(e) = app_handle_clone.emit("serial-parse-error", &event) {
+                            log::error!("Failed to emit serial-parse-error event: {}", e);
+                        }
+                        continue;
+                    }
+                    SerialEvent::Disconnected(status) => {
+                        if let Err(e) = app_handle_clone.emit("connection-status", &status) {
+                            log::error!("Failed to emit connection-status event: {}", e);
+                        }
+                        continue;
+                    }
+                    SerialEvent::Raw(line) => {
+                        let event = FromDevice {
+                            port: port.clone(),
+                            payload: RawSerialLine { line },
+                        };
+                        if let Err(e) = app_handle_clone.emit("serial-raw", &event) {
+                            log::error!("Failed to emit serial-raw event: {}", e);
+                        }
+                        continue;
+                    }
+                    SerialEvent::Diagnostics(diagnostics) => {
+                        let event = FromDevice {
+                            port: port.clone(),
+                            payload: diagnostics,
+                        };
+                        if let Err(e) = app_handle_clone.emit("device-diagnostics", &event) {
+                            log::error!("Failed to emit device-diagnostics event: {}", e);
+                        }
+                        continue;
+                    }
+                };
+
+                // Emit raw pot data
+                let event = FromDevice {
+                    port: port.clone(),
+                    payload: &data,
+                };
+                if let Err(e) = app_handle_clone.emit("pot-data", &event) {
+                    log::error!("Failed to emit pot-data event: {}", e);
+                }
+
+                // Frames show what the device really sends, e.g. firmware without `ID?`
+                update_channel_count(
+                    &app_handle_clone,
+                    &channel_counts,
+                    &port,
+                    data.raw_values().len(),
+                )
+                .await;
+
+                // Record the range of any channel being calibrated
+                {
+                    let raw_values = data.raw_values();
+                    let mut in_progress = calibrations_in_progress.write().await;
+                    for ((device, channel_id), observed) in in_progress.iter_mut() {
+                        if device.as_ref().is_some_and(|device| *device != port) {
+                            continue;
+                        }
+                        if let Some(&raw) = raw_values.get(channel_id.wrapping_sub(1)) {
+                            observed.observe(raw);
+                        }
+                    }
+                }
+
+                // A button press triggers the button action of every mapping on its channel
+                let debounce =
+                    Duration::from_millis(mute_thresholds.read().await.button_debounce_ms);
+                let pressed =
+                    buttons.presses(&data.buttons(), debounce, tokio::time::Instant::now());
+                if !pressed.is_empty() {
+                    let focused = focused_app.read().await.clone();
+                    let mappings = focus::resolve(
+                        &device_mappings(&active_mappings.read().await, &port),
+                        focused.as_deref(),
+                    );
+                    for channel_id in pressed {
+                        for mapping in mappings.iter().filter(|m| m.channel_id == channel_id) {
+                            if let Err(e) =
+                                press_mute_button(audio_manager.as_ref(), mapping, &mute_all_saved)
+                                    .await
+                            {
+                                log::warn!(
+                                    "Failed to toggle mute for channel {}: {}",
+                                    channel_id,
+                                    e
+                                );
+                            }
+                        }
+                    }
+                }
+
+                let mut percentages = fader_percentages(
+                    &data,
+                    calibration.read().await.for_port(Some(&port)),
+                    *volume_step.read().await,
+                    *invert_all_channels.read().await,
+                );
+
+                // Encoders nudge their targets right away, since every delta counts
+                {
+                    let previous_values = channel_values
+                        .read()
+                        .await
+                        .get(&port)
+                        .cloned()
+                        .unwrap_or_default();
+                    let focused = focused_app.read().await.clone();
+                    let mappings = focus::resolve(
+                        &device_mappings(&active_mappings.read().await, &port),
+                        focused.as_deref(),
+                    );
+                    let solo = solo.read().await;
+                    for (index, delta) in data.encoder_deltas().into_iter().enumerate() {
+                        let Some(delta) = delta else {
+                            continue;
+                        };
+                        let channel_id = index + 1;
+                        encoder_channels.insert(channel_id);
+
+                        // Encoder channels report their target's volume instead of the pot
+                        let mut value = previous_values.get(index).copied().unwrap_or(0.0);
+                        if delta != 0 {
+                            for mapping in mappings.iter().filter(|m| m.channel_id == channel_id) {
+                                // Ducked targets stay put until the solo ends
+                                if solo.as_ref().is_some_and(|s| s.is_ducked(mapping)) {
+                                    continue;
+                                }
+                                match nudge_mapping(audio_manager.as_ref(), mapping, delta) {
+                                    Ok(volume) => value = volume,
+                                    Err(e) => log::warn!(
+                                        "Failed to apply encoder on channel {}: {}",
+                                        channel_id,
+                                        e
+                                    ),
+                                }
+                            }
+                        }
+                        if let Some(slot) = percentages.get_mut(index) {
+                            *slot = value;
+                        }
+                    }
+                }
+
+                // Queue the channel values; the next timer tick applies them
+                channel_values
+                    .write()
+                    .await
+                    .insert(port.clone(), percentages.clone());
+                pending = Some(percentages);
+            }
+
+            // A reconnect of the same port has a new consumer using these entries
+            if !serial_manager.is_connected(&port) {
+                forget_device_channels(&app_handle_clone, &channel_counts, &channel_values, &port)
+                    .await;
+            }
+        });
+    }
+
+    Ok(status)
+}
+
+/// Closes `port`, or every connected device for `None`
+#[tauri::command]
+async fn disconnect_serial(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    port: Option<String>,
+) -> Result<(), MixerError> {
+    let ports = match port {
+        Some(port) => vec![port],
+        None => state.serial_manager.connected_ports(),
+    };
+
+    for port in ports {
+        state.serial_manager.disconnect(&port);
+
+        let status = state.serial_manager.get_status(Some(&port));
+        if let Err(e) = app_handle.emit("connection-status", &status) {
+            log::error!("Failed to emit connection-status event: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_update_rate(state: State<'_, AppState>, interval_ms: u32) -> Result<(), MixerError> {
+    state
+        .serial_manager
+        .send_command(&format!("RATE {}", interval_ms))
+        .map_err(MixerError::from)
+}
+
+/// Overrides the onboard LED of every connected device; older firmware ignores it
+#[tauri::command]
+async fn set_device_led(state: State<'_, AppState>, mode: LedMode) -> Result<(), MixerError> {
+    state
+        .serial_manager
+        .send_command(mode.command())
+        .map_err(MixerError::from)
+}
+
+/// Forwards everything the device sends as `serial-raw` events, for debugging
+/// firmware output
+#[tauri::command]
+async fn set_raw_monitor(state: State<'_, AppState>, enabled: bool) -> Result<(), MixerError> {
+    state.serial_manager.set_raw_monitor(enabled);
+    Ok(())
+}
+
+/// Health readings of `port`, or of the first connected device for `None`
+#[tauri::command]
+async fn get_device_diagnostics(
+    state: State<'_, AppState>,
+    port: Option<String>,
+) -> Result<Option<DeviceDiagnostics>, MixerError> {
+    Ok(state.serial_manager.get_diagnostics(port.as_deref()))
+}
+
+/// Status of `port`, or of the first connected device for `None`
+#[tauri::command]
+async fn get_serial_status(
+    state: State<'_, AppState>,
+    port: Option<String>,
+) -> Result<ConnectionStatus, MixerError> {
+    Ok(state.serial_manager.get_status(port.as_deref()))
+}
+
+#[tauri::command]
+async fn list_connected_devices(
+    state: State<'_, AppState>,
+) -> Result<Vec<ConnectionStatus>, MixerError> {
+    Ok(state.serial_manager.list_connected())
+}
+
+#[tauri::command]
+async fn get_audio_sessions(state: State<'_, AppState>) -> Result<Vec<AudioSession>, MixerError> {
+    let merge = *state.merge_sessions.read().await;
+    session_list(state.audio_manager.as_ref(), merge).map_err(MixerError::from)
+}
+
+/// Icon of a session's executable as a base64-encoded PNG, looked up apart
+/// from the session list so polls stay small. `None` when there is none.
+#[tauri::command]
+async fn get_session_icon(
+    state: State<'_, AppState>,
+    process_id: u32,
+) -> Result<Option<String>, MixerError> {
+    state
+        .audio_manager
+        .get_session_icon(process_id)
+        .map_err(MixerError::from)
+}
+
+/// Session list for pickers such as the mapping dropdown, without blocklisted
+/// executables and trimmed and sorted per `filter`
+#[tauri::command]
+async fn get_audio_sessions_filtered(
+    state: State<'_, AppState>,
+    filter: SessionFilter,
+) -> Result<Vec<AudioSession>, MixerError> {
+    let merge = *state.merge_sessions.read().await;
+    let sessions = session_list(state.audio_manager.as_ref(), merge).map_err(MixerError::from)?;
+    let blocklist = state.session_blocklist.read().await;
+    Ok(audio::filter_sessions(sessions, filter, &blocklist))
+}
+
+/// Replaces the executables hidden from filtered session lists
+#[tauri::command]
+async fn set_session_blocklist(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    names: Vec<String>,
+) -> Result<(), MixerError> {
+    let names: Vec<String> = names
+        .iter()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.session_blocklist = names.clone();
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    *state.session_blocklist.write().await = names;
+    Ok(())
+}
+
+/// Re-enumerates sessions now instead of waiting for the next poll
+#[tauri::command]
+async fn refresh_audio_sessions(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> Result<Vec<AudioSession>, MixerError> {
+    let sessions = refresh_session_cache(&state, &app_handle).await?;
+    state.session_refresh.notify_one();
+    Ok(sessions)
+}
+
+#[tauri::command]
+async fn set_merge_sessions(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    enabled: bool,
+) -> Result<(), MixerError> {
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.merge_sessions = enabled;
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    *state.merge_sessions.write().await = enabled;
+    refresh_session_cache(&state, &app_handle).await.map(|_| ())
+}
+
+#[tauri::command]
+async fn set_expose_system_sounds(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    enabled: bool,
+) -> Result<(), MixerError> {
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.expose_system_sounds = enabled;
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    state.audio_manager.set_expose_system_sounds(enabled);
+    refresh_session_cache(&state, &app_handle).await.map(|_| ())
+}
+
+/// Sets the USB IDs auto-detection looks for first; `None` restores the stock ones
+#[tauri::command]
+async fn set_device_filter(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    filter: Option<UsbDeviceFilter>,
+) -> Result<(), MixerError> {
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.device_filter = filter;
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    state.serial_manager.set_device_filter(filter);
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_invert_all(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    enabled: bool,
+) -> Result<(), MixerError> {
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.invert_all_channels = enabled;
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    *state.invert_all_channels.write().await = enabled;
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_soft_takeover(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    enabled: bool,
+) -> Result<(), MixerError> {
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.soft_takeover = enabled;
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    *state.soft_takeover.write().await = enabled;
+    Ok(())
+}
+
+/// Unmutes a muted target when its fader moves, instead of leaving the fader
+/// changing a volume nobody hears
+#[tauri::command]
+async fn set_unmute_on_fader_move(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    enabled: bool,
+) -> Result<(), MixerError> {
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.unmute_on_fader_move = enabled;
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    *state.unmute_on_fader_move.write().await = enabled;
+    Ok(())
+}
+
+/// Sends `SET` commands that drive motorized faders to their targets' volumes
+/// when something other than the fader changes them
+#[tauri::command]
+async fn set_motorized_faders(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    enabled: bool,
+) -> Result<(), MixerError> {
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.motorized_faders = enabled;
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    *state.motorized_faders.write().await = enabled;
+    Ok(())
+}
+
+/// Chooses whether the UI or a fader wins when both change a channel at once
+#[tauri::command]
+async fn set_volume_priority(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    priority: VolumePriority,
+) -> Result<(), MixerError> {
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.volume_priority = priority;
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    state.volume_arbiter.write().await.priority = priority;
+    Ok(())
+}
+
+/// Sets the delays between reconnect attempts; loops already retrying keep theirs
+#[tauri::command]
+async fn set_reconnect_backoff(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    backoff: BackoffConfig,
+) -> Result<(), MixerError> {
+    if backoff.initial_ms == 0 || !backoff.multiplier.is_finite() || !backoff.jitter.is_finite() {
+        return Err(MixerError::InvalidArgument(
+            "Backoff needs a non-zero initial delay and finite factors".to_string(),
+        ));
+    }
+
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.reconnect_backoff = backoff;
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    *state.reconnect_backoff.write().await = backoff;
+    Ok(())
+}
+
+/// Sets the step fader readings are rounded to, returning it after clamping
+#[tauri::command]
+async fn set_volume_step(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    step: f32,
+) -> Result<f32, MixerError> {
+    if !step.is_finite() {
+        return Err(MixerError::InvalidArgument(format!(
+            "Volume step must be a number, got {}",
+            step
+        )));
+    }
+    let step = step.clamp(MIN_VOLUME_STEP, MAX_VOLUME_STEP);
+
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.volume_step = step;
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    *state.volume_step.write().await = step;
+    Ok(step)
+}
+
+/// Sets where `mute_at_zero` targets mute and unmute, and the mute button debounce
+#[tauri::command]
+async fn set_mute_thresholds(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    thresholds: MuteThresholds,
+) -> Result<(), MixerError> {
+    let valid = thresholds.mute_below >= 0.0
+        && thresholds.mute_below <= thresholds.unmute_above
+        && thresholds.unmute_above <= 100.0;
+    if !valid {
+        return Err(MixerError::InvalidArgument(
+            "Mute thresholds must satisfy 0 <= mute_below <= unmute_above <= 100".to_string(),
+        ));
+    }
+
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.mute_thresholds = thresholds;
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    *state.mute_thresholds.write().await = thresholds;
+    Ok(())
+}
+
+/// Sets how often sessions are polled, returning the interval after clamping
+#[tauri::command]
+async fn set_session_poll_interval(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    interval_secs: u64,
+) -> Result<u64, MixerError> {
+    let interval_secs = interval_secs.clamp(
+        MIN_SESSION_POLL_INTERVAL_SECS,
+        MAX_SESSION_POLL_INTERVAL_SECS,
+    );
+
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.session_poll_interval_secs = interval_secs;
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    *state.session_poll_interval_secs.write().await = interval_secs;
+    // Rearms the poller, so a shorter interval doesn't wait out the old one
+    state.session_refresh.notify_one();
+    Ok(interval_secs)
+}
+
+#[tauri::command]
+async fn set_app_volume(
+    state: State<'_, AppState>,
+    process_id: u32,
+    volume: f32,
+) -> Result<(), MixerError> {
+    let volume = Volume::from_percent(volume)?;
+    claim_for_ui(&state, process_id).await?;
+
+    // A merged entry stands for every process of its executable
+    let merged_name = state
+        .last_audio_sessions
+        .read()
+        .await
+        .iter()
+        .find(|s| s.process_id == process_id && s.session_count > 1)
+        .map(|s| s.process_name.clone());
+
+    match merged_name {
+        Some(name) => state
+            .audio_manager
+            .set_volume_by_process_name(&name, volume)
+            .map(|_| ()),
+        None => state.audio_manager.set_app_volume(process_id, volume),
+    }
+    .map_err(MixerError::from)?;
+
+    let _ = state
+        .control_events
+        .send(ControlEvent::VolumeChanged(VolumeChange {
+            process_id,
+            volume: volume.as_percent(),
+        }));
+    Ok(())
+}
+
+/// Sets the volume of every session of an executable, which unlike a process
+/// ID survives the app restarting. Returns how many sessions were changed.
+#[tauri::command]
+async fn set_volume_by_name(
+    state: State<'_, AppState>,
+    name: String,
+    volume: f32,
+) -> Result<usize, MixerError> {
+    state
+        .audio_manager
+        .set_volume_by_process_name(&name, Volume::from_percent(volume)?)
+        .map_err(MixerError::from)
+}
+
+/// Sets many volumes in one call, e.g. when applying a snapshot
+#[tauri::command]
+async fn set_all_volumes(
+    state: State<'_, AppState>,
+    updates: Vec<(u32, f32)>,
+) -> Result<(), MixerError> {
+    let updates = updates
+        .into_iter()
+        .map(|(process_id, volume)| Ok((process_id, Volume::from_percent(volume)?)))
+        .collect::<Result<Vec<(u32, Volume)>, MixerError>>()?;
+    let last_sessions = state.last_audio_sessions.read().await.clone();
+    let is_merged = |process_id: u32| {
+        last_sessions
+            .iter()
+            .find(|s| s.process_id == process_id && s.session_count > 1)
+    };
+
+    // Merged entries stand for every process of their executable
+    let updates = if updates.iter().any(|&(pid, _)| is_merged(pid).is_some()) {
+        let sessions = state
+            .audio_manager
+            .get_audio_sessions()
+            .map_err(MixerError::from)?;
+        updates
+            .iter()
+            .flat_map(|&(process_id, volume)| match is_merged(process_id) {
+                Some(merged) => sessions
+                    .iter()
+                    .filter(|s| {
+                        s.process_id != 0
+                            && s.process_name.eq_ignore_ascii_case(&merged.process_name)
+                    })
+                    .map(|s| (s.process_id, volume))
+                    .collect(),
+                None => vec![(process_id, volume)],
+            })
+            .collect()
+    } else {
+        updates
+    };
+
+    state
+        .audio_manager
+        .set_volumes(&updates)
+        .map_err(MixerError::from)
+}
+
+#[tauri::command]
+async fn set_master_volume(state: State<'_, AppState>, volume: f32) -> Result<(), MixerError> {
+    let volume = Volume::from_percent(volume)?;
+    claim_for_ui(&state, MASTER_VOLUME_PROCESS_ID).await?;
+    state
+        .audio_manager
+        .set_master_volume(volume)
+        .map_err(MixerError::from)?;
+
+    let _ = state
+        .control_events
+        .send(ControlEvent::VolumeChanged(VolumeChange {
+            process_id: 0,
+            volume: volume.as_percent(),
+        }));
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_master_volume(state: State<'_, AppState>) -> Result<f32, MixerError> {
+    state
+        .audio_manager
+        .get_master_volume()
+        .map_err(MixerError::from)
 }
 
 #[tauri::command]
-async fn list_serial_ports() -> Result<Vec<SerialPortInfo>, String> {
-    SerialManager::list_ports().map_err(|e| e.to_string())
+async fn set_input_volume(state: State<'_, AppState>, volume: f32) -> Result<(), MixerError> {
+    state
+        .audio_manager
+        .set_input_volume(Volume::from_percent(volume)?)
+        .map_err(MixerError::from)
 }
 
 #[tauri::command]
-async fn connect_serial(
+async fn get_input_volume(state: State<'_, AppState>) -> Result<f32, MixerError> {
+    state
+        .audio_manager
+        .get_input_volume()
+        .map_err(MixerError::from)
+}
+
+#[tauri::command]
+async fn get_audio_capabilities(
+    state: State<'_, AppState>,
+) -> Result<AudioCapabilities, MixerError> {
+    Ok(state.audio_manager.capabilities())
+}
+
+#[tauri::command]
+async fn get_backend_info(state: State<'_, AppState>) -> Result<BackendInfo, MixerError> {
+    Ok(state.audio_manager.backend_info())
+}
+
+/// Checks that the audio backend really changes volumes, putting each one back
+#[tauri::command]
+async fn self_test(state: State<'_, AppState>) -> Result<SelfTestReport, MixerError> {
+    Ok(selftest::run(state.audio_manager.as_ref()))
+}
+
+#[tauri::command]
+async fn list_output_devices(state: State<'_, AppState>) -> Result<Vec<AudioDevice>, MixerError> {
+    state
+        .audio_manager
+        .list_output_devices()
+        .map_err(MixerError::from)
+}
+
+/// Switches the default output device, returning it as now listed
+#[tauri::command]
+async fn set_output_device(
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<AudioDevice, MixerError> {
+    state
+        .audio_manager
+        .set_default_output_device(&device_id)
+        .map_err(MixerError::from)?;
+    state
+        .audio_manager
+        .list_output_devices()
+        .map_err(MixerError::from)?
+        .into_iter()
+        .find(|device| device.id == device_id)
+        .ok_or_else(|| MixerError::DeviceNotFound(format!("No audio device {}", device_id)))
+}
+
+#[tauri::command]
+async fn get_peak_levels(state: State<'_, AppState>) -> Result<Vec<(u32, f32)>, MixerError> {
+    let levels = state
+        .audio_manager
+        .get_peak_levels()
+        .map_err(MixerError::from)?;
+    Ok(scale_peak_levels(levels, *state.meter.read().await))
+}
+
+fn scale_peak_levels(levels: Vec<(u32, f32)>, meter: MeterSettings) -> Vec<(u32, f32)> {
+    levels
+        .into_iter()
+        .map(|(pid, peak)| (pid, meter.apply(peak)))
+        .collect()
+}
+
+/// Sets the units of peak meter readings and whether they are weighted
+#[tauri::command]
+async fn set_meter_scale(
     state: State<'_, AppState>,
-    port: Option<String>,
     app_handle: AppHandle,
-) -> Result<ConnectionStatus, String> {
-    let status = state
-        .serial_manager
-        .connect(port)
-        .map_err(|e| e.to_string())?;
+    scale: MeterScale,
+    weighted: bool,
+) -> Result<(), MixerError> {
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.meter_scale = scale;
+    config.meter_weighting = weighted;
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
 
-    if status.connected {
-        // Start reading data and emitting events
-        let (tx, mut rx) = mpsc::channel(100);
+    *state.meter.write().await = MeterSettings { scale, weighted };
+    Ok(())
+}
 
-        let serial_manager = state.serial_manager.clone();
-        serial_manager
-            .start_reading(tx)
-            .await
-            .map_err(|e| e.to_string())?;
+#[tauri::command]
+async fn mute_all(state: State<'_, AppState>) -> Result<(), MixerError> {
+    apply_mute_all(state.audio_manager.as_ref(), &state.mute_all_saved)
+        .await
+        .map_err(MixerError::from)
+}
 
-        // Spawn task to emit pot data events
-        let app_handle_clone = app_handle.clone();
-        let audio_manager = state.audio_manager.clone();
+#[tauri::command]
+async fn unmute_all(state: State<'_, AppState>) -> Result<(), MixerError> {
+    restore_mute_all(state.audio_manager.as_ref(), &state.mute_all_saved)
+        .await
+        .map_err(MixerError::from)
+}
 
-        tokio::spawn(async move {
-            while let Some(data) = rx.recv().await {
-                // Emit raw pot data
-                if let Err(e) = app_handle_clone.emit("pot-data", &data) {
-                    log::error!("Failed to emit pot-data event: {}", e);
-                }
+/// Records the current volumes so `restore_snapshot` can undo later experiments
+#[tauri::command]
+async fn snapshot_volumes(state: State<'_, AppState>) -> Result<(), MixerError> {
+    let saved = state
+        .audio_manager
+        .snapshot_volumes()
+        .map_err(MixerError::from)?;
+    *state.volume_snapshot.write().await = Some(saved);
+    Ok(())
+}
+
+#[tauri::command]
+async fn restore_snapshot(state: State<'_, AppState>) -> Result<(), MixerError> {
+    restore_volume_snapshot(&state)
+        .await
+        .map_err(MixerError::from)
+}
+
+async fn restore_volume_snapshot(state: &AppState) -> anyhow::Result<()> {
+    let snapshot = state.volume_snapshot.read().await;
+    let saved = snapshot
+        .as_ref()
+        .ok_or_else(|| MixerError::InvalidArgument("No volume snapshot taken".to_string()))?;
+    state.audio_manager.restore_volumes(saved)
+}
 
-                // Use pot1 to control master volume directly
-                let (pot1, _pot2, _pot3) = data.to_percentages();
-                let _ = audio_manager.set_master_volume(pot1);
+#[tauri::command]
+async fn solo_channel(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    channel_id: usize,
+) -> Result<(), MixerError> {
+    let duck_level = config::load_config(&app_handle)
+        .map_err(MixerError::from)?
+        .duck_level;
+
+    // Held throughout so fader writes can't interleave with the capture. Same
+    // lock order as the reader task: mappings, then solo.
+    let mappings = state.active_mappings.read().await;
+    let mut solo = state.solo.write().await;
+    let audio_manager = state.audio_manager.as_ref();
+
+    // Restore first when switching solo, so the saved levels are never ducked ones
+    if let Some(previous) = solo.take() {
+        restore_solo(audio_manager, &mappings, previous);
+    }
+
+    let sessions = audio_manager
+        .get_audio_sessions()
+        .map_err(MixerError::from)?;
+
+    let mut new_solo = SoloState {
+        channel_id,
+        saved_volumes: Vec::new(),
+        moved_channels: HashMap::new(),
+    };
+
+    let ducked: Vec<&ChannelMapping> = mappings.iter().filter(|m| new_solo.is_ducked(m)).collect();
+    for mapping in ducked {
+        for process_id in mapped_process_ids(mapping, &sessions) {
+            if new_solo
+                .saved_volumes
+                .iter()
+                .any(|&(id, _)| id == process_id)
+            {
+                continue;
             }
-        });
+            let Some(session) = sessions.iter().find(|s| s.process_id == process_id) else {
+                continue;
+            };
+
+            new_solo
+                .saved_volumes
+                .push((process_id, Volume::from_percent(session.volume)?));
+            let ducked = Volume::from_percent(duck_level.min(session.volume))?;
+            if let Err(e) = audio_manager.set_app_volume(process_id, ducked) {
+                log::warn!("Failed to duck process {}: {}", process_id, e);
+            }
+        }
     }
 
-    Ok(status)
+    *solo = Some(new_solo);
+    Ok(())
 }
 
 #[tauri::command]
-async fn disconnect_serial(state: State<'_, AppState>) -> Result<(), String> {
-    state.serial_manager.disconnect();
+async fn clear_solo(state: State<'_, AppState>) -> Result<(), MixerError> {
+    let mappings = state.active_mappings.read().await;
+    if let Some(previous) = state.solo.write().await.take() {
+        restore_solo(state.audio_manager.as_ref(), &mappings, previous);
+    }
     Ok(())
 }
 
+/// Moves the volume of everything mapped to a channel by `delta` percentage
+/// points, e.g. from a hotkey, returning the new volume
 #[tauri::command]
-async fn get_serial_status(state: State<'_, AppState>) -> Result<ConnectionStatus, String> {
-    Ok(state.serial_manager.get_status())
+async fn adjust_channel_volume(
+    state: State<'_, AppState>,
+    channel_id: usize,
+    delta: f32,
+) -> Result<f32, MixerError> {
+    if !delta.is_finite() {
+        return Err(MixerError::InvalidVolume(delta));
+    }
+
+    let mappings: Vec<ChannelMapping> = state
+        .active_mappings
+        .read()
+        .await
+        .iter()
+        .filter(|m| m.channel_id == channel_id)
+        .cloned()
+        .collect();
+    if mappings.is_empty() {
+        return Err(MixerError::InvalidArgument(format!(
+            "Channel {} isn't mapped",
+            channel_id
+        )));
+    }
+
+    let mut volume = 0.0;
+    for mapping in &mappings {
+        volume = adjust_mapping(state.audio_manager.as_ref(), mapping, delta)
+            .map_err(MixerError::from)?;
+    }
+    Ok(volume)
 }
 
 #[tauri::command]
-async fn get_audio_sessions(state: State<'_, AppState>) -> Result<Vec<AudioSession>, String> {
-    state
-        .audio_manager
-        .get_audio_sessions()
-        .map_err(|e| e.to_string())
+async fn get_mixer_channels(state: State<'_, AppState>) -> Result<Vec<MixerChannel>, MixerError> {
+    Ok(mixer_channels(
+        &*state.channel_counts.read().await,
+        &*state.channel_values.read().await,
+        &*state.channel_labels.read().await,
+    ))
+}
+
+/// The physical channels of every connected device in port order, numbered
+/// on across devices so ids stay unique. The default bank stands in while
+/// nothing is connected.
+fn mixer_channels(
+    channel_counts: &HashMap<String, usize>,
+    values: &HashMap<String, Vec<f32>>,
+    labels: &PerDevice<HashMap<usize, String>>,
+) -> Vec<MixerChannel> {
+    let mut devices: Vec<(Option<&str>, usize)> = channel_counts
+        .iter()
+        .map(|(port, &count)| (Some(port.as_str()), count))
+        .collect();
+    devices.sort();
+    if devices.is_empty() {
+        devices.push((None, DEFAULT_CHANNEL_COUNT));
+    }
+
+    let mut channels = Vec::new();
+    for (device, channel_count) in devices {
+        let device_values = device.and_then(|port| values.get(port));
+        for channel in 1..=channel_count {
+            channels.push(MixerChannel {
+                id: channels.len() + 1,
+                channel,
+                value: device_values
+                    .and_then(|values| values.get(channel - 1))
+                    .copied()
+                    .unwrap_or(0.0),
+                is_physical: true,
+                label: labels.label(device, channel).cloned(),
+                device: device.map(str::to_string),
+            });
+        }
+    }
+    channels
 }
 
+/// Names a channel in the UI; `None` or a blank label removes the name. With
+/// a `port` the name only applies to that device's channel.
 #[tauri::command]
-async fn set_app_volume(
+async fn set_channel_label(
     state: State<'_, AppState>,
-    process_id: u32,
-    volume: f32,
-) -> Result<(), String> {
-    state
-        .audio_manager
-        .set_app_volume(process_id, volume)
-        .map_err(|e| e.to_string())
+    app_handle: AppHandle,
+    channel_id: usize,
+    label: Option<String>,
+    port: Option<String>,
+) -> Result<(), MixerError> {
+    if channel_id == 0 {
+        return Err(MixerError::InvalidArgument(
+            "Channel ids start at 1".to_string(),
+        ));
+    }
+    let label = label
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty());
+
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    let labels = match port {
+        Some(port) => config.device_channel_labels.entry(port).or_default(),
+        None => &mut config.channel_labels,
+    };
+    match label {
+        Some(label) => labels.insert(channel_id, label),
+        None => labels.remove(&channel_id),
+    };
+    config
+        .device_channel_labels
+        .retain(|_, labels| !labels.is_empty());
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    *state.channel_labels.write().await = config.label_tables();
+    Ok(())
+}
+
+#[tauri::command]
+async fn export_config(app_handle: AppHandle, path: String) -> Result<(), MixerError> {
+    config::export_config(&app_handle, Path::new(&path)).map_err(MixerError::from)
 }
 
 #[tauri::command]
-async fn set_master_volume(state: State<'_, AppState>, volume: f32) -> Result<(), String> {
+async fn import_config(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    path: String,
+) -> Result<ProfileList, MixerError> {
+    let config = config::import_config(&app_handle, Path::new(&path)).map_err(MixerError::from)?;
+
+    state.serial_manager.set_protocol(config.protocol);
+    state.serial_manager.set_device_filter(config.device_filter);
+    state.serial_manager.set_smoothing(SmoothingSettings {
+        smoothing_factor: config.smoothing_factor,
+        deadband: config.deadband,
+    });
+    *state.active_mappings.write().await = config.active_mappings();
+    *state.calibration.write().await = config.calibration_tables();
+    *state.channel_labels.write().await = config.label_tables();
+    *state.merge_sessions.write().await = config.merge_sessions;
+    logging::apply_level(config.log_level);
+    *state.session_blocklist.write().await = config.session_blocklist.clone();
+    *state.soft_takeover.write().await = config.soft_takeover;
+    *state.invert_all_channels.write().await = config.invert_all_channels;
+    *state.motorized_faders.write().await = config.motorized_faders;
+    state.volume_arbiter.write().await.priority = config.volume_priority;
+    *state.volume_step.write().await = config.volume_step.clamp(MIN_VOLUME_STEP, MAX_VOLUME_STEP);
+    *state.mute_thresholds.write().await = config.mute_thresholds;
+    *state.unmute_on_fader_move.write().await = config.unmute_on_fader_move;
+    *state.reconnect_backoff.write().await = config.reconnect_backoff;
+    *state.meter.write().await = MeterSettings {
+        scale: config.meter_scale,
+        weighted: config.meter_weighting,
+    };
+    *state.session_poll_interval_secs.write().await = config.session_poll_interval_secs.clamp(
+        MIN_SESSION_POLL_INTERVAL_SECS,
+        MAX_SESSION_POLL_INTERVAL_SECS,
+    );
     state
         .audio_manager
-        .set_master_volume(volume)
-        .map_err(|e| e.to_string())
+        .set_expose_system_sounds(config.expose_system_sounds);
+    state.last_audio_sessions.write().await.clear();
+    state.session_refresh.notify_one();
+
+    let mut hotkeys = state.hotkeys.write().await;
+    if let Err(e) = app_handle.global_shortcut().unregister_all() {
+        log::warn!("Failed to unregister hotkeys: {}", e);
+    }
+    register_hotkeys(&app_handle, &config.hotkeys);
+    *hotkeys = config.hotkeys.clone();
+    drop(hotkeys);
+
+    Ok(profile_list(&config))
+}
+
+/// Changes how verbose the log is, effective immediately
+#[tauri::command]
+async fn set_log_level(app_handle: AppHandle, level: LogLevel) -> Result<(), MixerError> {
+    let mut config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    config.log_level = level;
+    config::save_config(&app_handle, &config).map_err(MixerError::from)?;
+
+    logging::apply_level(level);
+    log::info!("Log level set to {:?}", level);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_log_path(app_handle: AppHandle) -> Result<String, MixerError> {
+    let path = logging::log_path(&app_handle).map_err(MixerError::from)?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+#[tauri::command]
+async fn get_settings(app_handle: AppHandle) -> Result<AppConfig, MixerError> {
+    config::load_config(&app_handle).map_err(MixerError::from)
+}
+
+/// Changes the general settings; fields left out keep their current value
+#[tauri::command]
+async fn update_settings(
+    app_handle: AppHandle,
+    start_with_windows: Option<bool>,
+    minimize_to_tray: Option<bool>,
+    auto_connect: Option<bool>,
+    theme: Option<String>,
+) -> Result<AppConfig, MixerError> {
+    config::update_settings(
+        &app_handle,
+        start_with_windows,
+        minimize_to_tray,
+        auto_connect,
+        theme,
+    )
+    .map_err(MixerError::from)
+}
+
+/// Binds `accelerator` to `action` system-wide, replacing any action already
+/// on that key combination
+#[tauri::command]
+async fn set_hotkey(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    accelerator: String,
+    action: HotkeyAction,
+) -> Result<Vec<HotkeyBinding>, MixerError> {
+    let shortcut = parse_accelerator(&accelerator)?;
+
+    let mut hotkeys = state.hotkeys.write().await;
+    if !hotkeys
+        .iter()
+        .any(|binding| is_bound_to(binding, &shortcut))
+    {
+        register_hotkey(&app_handle, shortcut, &accelerator)?;
+    }
+    hotkeys.retain(|binding| !is_bound_to(binding, &shortcut));
+    hotkeys.push(HotkeyBinding {
+        accelerator,
+        action,
+    });
+
+    config::save_hotkeys(&app_handle, &hotkeys).map_err(MixerError::from)?;
+    Ok(hotkeys.clone())
+}
+
+#[tauri::command]
+async fn clear_hotkey(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    accelerator: String,
+) -> Result<Vec<HotkeyBinding>, MixerError> {
+    let shortcut = parse_accelerator(&accelerator)?;
+
+    // May already be unregistered if registering failed at startup
+    if let Err(e) = app_handle.global_shortcut().unregister(shortcut) {
+        log::warn!("Failed to unregister hotkey {}: {}", accelerator, e);
+    }
+
+    let mut hotkeys = state.hotkeys.write().await;
+    hotkeys.retain(|binding| !is_bound_to(binding, &shortcut));
+
+    config::save_hotkeys(&app_handle, &hotkeys).map_err(MixerError::from)?;
+    Ok(hotkeys.clone())
+}
+
+#[tauri::command]
+async fn list_profiles(app_handle: AppHandle) -> Result<ProfileList, MixerError> {
+    let config = config::load_config(&app_handle).map_err(MixerError::from)?;
+    Ok(profile_list(&config))
+}
+
+#[tauri::command]
+async fn create_profile(
+    app_handle: AppHandle,
+    name: String,
+    mappings: Option<Vec<ChannelMapping>>,
+) -> Result<ProfileList, MixerError> {
+    // New profiles start as a copy of the active one unless mappings are given
+    let mappings = match mappings {
+        Some(mappings) => mappings,
+        None => config::load_config(&app_handle)
+            .map_err(MixerError::from)?
+            .active_mappings(),
+    };
+
+    let config = config::create_profile(&app_handle, name, mappings).map_err(MixerError::from)?;
+    Ok(profile_list(&config))
+}
+
+#[tauri::command]
+async fn delete_profile(app_handle: AppHandle, name: String) -> Result<ProfileList, MixerError> {
+    let config = config::delete_profile(&app_handle, &name).map_err(MixerError::from)?;
+    Ok(profile_list(&config))
+}
+
+#[tauri::command]
+async fn switch_profile(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    name: String,
+) -> Result<ProfileList, MixerError> {
+    let config = config::switch_profile(&app_handle, &name).map_err(MixerError::from)?;
+
+    // Swap under the write lock so the reader never sees a mix of two profiles
+    *state.active_mappings.write().await = config.active_mappings();
+
+    Ok(profile_list(&config))
+}
+
+#[tauri::command]
+async fn save_channel_mapping(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    mapping: ChannelMapping,
+) -> Result<Vec<ChannelMapping>, MixerError> {
+    let config = config::save_channel_mapping(&app_handle, mapping).map_err(MixerError::from)?;
+    let mappings = config.active_mappings();
+
+    *state.active_mappings.write().await = mappings.clone();
+
+    Ok(mappings)
 }
 
+/// Watches the faders until one is moved, for a "move the fader you want to
+/// assign" prompt. The result arrives as an `identified-channel` event, `null`
+/// on timeout; starting again replaces a watch still running.
 #[tauri::command]
-async fn get_master_volume(state: State<'_, AppState>) -> Result<f32, String> {
+async fn identify_channels(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    timeout_ms: Option<u64>,
+) -> Result<(), MixerError> {
+    let token = state.cancellation_token.child_token();
+    if let Some(previous) = state.identify_channels.write().await.replace(token.clone()) {
+        previous.cancel();
+    }
+
+    let channel_values = state.channel_values.clone();
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_IDENTIFY_TIMEOUT_MS));
+    tauri::async_runtime::spawn(async move {
+        let identified = tokio::select! {
+            _ = token.cancelled() => return,
+            identified = watch_for_moved_channel(&channel_values, timeout) => identified,
+        };
+        match &identified {
+            Some(channel) => log::info!(
+                "Identified channel {} on {} (moved {:.0}%)",
+                channel.channel_id,
+                channel.port,
+                channel.delta
+            ),
+            None => log::info!("No fader moved while identifying channels"),
+        }
+        if let Err(e) = app_handle.emit("identified-channel", &identified) {
+            log::error!("Failed to emit identified-channel event: {}", e);
+        }
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn cancel_identify_channels(state: State<'_, AppState>) -> Result<(), MixerError> {
+    if let Some(token) = state.identify_channels.write().await.take() {
+        token.cancel();
+    }
+    Ok(())
+}
+
+/// Samples every device's fader values until one has moved at least
+/// `IDENTIFY_MIN_DELTA` from where it started, then returns whichever moved
+/// furthest within `IDENTIFY_SETTLE_TIME`. `None` if nothing moved in time.
+async fn watch_for_moved_channel(
+    channel_values: &RwLock<HashMap<String, Vec<f32>>>,
+    timeout: Duration,
+) -> Option<IdentifiedChannel> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut baseline = channel_values.read().await.clone();
+    // Largest movement seen so far, by port and channel id
+    let mut moved: HashMap<(String, usize), f32> = HashMap::new();
+    let mut decide_at = None;
+    let mut poll_timer = tokio::time::interval(IDENTIFY_POLL_INTERVAL);
+
+    loop {
+        poll_timer.tick().await;
+        let now = tokio::time::Instant::now();
+
+        for (port, values) in channel_values.read().await.iter() {
+            // A device connected midway starts from its first reading
+            let start = baseline
+                .entry(port.clone())
+                .or_insert_with(|| values.clone());
+            for (index, (&value, &from)) in values.iter().zip(start.iter()).enumerate() {
+                let largest = moved.entry((port.clone(), index + 1)).or_insert(0.0);
+                *largest = largest.max((value - from).abs());
+            }
+        }
+
+        if decide_at.is_none() && moved.values().any(|&delta| delta >= IDENTIFY_MIN_DELTA) {
+            decide_at = Some(now + IDENTIFY_SETTLE_TIME);
+        }
+        match decide_at {
+            Some(at) if now >= at => {
+                return moved
+                    .into_iter()
+                    .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                    .map(|((port, channel_id), delta)| IdentifiedChannel {
+                        port,
+                        channel_id,
+                        delta,
+                    });
+            }
+            None if now >= deadline => return None,
+            _ => {}
+        }
+    }
+}
+
+/// Starts recording a fader's range. With a `port` only that device's fader
+/// counts and the result is kept for it alone; otherwise any device's fader
+/// on the channel counts and the result is shared.
+#[tauri::command]
+async fn start_channel_calibration(
+    state: State<'_, AppState>,
+    channel_id: usize,
+    port: Option<String>,
+) -> Result<(), MixerError> {
+    let channel_counts = state.channel_counts.read().await;
+    let channel_count = match &port {
+        Some(port) => channel_counts
+            .get(port)
+            .copied()
+            .ok_or(MixerError::NotConnected)?,
+        None => channel_counts
+            .values()
+            .copied()
+            .max()
+            .unwrap_or(DEFAULT_CHANNEL_COUNT),
+    };
+    if !(1..=channel_count).contains(&channel_id) {
+        return Err(MixerError::InvalidArgument(format!(
+            "Invalid channel id {}",
+            channel_id
+        )));
+    }
+
     state
-        .audio_manager
-        .get_master_volume()
-        .map_err(|e| e.to_string())
+        .calibrations_in_progress
+        .write()
+        .await
+        .insert((port, channel_id), CalibrationData::empty());
+
+    Ok(())
 }
 
 #[tauri::command]
-async fn get_mixer_channels(_state: State<'_, AppState>) -> Result<Vec<MixerChannel>, String> {
-    let mut channels = Vec::new();
+async fn finish_channel_calibration(
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+    channel_id: usize,
+    port: Option<String>,
+) -> Result<CalibrationData, MixerError> {
+    let observed = state
+        .calibrations_in_progress
+        .write()
+        .await
+        .remove(&(port.clone(), channel_id))
+        .ok_or_else(|| {
+            MixerError::InvalidArgument(format!("Channel {} is not being calibrated", channel_id))
+        })?;
 
-    // Only return 3 physical channels
-    for i in 1..=3 {
-        channels.push(MixerChannel {
-            id: i,
-            value: 0.0,
-            is_physical: true,
-        });
+    if !observed.is_valid() {
+        return Err(MixerError::InvalidArgument(format!(
+            "No usable range observed for channel {}, sweep the fader end to end",
+            channel_id
+        )));
     }
 
-    Ok(channels)
+    let config = config::save_calibration(&app_handle, port.as_deref(), channel_id, observed)
+        .map_err(MixerError::from)?;
+    *state.calibration.write().await = config.calibration_tables();
+
+    Ok(observed)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let context = tauri::generate_context!();
+    if headless::requested() {
+        if let Err(e) = headless::run(context) {
+            log::error!("Headless mode failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     tauri::Builder::default()
+        .plugin(logging::plugin())
         .plugin(tauri_plugin_opener::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(handle_hotkey)
+                .build(),
+        )
         .setup(|app| {
             let app_handle = app.handle().clone();
 
+            let config = config::load_config(&app_handle).unwrap_or_else(|e| {
+                log::error!("Failed to load config, using defaults: {}", e);
+                AppConfig::default()
+            });
+            logging::apply_level(config.log_level);
+
+            // Re-applied on every launch so the login entry follows the app if it moved
+            if let Err(e) = autostart::set_enabled(&app_handle, config.start_with_windows) {
+                log::warn!("Failed to apply start-on-login setting: {}", e);
+            }
+
+            let serial_manager = SerialManager::new();
+            serial_manager.set_protocol(config.protocol);
+            serial_manager.set_device_filter(config.device_filter);
+            serial_manager.set_smoothing(SmoothingSettings {
+                smoothing_factor: config.smoothing_factor,
+                deadband: config.deadband,
+            });
+
+            let audio_manager: Arc<dyn AudioManager> = if SimAudioManager::requested() {
+                log::info!("Using simulated audio backend");
+                Arc::new(SimAudioManager::new())
+            } else {
+                Arc::new(WindowsAudioManager::new())
+            };
+            audio_manager.set_expose_system_sounds(config.expose_system_sounds);
+
             let app_state = AppState {
-                serial_manager: Arc::new(SerialManager::new()),
-                audio_manager: Arc::new(WindowsAudioManager::new()),
+                serial_manager: Arc::new(serial_manager),
+                audio_manager,
                 cancellation_token: CancellationToken::new(),
                 last_audio_sessions: Arc::new(RwLock::new(Vec::new())),
+                active_mappings: Arc::new(RwLock::new(config.active_mappings())),
+                calibration: Arc::new(RwLock::new(config.calibration_tables())),
+                channel_labels: Arc::new(RwLock::new(config.label_tables())),
+                calibrations_in_progress: Arc::new(RwLock::new(HashMap::new())),
+                channel_values: Arc::new(RwLock::new(HashMap::new())),
+                channel_counts: Arc::new(RwLock::new(HashMap::new())),
+                mute_all_saved: Arc::new(RwLock::new(None)),
+                volume_snapshot: Arc::new(RwLock::new(None)),
+                solo: Arc::new(RwLock::new(None)),
+                merge_sessions: Arc::new(RwLock::new(config.merge_sessions)),
+                session_blocklist: Arc::new(RwLock::new(config.session_blocklist.clone())),
+                invert_all_channels: Arc::new(RwLock::new(config.invert_all_channels)),
+                soft_takeover: Arc::new(RwLock::new(config.soft_takeover)),
+                motorized_faders: Arc::new(RwLock::new(config.motorized_faders)),
+                volume_arbiter: Arc::new(RwLock::new(VolumeArbiter::new(config.volume_priority))),
+                volume_step: Arc::new(RwLock::new(
+                    config.volume_step.clamp(MIN_VOLUME_STEP, MAX_VOLUME_STEP),
+                )),
+                mute_thresholds: Arc::new(RwLock::new(config.mute_thresholds)),
+                unmute_on_fader_move: Arc::new(RwLock::new(config.unmute_on_fader_move)),
+                identify_channels: Arc::new(RwLock::new(None)),
+                reconnect_backoff: Arc::new(RwLock::new(config.reconnect_backoff)),
+                meter: Arc::new(RwLock::new(MeterSettings {
+                    scale: config.meter_scale,
+                    weighted: config.meter_weighting,
+                })),
+                session_refresh: Arc::new(Notify::new()),
+                hotkeys: Arc::new(RwLock::new(config.hotkeys.clone())),
+                focused_app: Arc::new(RwLock::new(None)),
+                control_events: broadcast::channel(control::EVENT_BUFFER).0,
+                session_poll_interval_secs: Arc::new(RwLock::new(
+                    config.session_poll_interval_secs.clamp(
+                        MIN_SESSION_POLL_INTERVAL_SECS,
+                        MAX_SESSION_POLL_INTERVAL_SECS,
+                    ),
+                )),
             };
 
             app.manage(app_state);
 
+            register_hotkeys(app.handle(), &config.hotkeys);
+
+            // The window starts hidden so it doesn't appear at the default
+            // position and then jump
+            if let Some(window) = app.get_webview_window("main") {
+                if let Some(geometry) = config.window {
+                    if let Err(e) = window::restore_geometry(&window, geometry) {
+                        log::warn!("Failed to restore window geometry: {}", e);
+                    }
+                }
+                window::track_geometry(app_handle.clone(), window.clone());
+                let _ = window.show();
+            }
+
             // Setup system tray
             #[cfg(desktop)]
             {
@@ -157,14 +3366,22 @@ pub fn run() {
                 let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
                 let show = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
                 let hide = MenuItem::with_id(app, "hide", "Hide", true, None::<&str>)?;
+                let restore_volumes = MenuItem::with_id(
+                    app,
+                    "restore_volumes",
+                    "Restore volumes",
+                    true,
+                    None::<&str>,
+                )?;
 
-                let menu = Menu::with_items(app, &[&show, &hide, &quit])?;
+                let menu = Menu::with_items(app, &[&show, &hide, &restore_volumes, &quit])?;
 
                 let _tray = TrayIconBuilder::new()
                     .menu(&menu)
                     .tooltip("PC Audio Mixer")
                     .on_menu_event(|app, event| match event.id.as_ref() {
                         "quit" => {
+                            shutdown(app);
                             app.exit(0);
                         }
                         "show" => {
@@ -178,6 +3395,15 @@ pub fn run() {
                                 let _ = window.hide();
                             }
                         }
+                        "restore_volumes" => {
+                            let app = app.clone();
+                            tauri::async_runtime::spawn(async move {
+                                let state = app.state::<AppState>();
+                                if let Err(e) = restore_volume_snapshot(&state).await {
+                                    log::warn!("Failed to restore volumes: {}", e);
+                                }
+                            });
+                        }
                         _ => {}
                     })
                     .on_tray_icon_event(|tray, event| {
@@ -195,51 +3421,258 @@ pub fn run() {
                     .build(app)?;
             }
 
-            // Auto-connect to Pico on startup
-            let state = app.state::<AppState>();
+            // Auto-connect to every Pico on startup
+            let state = app.state::<AppState>();
+            let app_handle_clone = app_handle.clone();
+            let last_port = config.last_port.clone();
+            let serial_manager = state.serial_manager.clone();
+            let reconnect_backoff = state.reconnect_backoff.clone();
+            let cancellation_token = state.cancellation_token.clone();
+
+            tauri::async_runtime::spawn(async move {
+                // Wait a bit for the UI to be ready
+                tokio::select! {
+                    _ = cancellation_token.cancelled() => return,
+                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(1)) => {}
+                }
+
+                // Keep scanning with backoff until a device is plugged in, only
+                // reporting the first miss
+                let mut backoff = Backoff::new(*reconnect_backoff.read().await);
+                let mut port = last_port;
+                let mut report_failure = true;
+                while !connect_all(&app_handle_clone, port.take(), report_failure).await {
+                    report_failure = false;
+                    tokio::select! {
+                        _ = cancellation_token.cancelled() => return,
+                        _ = tokio::time::sleep(backoff.next_delay()) => {}
+                    }
+                    // Connected by hand in the meantime
+                    if !serial_manager.connected_ports().is_empty() {
+                        break;
+                    }
+                }
+            });
+
+            // Watch for a port that stays open but stops sending
             let serial_manager = state.serial_manager.clone();
-            let app_handle_clone = app_handle.clone();
+            let app_handle_clone4 = app_handle.clone();
+            let cancellation_token = state.cancellation_token.clone();
+            let stall_timeout = Duration::from_secs(config.stall_timeout_secs);
+            let reconnect_on_stall = config.reconnect_on_stall;
 
             tauri::async_runtime::spawn(async move {
-                // Wait a bit for the UI to be ready
-                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                // Ports whose stall was already reported
+                let mut stalled: HashSet<String> = HashSet::new();
+
+                loop {
+                    tokio::select! {
+                        _ = cancellation_token.cancelled() => {
+                            log::info!("Serial watchdog task cancelled");
+                            break;
+                        }
+                        _ = tokio::time::sleep(STALL_CHECK_INTERVAL) => {
+                            let ports = serial_manager.connected_ports();
+                            stalled.retain(|port| ports.contains(port));
+
+                            for port in ports {
+                                let since_data = match serial_manager.time_since_last_data(&port) {
+                                    Some(since_data) if !stall_timeout.is_zero() && since_data >= stall_timeout => since_data,
+                                    _ => {
+                                        stalled.remove(&port);
+                                        continue;
+                                    }
+                                };
 
-                // Try auto-connect
-                if let Ok(status) = serial_manager.connect(None) {
-                    if let Err(e) = app_handle_clone.emit("connection-status", &status) {
-                        log::error!("Failed to emit connection-status event: {}", e);
+                                // Report each stall once, until data flows again
+                                if !stalled.insert(port.clone()) {
+                                    continue;
+                                }
+
+                                log::warn!("No serial data from {} for {:.1}s", port, since_data.as_secs_f32());
+                                let event = SerialStalled {
+                                    port: Some(port.clone()),
+                                    seconds_since_data: since_data.as_secs_f32(),
+                                };
+                                if let Err(e) = app_handle_clone4.emit("serial-stalled", &event) {
+                                    log::error!("Failed to emit serial-stalled event: {}", e);
+                                }
+
+                                if reconnect_on_stall {
+                                    let reconnected = match open_serial(&app_handle_clone4, Some(port.clone())).await {
+                                        Ok(status) => {
+                                            if let Err(e) = app_handle_clone4.emit("connection-status", &status) {
+                                                log::error!("Failed to emit connection-status event: {}", e);
+                                            }
+                                            status.connected
+                                        }
+                                        Err(e) => {
+                                            log::error!("Failed to reconnect stalled port: {}", e);
+                                            false
+                                        }
+                                    };
+                                    // E.g. unplugged; keep trying without holding up the watchdog
+                                    if !reconnected {
+                                        tauri::async_runtime::spawn(reconnect_with_backoff(app_handle_clone4.clone(), port));
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
             });
 
+            // Signalled by session notifications, and by output switches since
+            // sessions on the new device aren't covered by them
+            let sessions_changed = Arc::new(Notify::new());
+
+            // Report output switches so the UI can show which device master controls
+            let app_handle_clone5 = app_handle.clone();
+            let device_sessions_changed = sessions_changed.clone();
+            if let Err(e) = state
+                .audio_manager
+                .watch_default_device(Box::new(move |device: AudioDevice| {
+                    log::info!("Default output device changed to {}", device.name);
+                    device_sessions_changed.notify_one();
+                    if let Err(e) = app_handle_clone5.emit("device-changed", &device) {
+                        log::error!("Failed to emit device-changed event: {}", e);
+                    }
+                }))
+            {
+                log::warn!("Failed to watch for output device changes: {}", e);
+            }
+
             // Start audio session polling with proper cancellation
             let audio_manager = state.audio_manager.clone();
             let app_handle_clone2 = app_handle.clone();
             let cancellation_token = state.cancellation_token.clone();
             let last_sessions_state = state.last_audio_sessions.clone();
+            let merge_sessions = state.merge_sessions.clone();
+            let active_mappings = state.active_mappings.clone();
+            let channel_values = state.channel_values.clone();
+            let mute_thresholds = state.mute_thresholds.clone();
+            let poll_interval_secs = state.session_poll_interval_secs.clone();
+            let session_refresh = state.session_refresh.clone();
+            let control_events = state.control_events.clone();
+            let mut saved_volumes = config.session_volumes.clone();
+
+            // Polling still runs at the configured interval, catching whatever
+            // notifications miss
+            let notifier = sessions_changed.clone();
+            if let Err(e) = state
+                .audio_manager
+                .watch_sessions(Arc::new(move || notifier.notify_one()))
+            {
+                log::warn!("Failed to watch audio sessions, polling instead: {}", e);
+            }
 
             tauri::async_runtime::spawn(async move {
+                let mut seen_sessions = HashSet::new();
+                // When the remembered volumes are next written, while they have unsaved changes
+                let mut volumes_save_due: Option<tokio::time::Instant> = None;
+
+                // Put apps back at their saved volumes right away, then again
+                // for each app that starts later
+                let mut next_poll = tokio::time::Instant::now();
+
                 loop {
                     tokio::select! {
                         _ = cancellation_token.cancelled() => {
                             // Clean shutdown
                             log::info!("Audio session polling task cancelled");
+                            if volumes_save_due.is_some() {
+                                if let Err(e) = config::save_session_volumes(&app_handle_clone2, &saved_volumes) {
+                                    log::error!("Failed to save session volumes: {}", e);
+                                }
+                            }
                             break;
                         }
-                        _ = tokio::time::sleep(tokio::time::Duration::from_secs(AUDIO_SESSION_POLL_INTERVAL_SECS)) => {
+                        _ = tokio::time::sleep_until(volumes_save_due.unwrap_or_else(tokio::time::Instant::now)),
+                            if volumes_save_due.is_some() =>
+                        {
+                            volumes_save_due = None;
+                            if let Err(e) = config::save_session_volumes(&app_handle_clone2, &saved_volumes) {
+                                log::error!("Failed to save session volumes: {}", e);
+                            }
+                        }
+                        _ = session_refresh.notified() => {
+                            // Just refreshed by hand or the interval changed, so wait a
+                            // full interval from now
+                            next_poll = tokio::time::Instant::now() + Duration::from_secs(*poll_interval_secs.read().await);
+                        }
+                        _ = sessions_changed.notified() => {
+                            // Restarted by each notification, so a burst refreshes once
+                            next_poll = tokio::time::Instant::now() + SESSION_CHANGE_DEBOUNCE;
+                        }
+                        _ = tokio::time::sleep_until(next_poll) => {
+                            next_poll = tokio::time::Instant::now() + Duration::from_secs(*poll_interval_secs.read().await);
+
                             // Get current audio sessions
-                            match audio_manager.get_audio_sessions() {
+                            let merge = *merge_sessions.read().await;
+                            match session_list(audio_manager.as_ref(), merge) {
                                 Ok(current_sessions) => {
+                                    let volumes_changed = restore_volumes(
+                                        audio_manager.as_ref(),
+                                        &current_sessions,
+                                        &active_mappings.read().await,
+                                        &mut saved_volumes,
+                                        &mut seen_sessions,
+                                    );
+                                    if volumes_changed && volumes_save_due.is_none() {
+                                        volumes_save_due = Some(tokio::time::Instant::now() + SESSION_VOLUMES_SAVE_DELAY);
+                                    }
+
                                     // Use write lock for atomic comparison and update
                                     let mut last = last_sessions_state.write().await;
                                     if *last != current_sessions {
-                                        // Update stored sessions atomically with the same lock
+                                        let _ = control_events.send(ControlEvent::AudioSessionsUpdated(current_sessions.clone()));
+                                    }
+                                    if last.is_empty() {
+                                        // Nothing to diff against, e.g. after the list
+                                        // changed shape, so send it whole
                                         *last = current_sessions.clone();
-                                        drop(last); // Release lock before emitting
+                                        drop(last);
+                                        if !current_sessions.is_empty() {
+                                            if let Err(e) = app_handle_clone2.emit("audio-sessions-updated", &current_sessions) {
+                                                log::error!("Failed to emit audio-sessions-updated event: {}", e);
+                                            }
+                                        }
+                                    } else {
+                                        let delta = audio::diff_sessions(&last, &current_sessions);
+                                        if !delta.is_empty() {
+                                            let mappings = active_mappings.read().await.clone();
+                                            let changes = mapped_app_changes(&last, &delta, &mappings);
+
+                                            // Update stored sessions atomically with the same lock
+                                            *last = current_sessions;
+                                            drop(last); // Release lock before emitting
 
-                                        // Emit update event with error handling
-                                        if let Err(e) = app_handle_clone2.emit("audio-sessions-updated", &current_sessions) {
-                                            log::error!("Failed to emit audio-sessions-updated event: {}", e);
+                                            // A relaunched app starts at the OS's remembered volume,
+                                            // so bring it back in line with its fader
+                                            let values = channel_values.read().await.clone();
+                                            let thresholds = *mute_thresholds.read().await;
+                                            for (mapping, app) in &changes.started {
+                                                log::info!("Mapped app {} appeared on channel {}", app.process_name, app.channel_id);
+                                                if let Some(value) = last_fader_value(&values, mapping) {
+                                                    if let Err(e) = reapply_fader(audio_manager.as_ref(), mapping, value, thresholds) {
+                                                        log::warn!("Failed to apply channel {} to {}: {}", app.channel_id, app.process_name, e);
+                                                    }
+                                                }
+                                                if let Err(e) = app_handle_clone2.emit("mapped-app-appeared", app) {
+                                                    log::error!("Failed to emit mapped-app-appeared event: {}", e);
+                                                }
+                                            }
+                                            for (_, app) in &changes.stopped {
+                                                log::info!("Mapped app {} disappeared from channel {}", app.process_name, app.channel_id);
+                                                if let Err(e) = app_handle_clone2.emit("mapped-app-disappeared", app) {
+                                                    log::error!("Failed to emit mapped-app-disappeared event: {}", e);
+                                                }
+                                            }
+
+                                            // Only what changed goes over IPC
+                                            if let Err(e) = app_handle_clone2.emit("audio-sessions-delta", &delta) {
+                                                log::error!("Failed to emit audio-sessions-delta event: {}", e);
+                                            }
                                         }
                                     }
                                 }
@@ -252,6 +3685,94 @@ pub fn run() {
                 }
             });
 
+            // Retarget follow-focus channels as the foreground app changes
+            let active_mappings = state.active_mappings.clone();
+            let focused_app = state.focused_app.clone();
+            let app_handle_clone6 = app_handle.clone();
+            let cancellation_token = state.cancellation_token.clone();
+
+            tauri::async_runtime::spawn(async move {
+                let mut tracker = FocusTracker::default();
+                let mut focus_timer = tokio::time::interval(focus::POLL_INTERVAL);
+                focus_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+                loop {
+                    tokio::select! {
+                        _ = cancellation_token.cancelled() => {
+                            log::info!("Focus tracking task cancelled");
+                            break;
+                        }
+                        _ = focus_timer.tick() => {
+                            let following = active_mappings
+                                .read()
+                                .await
+                                .iter()
+                                .any(|m| m.target == ChannelTarget::FollowFocus);
+                            if !following {
+                                continue;
+                            }
+
+                            let now = tokio::time::Instant::now();
+                            let foreground = focus::foreground_executable();
+                            let Some(executable) = tracker.observe(foreground.as_deref(), now) else {
+                                continue;
+                            };
+                            log::debug!("Follow-focus channels now control {}", executable);
+                            *focused_app.write().await = Some(executable.clone());
+                            if let Err(e) = app_handle_clone6.emit("focused-app-changed", executable) {
+                                log::error!("Failed to emit focused-app-changed event: {}", e);
+                            }
+                        }
+                    }
+                }
+            });
+
+            if config.control_server.enabled {
+                let control_server = config.control_server.clone();
+                let audio_manager = state.audio_manager.clone();
+                let control_events = state.control_events.clone();
+                let cancellation_token = state.cancellation_token.clone();
+
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = control::serve(&control_server, audio_manager, control_events, cancellation_token).await {
+                        log::error!("Control server stopped: {}", e);
+                    }
+                });
+            }
+
+            // Stream peak meters to the frontend
+            let audio_manager = state.audio_manager.clone();
+            let app_handle_clone3 = app_handle.clone();
+            let cancellation_token = state.cancellation_token.clone();
+            let meter = state.meter.clone();
+
+            tauri::async_runtime::spawn(async move {
+                let mut meter_timer = tokio::time::interval(PEAK_LEVEL_INTERVAL);
+                meter_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+                loop {
+                    tokio::select! {
+                        _ = cancellation_token.cancelled() => {
+                            log::info!("Peak level task cancelled");
+                            break;
+                        }
+                        _ = meter_timer.tick() => {
+                            match audio_manager.get_peak_levels() {
+                                Ok(levels) => {
+                                    let levels = scale_peak_levels(levels, *meter.read().await);
+                                    if let Err(e) = app_handle_clone3.emit("peak-levels", &levels) {
+                                        log::error!("Failed to emit peak-levels event: {}", e);
+                                    }
+                                }
+                                Err(e) => {
+                                    log::debug!("Failed to get peak levels: {}", e);
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -259,12 +3780,1168 @@ pub fn run() {
             connect_serial,
             disconnect_serial,
             get_serial_status,
+            list_connected_devices,
+            get_device_diagnostics,
+            set_update_rate,
+            set_device_led,
+            set_raw_monitor,
             get_audio_sessions,
+            get_audio_sessions_filtered,
+            get_session_icon,
+            set_session_blocklist,
+            refresh_audio_sessions,
+            set_merge_sessions,
+            set_invert_all,
+            set_soft_takeover,
+            set_motorized_faders,
+            set_unmute_on_fader_move,
+            set_volume_step,
+            set_mute_thresholds,
+            set_volume_priority,
+            set_reconnect_backoff,
+            set_meter_scale,
+            set_expose_system_sounds,
+            set_device_filter,
+            set_session_poll_interval,
+            set_all_volumes,
+            set_volume_by_name,
             set_app_volume,
             set_master_volume,
             get_master_volume,
+            set_input_volume,
+            get_input_volume,
+            get_audio_capabilities,
+            get_backend_info,
+            self_test,
+            list_output_devices,
+            set_output_device,
+            get_peak_levels,
+            mute_all,
+            unmute_all,
+            snapshot_volumes,
+            restore_snapshot,
+            solo_channel,
+            clear_solo,
             get_mixer_channels,
+            set_channel_label,
+            adjust_channel_volume,
+            set_hotkey,
+            clear_hotkey,
+            export_config,
+            import_config,
+            get_settings,
+            set_log_level,
+            get_log_path,
+            update_settings,
+            list_profiles,
+            create_profile,
+            delete_profile,
+            switch_profile,
+            save_channel_mapping,
+            identify_channels,
+            cancel_identify_channels,
+            start_channel_calibration,
+            finish_channel_calibration,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Covers the tray quit as well as closing the last window
+            if let tauri::RunEvent::Exit = event {
+                shutdown(app_handle);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Debug, PartialEq)]
+    enum Call {
+        Master(f32),
+        Input(f32),
+        App(u32, f32),
+        Device(String, f32),
+        MasterMute(bool),
+        AppMute(u32, bool),
+    }
+
+    /// Records volume writes; everything else is inert
+    #[derive(Default)]
+    struct RecordingAudioManager {
+        calls: Mutex<Vec<Call>>,
+        // Reported by `get_master_mute`, whatever was written since
+        master_muted: bool,
+        // How often the session list was asked for
+        session_reads: std::sync::atomic::AtomicUsize,
+    }
+
+    impl RecordingAudioManager {
+        fn take_calls(&self) -> Vec<Call> {
+            std::mem::take(&mut self.calls.lock().unwrap())
+        }
+
+        fn record(&self, call: Call) -> anyhow::Result<()> {
+            self.calls.lock().unwrap().push(call);
+            Ok(())
+        }
+    }
+
+    impl AudioManager for RecordingAudioManager {
+        fn get_audio_sessions(&self) -> anyhow::Result<Vec<AudioSession>> {
+            self.session_reads
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Ok(Vec::new())
+        }
+        fn set_app_volume(&self, process_id: u32, volume: Volume) -> anyhow::Result<()> {
+            self.record(Call::App(process_id, volume.as_percent()))
+        }
+        fn set_master_volume(&self, volume: Volume) -> anyhow::Result<()> {
+            self.record(Call::Master(volume.as_percent()))
+        }
+        fn get_master_volume(&self) -> anyhow::Result<f32> {
+            Ok(0.0)
+        }
+        fn set_input_volume(&self, volume: Volume) -> anyhow::Result<()> {
+            self.record(Call::Input(volume.as_percent()))
+        }
+        fn get_input_volume(&self) -> anyhow::Result<f32> {
+            Ok(0.0)
+        }
+        fn set_app_mute(&self, process_id: u32, muted: bool) -> anyhow::Result<()> {
+            self.record(Call::AppMute(process_id, muted))
+        }
+        fn set_master_mute(&self, muted: bool) -> anyhow::Result<()> {
+            self.record(Call::MasterMute(muted))
+        }
+        fn get_master_mute(&self) -> anyhow::Result<bool> {
+            Ok(self.master_muted)
+        }
+        fn set_input_mute(&self, _muted: bool) -> anyhow::Result<()> {
+            Ok(())
+        }
+        fn get_input_mute(&self) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+        fn list_output_devices(&self) -> anyhow::Result<Vec<AudioDevice>> {
+            Ok(Vec::new())
+        }
+        fn set_device_volume(&self, device_id: &str, volume: Volume) -> anyhow::Result<()> {
+            self.record(Call::Device(device_id.to_string(), volume.as_percent()))
+        }
+        fn get_device_volume(&self, _device_id: &str) -> anyhow::Result<f32> {
+            Ok(0.0)
+        }
+        fn set_device_mute(&self, _device_id: &str, _muted: bool) -> anyhow::Result<()> {
+            Ok(())
+        }
+        fn get_device_mute(&self, _device_id: &str) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+        fn supports_per_app_volume(&self) -> bool {
+            true
+        }
+        fn supports_mute(&self) -> bool {
+            true
+        }
+        fn supports_metering(&self) -> bool {
+            false
+        }
+        fn backend_name(&self) -> &'static str {
+            "recording"
+        }
+        fn get_peak_levels(&self) -> anyhow::Result<Vec<(u32, f32)>> {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Runs one write pass over fresh state, returning the calls it made
+    fn write_once(mappings: &[ChannelMapping], percentages: &[f32]) -> Vec<Call> {
+        let recorder = Arc::new(RecordingAudioManager::default());
+        let audio_manager: Arc<dyn AudioManager> = recorder.clone();
+        write_changed_volumes(
+            &audio_manager,
+            mappings,
+            percentages,
+            &mut ChannelOutputs::default(),
+            &mut VolumeRamps::default(),
+            None,
+            None,
+        );
+        recorder.take_calls()
+    }
+
+    /// Runs write passes over the same state, returning every call made
+    fn write_sequence(mappings: &[ChannelMapping], passes: &[&[f32]]) -> Vec<Call> {
+        let passes: Vec<(&[ChannelMapping], &[f32])> = passes
+            .iter()
+            .map(|&percentages| (mappings, percentages))
+            .collect();
+        write_sequence_with_mappings(&passes)
+    }
+
+    /// Like `write_sequence`, with the mappings swapped out between passes
+    fn write_sequence_with_mappings(passes: &[(&[ChannelMapping], &[f32])]) -> Vec<Call> {
+        let recorder = Arc::new(RecordingAudioManager::default());
+        let audio_manager: Arc<dyn AudioManager> = recorder.clone();
+        let mut outputs = ChannelOutputs::default();
+        let mut ramps = VolumeRamps::default();
+        for &(mappings, percentages) in passes {
+            write_changed_volumes(
+                &audio_manager,
+                mappings,
+                percentages,
+                &mut outputs,
+                &mut ramps,
+                None,
+                None,
+            );
+        }
+        recorder.take_calls()
+    }
+
+    #[test]
+    fn master_mapping_sets_master_volume() {
+        let mappings = [ChannelMapping::new(1, ChannelTarget::Master)];
+        assert_eq!(write_once(&mappings, &[40.0]), vec![Call::Master(40.0)]);
+    }
+
+    #[test]
+    fn app_mapping_sets_its_process_volume() {
+        let mappings = [ChannelMapping::new(2, ChannelTarget::App(1234))];
+        assert_eq!(
+            write_once(&mappings, &[10.0, 80.0]),
+            vec![Call::App(1234, 80.0)]
+        );
+    }
+
+    #[test]
+    fn each_target_kind_reaches_its_setter() {
+        let mappings = [
+            ChannelMapping::new(1, ChannelTarget::Input),
+            ChannelMapping::new(2, ChannelTarget::Device("speakers".to_string())),
+        ];
+        assert_eq!(
+            write_once(&mappings, &[20.0, 80.0]),
+            vec![
+                Call::Input(20.0),
+                Call::Device("speakers".to_string(), 80.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn unmapped_channels_do_nothing() {
+        let mappings = [ChannelMapping::new(3, ChannelTarget::Master)];
+        assert!(write_once(&mappings, &[10.0, 20.0]).is_empty());
+    }
+
+    #[test]
+    fn unchanged_channels_are_not_rewritten() {
+        let recorder = Arc::new(RecordingAudioManager::default());
+        let audio_manager: Arc<dyn AudioManager> = recorder.clone();
+        let mappings = [
+            ChannelMapping::new(1, ChannelTarget::Master),
+            ChannelMapping::new(2, ChannelTarget::App(1234)),
+        ];
+        let mut outputs = ChannelOutputs::default();
+        let mut ramps = VolumeRamps::default();
+
+        for percentages in [[50.0, 50.0], [50.0, 70.0]] {
+            write_changed_volumes(
+                &audio_manager,
+                &mappings,
+                &percentages,
+                &mut outputs,
+                &mut ramps,
+                None,
+                None,
+            );
+        }
+
+        assert_eq!(
+            recorder.take_calls(),
+            vec![
+                Call::Master(50.0),
+                Call::App(1234, 50.0),
+                Call::App(1234, 70.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn changed_mappings_rewrite_a_channel_that_hasnt_moved() {
+        let recorder = Arc::new(RecordingAudioManager::default());
+        let audio_manager: Arc<dyn AudioManager> = recorder.clone();
+        let mut outputs = ChannelOutputs::default();
+        let mut ramps = VolumeRamps::default();
+
+        for target in [
+            ChannelTarget::Master,
+            ChannelTarget::Master,
+            ChannelTarget::App(1234),
+        ] {
+            write_changed_volumes(
+                &audio_manager,
+                &[ChannelMapping::new(1, target)],
+                &[50.0],
+                &mut outputs,
+                &mut ramps,
+                None,
+                None,
+            );
+        }
+
+        assert_eq!(
+            recorder.take_calls(),
+            vec![Call::Master(50.0), Call::App(1234, 50.0)]
+        );
+    }
+
+    fn mute_at_zero(channel_id: usize, target: ChannelTarget) -> ChannelMapping {
+        ChannelMapping {
+            mute_at_zero: true,
+            ..ChannelMapping::new(channel_id, target)
+        }
+    }
+
+    #[test]
+    fn mute_at_zero_mutes_instead_of_writing_zero() {
+        let mappings = [mute_at_zero(1, ChannelTarget::Master)];
+        assert_eq!(write_once(&mappings, &[0.0]), vec![Call::MasterMute(true)]);
+    }
+
+    #[test]
+    fn without_mute_at_zero_the_bottom_is_just_zero_volume() {
+        let mappings = [ChannelMapping::new(1, ChannelTarget::Master)];
+        assert_eq!(write_once(&mappings, &[0.0]), vec![Call::Master(0.0)]);
+    }
+
+    #[test]
+    fn leaving_zero_sets_volume_then_unmutes() {
+        let mappings = [mute_at_zero(1, ChannelTarget::App(1234))];
+        assert_eq!(
+            write_sequence(&mappings, &[&[0.0], &[40.0], &[50.0]]),
+            vec![
+                Call::AppMute(1234, true),
+                Call::App(1234, 40.0),
+                Call::AppMute(1234, false),
+                Call::App(1234, 50.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn mute_at_zero_only_calls_mute_on_transitions() {
+        let mappings = [
+            mute_at_zero(1, ChannelTarget::Master),
+            ChannelMapping::new(2, ChannelTarget::App(1234)),
+        ];
+        // Channel 2 moving re-runs the pass while channel 1 sits at zero
+        assert_eq!(
+            write_sequence(&mappings, &[&[0.0, 20.0], &[0.0, 40.0], &[20.0, 40.0]]),
+            vec![
+                Call::MasterMute(true),
+                Call::App(1234, 20.0),
+                Call::App(1234, 40.0),
+                Call::Master(20.0),
+                Call::MasterMute(false)
+            ]
+        );
+    }
+
+    #[test]
+    fn swapping_a_muted_mapping_unmutes_its_old_target() {
+        let muting = [mute_at_zero(1, ChannelTarget::Master)];
+        let swapped = [ChannelMapping::new(1, ChannelTarget::App(1234))];
+        assert_eq!(
+            write_sequence_with_mappings(&[(&muting, &[0.0]), (&swapped, &[0.0])]),
+            vec![
+                Call::MasterMute(true),
+                Call::MasterMute(false),
+                Call::App(1234, 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn mute_at_zero_ignores_a_fader_dithering_at_the_bottom() {
+        let mappings = [mute_at_zero(1, ChannelTarget::Master)];
+        assert_eq!(
+            write_sequence(
+                &mappings,
+                &[
+                    &[0.0],
+                    &[2.0],
+                    &[0.0],
+                    &[2.0],
+                    &[0.0],
+                    &[4.0],
+                    &[2.0],
+                    &[4.0]
+                ]
+            ),
+            vec![
+                Call::MasterMute(true),
+                Call::Master(4.0),
+                Call::MasterMute(false),
+                Call::Master(2.0),
+                Call::Master(4.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn button_debouncer_ignores_bounces_within_the_interval() {
+        let mut debouncer = ButtonDebouncer::default();
+        let interval = Duration::from_millis(50);
+        let start = tokio::time::Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        assert!(debouncer
+            .presses(&[false, false], interval, at(0))
+            .is_empty());
+        assert_eq!(debouncer.presses(&[true, false], interval, at(10)), vec![1]);
+        // Contact bounce right after the press
+        assert!(debouncer
+            .presses(&[false, false], interval, at(15))
+            .is_empty());
+        assert!(debouncer
+            .presses(&[true, false], interval, at(20))
+            .is_empty());
+        assert!(debouncer
+            .presses(&[false, false], interval, at(70))
+            .is_empty());
+        assert_eq!(
+            debouncer.presses(&[true, true], interval, at(130)),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn fader_move_unmutes_a_muted_target_only_when_enabled() {
+        let mappings = [ChannelMapping::new(1, ChannelTarget::Master)];
+        for (enabled, expected) in [
+            (false, vec![Call::Master(40.0)]),
+            (true, vec![Call::Master(40.0), Call::MasterMute(false)]),
+        ] {
+            let recorder = Arc::new(RecordingAudioManager {
+                master_muted: true,
+                ..Default::default()
+            });
+            let audio_manager: Arc<dyn AudioManager> = recorder.clone();
+            let settings = WriteSettings {
+                unmute_on_fader_move: enabled,
+                ..Default::default()
+            };
+            FaderWriter::default().write(&audio_manager, &mappings, &[40.0], &[], None, settings);
+            assert_eq!(recorder.take_calls(), expected);
+        }
+    }
+
+    #[test]
+    fn inverted_mute_at_zero_mutes_at_the_top() {
+        let mappings = [ChannelMapping {
+            inverted: true,
+            ..mute_at_zero(1, ChannelTarget::Master)
+        }];
+        assert_eq!(
+            write_once(&mappings, &[100.0]),
+            vec![Call::MasterMute(true)]
+        );
+        assert_eq!(write_once(&mappings, &[0.0]), vec![Call::Master(100.0)]);
+    }
+
+    #[test]
+    fn invert_all_composes_with_channel_invert() {
+        let plain = ChannelMapping::new(1, ChannelTarget::Master);
+        let inverted = ChannelMapping {
+            inverted: true,
+            ..ChannelMapping::new(2, ChannelTarget::Input)
+        };
+        let mappings = [plain, inverted];
+
+        assert_eq!(
+            write_once(&mappings, &orient_faders(vec![20.0, 20.0], false)),
+            vec![Call::Master(20.0), Call::Input(80.0)]
+        );
+        // Two inverts cancel out
+        assert_eq!(
+            write_once(&mappings, &orient_faders(vec![20.0, 20.0], true)),
+            vec![Call::Master(80.0), Call::Input(20.0)]
+        );
+    }
+
+    #[test]
+    fn crossfade_mapping_splits_fader_between_apps() {
+        let mapping = ChannelMapping::new(
+            1,
+            ChannelTarget::Crossfade {
+                app_a: 10,
+                app_b: 20,
+                equal_power: false,
+            },
+        );
+        assert_eq!(
+            write_once(std::slice::from_ref(&mapping), &[0.0]),
+            vec![Call::App(10, 100.0), Call::App(20, 0.0)]
+        );
+        assert_eq!(
+            write_once(std::slice::from_ref(&mapping), &[50.0]),
+            vec![Call::App(10, 50.0), Call::App(20, 50.0)]
+        );
+        assert_eq!(
+            write_once(&[mapping], &[100.0]),
+            vec![Call::App(10, 0.0), Call::App(20, 100.0)]
+        );
+    }
+
+    #[test]
+    fn follow_focus_mapping_waits_for_a_focused_app() {
+        let mut mapping = ChannelMapping::new(1, ChannelTarget::FollowFocus);
+        mapping.process_names = vec!["ignored.exe".to_string()];
+
+        assert!(write_once(&focus::resolve(&[mapping.clone()], None), &[40.0]).is_empty());
+
+        // Every session of the focused executable follows the fader
+        let audio_manager: Arc<dyn AudioManager> = Arc::new(SimAudioManager::new());
+        let resolved = focus::resolve(&[mapping], Some("chrome.exe"));
+        FaderWriter::default().write(
+            &audio_manager,
+            &resolved,
+            &[40.0],
+            &audio_manager.get_audio_sessions().unwrap(),
+            None,
+            WriteSettings::default(),
+        );
+        let chrome: Vec<f32> = audio_manager
+            .get_audio_sessions()
+            .unwrap()
+            .into_iter()
+            .filter(|s| s.process_name == "chrome.exe")
+            .map(|s| s.volume.round())
+            .collect();
+        assert_eq!(chrome, vec![40.0, 40.0]);
+    }
+
+    #[test]
+    fn ui_priority_holds_off_the_fader_for_a_moment() {
+        let mut arbiter = VolumeArbiter::new(VolumePriority::Ui);
+        let start = tokio::time::Instant::now();
+
+        assert!(arbiter.claim(&[1], VolumeSource::Ui, start));
+        assert!(!arbiter.claim(
+            &[1],
+            VolumeSource::Fader,
+            start + Duration::from_millis(100)
+        ));
+        // Other channels are unaffected
+        assert!(arbiter.claim(&[2], VolumeSource::Fader, start));
+        assert!(arbiter.claim(&[1], VolumeSource::Fader, start + VOLUME_CONFLICT_WINDOW));
+        // The UI always gets through, and the fader took the channel back last
+        assert!(arbiter.claim(&[1], VolumeSource::Ui, start + VOLUME_CONFLICT_WINDOW));
+    }
+
+    #[test]
+    fn physical_priority_refuses_the_ui_while_a_fader_moves() {
+        let mut arbiter = VolumeArbiter::new(VolumePriority::Physical);
+        let start = tokio::time::Instant::now();
+
+        assert!(arbiter.claim(&[1], VolumeSource::Fader, start));
+        // Any blocked channel refuses the whole change, recording none of it
+        assert!(!arbiter.claim(
+            &[2, 1],
+            VolumeSource::Ui,
+            start + Duration::from_millis(500)
+        ));
+        assert!(arbiter.claim(&[2], VolumeSource::Fader, start));
+        assert!(arbiter.claim(&[1], VolumeSource::Ui, start + VOLUME_CONFLICT_WINDOW));
+    }
+
+    #[test]
+    fn channels_controlling_finds_master_and_app_mappings() {
+        let mut grouped = ChannelMapping::new(2, ChannelTarget::App(0));
+        grouped.process_names = vec!["chrome.exe".to_string()];
+        let mappings = vec![
+            ChannelMapping::new(1, ChannelTarget::Master),
+            grouped,
+            ChannelMapping::new(3, ChannelTarget::App(7)),
+        ];
+        let session = |process_id: u32, name: &str| AudioSession {
+            process_id,
+            process_name: name.to_string(),
+            display_name: name.to_string(),
+            volume: 100.0,
+            is_muted: false,
+            session_count: 1,
+        };
+        let sessions = vec![session(5, "chrome.exe"), session(7, "game.exe")];
+
+        assert_eq!(channels_controlling(&mappings, &sessions, 0), vec![1]);
+        assert_eq!(channels_controlling(&mappings, &sessions, 5), vec![2]);
+        assert_eq!(channels_controlling(&mappings, &sessions, 7), vec![3]);
+        assert!(channels_controlling(&mappings, &sessions, 9).is_empty());
+    }
+
+    #[test]
+    fn restore_volumes_matches_sessions_by_process_name() {
+        use audio::SavedVolume;
+
+        let audio_manager = SimAudioManager::new();
+        let saved = |process_id: u32, process_name: &str, volume: f32| SavedVolume {
+            process_id,
+            process_name: process_name.to_string(),
+            volume,
+        };
+        let snapshot = vec![
+            saved(0, "", 40.0),
+            saved(1234, "chrome.exe", 30.0),
+            // Spotify restarted under a new PID
+            saved(4321, "Spotify.exe", 20.0),
+            // A closed game whose PID Discord now has
+            saved(9012, "game.exe", 10.0),
+        ];
+
+        audio_manager.restore_volumes(&snapshot).unwrap();
+
+        let volumes: Vec<(u32, f32)> = audio_manager
+            .get_audio_sessions()
+            .unwrap()
+            .iter()
+            .map(|s| (s.process_id, s.volume))
+            .collect();
+        assert_eq!(
+            volumes,
+            vec![
+                (0, 40.0),
+                (1234, 30.0),
+                (1235, 50.0),
+                (5678, 20.0),
+                (9012, 80.0)
+            ]
+        );
+    }
+
+    fn volume_of(audio_manager: &dyn AudioManager, process_id: u32) -> f32 {
+        audio_manager
+            .get_audio_sessions()
+            .unwrap()
+            .into_iter()
+            .find(|s| s.process_id == process_id)
+            .unwrap()
+            .volume
+    }
+
+    #[test]
+    fn new_sessions_get_their_saved_volume_unless_a_fader_drives_them() {
+        let audio_manager = SimAudioManager::new();
+        let mappings = [ChannelMapping::new(2, ChannelTarget::App(9012))];
+        let mut saved = HashMap::from([
+            ("spotify.exe".to_string(), 20.0),
+            ("discord.exe".to_string(), 30.0),
+        ]);
+        let mut seen = HashSet::new();
+
+        let sessions = audio_manager.get_audio_sessions().unwrap();
+        assert!(restore_volumes(
+            &audio_manager,
+            &sessions,
+            &mappings,
+            &mut saved,
+            &mut seen
+        ));
+
+        assert_eq!(volume_of(&audio_manager, 5678), 20.0);
+        assert_eq!(volume_of(&audio_manager, 9012), 80.0);
+        // Recorded straight away, apart from the fader's app
+        assert_eq!(saved.get("chrome.exe"), Some(&50.0));
+        assert_eq!(saved.get("discord.exe"), Some(&30.0));
+
+        // Nothing moved, so there is nothing to save
+        let sessions = audio_manager.get_audio_sessions().unwrap();
+        assert!(!restore_volumes(
+            &audio_manager,
+            &sessions,
+            &mappings,
+            &mut saved,
+            &mut seen
+        ));
+    }
+
+    #[test]
+    fn a_session_seen_again_under_a_freed_pid_is_restored() {
+        let audio_manager = SimAudioManager::new();
+        let mut saved = HashMap::from([("spotify.exe".to_string(), 20.0)]);
+        let mut seen = HashSet::new();
+
+        let sessions = audio_manager.get_audio_sessions().unwrap();
+        restore_volumes(&audio_manager, &sessions, &[], &mut saved, &mut seen);
+        // Spotify closes, then comes back under the same PID at another volume
+        let without_spotify: Vec<AudioSession> = sessions
+            .iter()
+            .filter(|s| s.process_id != 5678)
+            .cloned()
+            .collect();
+        restore_volumes(&audio_manager, &without_spotify, &[], &mut saved, &mut seen);
+        audio_manager
+            .set_app_volume(5678, Volume::from_percent(90.0).unwrap())
+            .unwrap();
+
+        let sessions = audio_manager.get_audio_sessions().unwrap();
+        restore_volumes(&audio_manager, &sessions, &[], &mut saved, &mut seen);
+        assert_eq!(volume_of(&audio_manager, 5678), 20.0);
+    }
+
+    #[test]
+    fn mapped_app_changes_reports_only_mapped_sessions() {
+        let mut grouped = ChannelMapping::new(2, ChannelTarget::App(0));
+        grouped.process_names = vec!["spotify.exe".to_string()];
+        let mappings = vec![ChannelMapping::new(1, ChannelTarget::Master), grouped];
+        let session = |process_id: u32, name: &str| AudioSession {
+            process_id,
+            process_name: name.to_string(),
+            display_name: name.to_string(),
+            volume: 100.0,
+            is_muted: false,
+            session_count: 1,
+        };
+        let previous = vec![session(5, "Spotify.exe"), session(6, "game.exe")];
+        let current = vec![session(7, "Spotify.exe"), session(8, "chat.exe")];
+        let delta = audio::diff_sessions(&previous, &current);
+
+        let changes = mapped_app_changes(&previous, &delta, &mappings);
+        let ids = |changes: &[(&ChannelMapping, MappedApp)]| -> Vec<(usize, u32)> {
+            changes
+                .iter()
+                .map(|(_, app)| (app.channel_id, app.process_id))
+                .collect()
+        };
+        assert_eq!(ids(&changes.started), vec![(2, 7)]);
+        assert_eq!(ids(&changes.stopped), vec![(2, 5)]);
+    }
+
+    #[test]
+    fn app_mapping_is_rematched_by_name_when_its_session_goes() {
+        let audio_manager = SimAudioManager::new();
+        let mut mapping = ChannelMapping::new(2, ChannelTarget::App(4321));
+        mapping.process_name = Some("spotify.exe".to_string());
+
+        let sessions = audio_manager.get_audio_sessions().unwrap();
+        let rematch = apply_mapping_rematching(&audio_manager, &mapping, 30.0, &sessions)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (rematch.old_process_id, rematch.new_process_id),
+            (4321, 5678)
+        );
+        let spotify = audio_manager
+            .get_audio_sessions()
+            .unwrap()
+            .into_iter()
+            .find(|s| s.process_id == 5678)
+            .unwrap();
+        assert_eq!(spotify.volume, 30.0);
+
+        let mut mappings = vec![mapping.clone()];
+        assert!(rematch.apply(&mut mappings));
+        assert_eq!(mappings[0].target, ChannelTarget::App(5678));
+
+        // Without a name there is nothing to match on
+        mapping.process_name = None;
+        assert!(apply_mapping_rematching(&audio_manager, &mapping, 30.0, &sessions).is_err());
+    }
+
+    #[test]
+    fn rematching_only_searches_the_given_sessions() {
+        let audio_manager = SimAudioManager::new();
+        let mut mapping = ChannelMapping::new(2, ChannelTarget::App(4321));
+        mapping.process_name = Some("spotify.exe".to_string());
+
+        // Spotify is running, but the poller hasn't listed it yet
+        assert!(apply_mapping_rematching(&audio_manager, &mapping, 30.0, &[]).is_err());
+    }
+
+    #[test]
+    fn merged_app_mapping_writes_every_process_of_its_executable() {
+        let audio_manager: Arc<dyn AudioManager> = Arc::new(SimAudioManager::new());
+        let mut mapping = ChannelMapping::new(1, ChannelTarget::App(1234));
+        mapping.process_name = Some("chrome.exe".to_string());
+
+        let volumes = |audio_manager: &Arc<dyn AudioManager>| -> Vec<f32> {
+            audio_manager
+                .get_audio_sessions()
+                .unwrap()
+                .into_iter()
+                .filter(|s| s.process_name == "chrome.exe")
+                .map(|s| s.volume.round())
+                .collect()
+        };
+
+        let raw = expand_merged_apps(vec![mapping.clone()], false);
+        FaderWriter::default().write(
+            &audio_manager,
+            &raw,
+            &[40.0],
+            &audio_manager.get_audio_sessions().unwrap(),
+            None,
+            WriteSettings::default(),
+        );
+        assert_eq!(volumes(&audio_manager), vec![40.0, 50.0]);
+
+        let merged = expand_merged_apps(vec![mapping], true);
+        FaderWriter::default().write(
+            &audio_manager,
+            &merged,
+            &[30.0],
+            &audio_manager.get_audio_sessions().unwrap(),
+            None,
+            WriteSettings::default(),
+        );
+        assert_eq!(volumes(&audio_manager), vec![30.0, 30.0]);
+    }
+
+    #[test]
+    fn grouped_writes_match_names_against_the_given_sessions() {
+        let recorder = Arc::new(RecordingAudioManager::default());
+        let audio_manager: Arc<dyn AudioManager> = recorder.clone();
+        let sessions = SimAudioManager::new().get_audio_sessions().unwrap();
+        let mut mapping = ChannelMapping::new(1, ChannelTarget::AppByName);
+        mapping.process_names = vec!["chrome.exe".to_string()];
+        let settings = WriteSettings {
+            unmute_on_fader_move: true,
+            ..Default::default()
+        };
+
+        FaderWriter::default().write(
+            &audio_manager,
+            &[mapping],
+            &[40.0],
+            &sessions,
+            None,
+            settings,
+        );
+
+        assert_eq!(
+            recorder.take_calls(),
+            vec![Call::App(1234, 40.0), Call::App(1235, 40.0)]
+        );
+        assert_eq!(
+            recorder
+                .session_reads
+                .load(std::sync::atomic::Ordering::Relaxed),
+            0
+        );
+    }
+
+    #[test]
+    fn fader_writes_are_reported_for_each_process_they_reach() {
+        let audio_manager: Arc<dyn AudioManager> = Arc::new(SimAudioManager::new());
+        let sessions = audio_manager.get_audio_sessions().unwrap();
+        let mut chrome = ChannelMapping::new(2, ChannelTarget::App(1234));
+        chrome.process_name = Some("chrome.exe".to_string());
+        let mappings = expand_merged_apps(
+            vec![ChannelMapping::new(1, ChannelTarget::Master), chrome],
+            true,
+        );
+        let mut writer = FaderWriter::default();
+
+        writer.write(
+            &audio_manager,
+            &mappings,
+            &[40.0, 60.0],
+            &audio_manager.get_audio_sessions().unwrap(),
+            None,
+            WriteSettings::default(),
+        );
+        let changes: Vec<(u32, f32)> = fader_volume_changes(writer.written(), &sessions)
+            .into_iter()
+            .map(|c| (c.process_id, c.volume.round()))
+            .collect();
+        assert_eq!(changes, vec![(0, 40.0), (1234, 60.0), (1235, 60.0)]);
+
+        // Nothing moved, so nothing is reported
+        writer.write(
+            &audio_manager,
+            &mappings,
+            &[40.0, 60.0],
+            &audio_manager.get_audio_sessions().unwrap(),
+            None,
+            WriteSettings::default(),
+        );
+        assert!(fader_volume_changes(writer.written(), &sessions).is_empty());
+    }
+
+    fn ramped(channel_id: usize, target: ChannelTarget) -> ChannelMapping {
+        ChannelMapping {
+            ramp_ms: 50,
+            ..ChannelMapping::new(channel_id, target)
+        }
+    }
+
+    fn session_volumes(audio_manager: &dyn AudioManager, name: &str) -> Vec<f32> {
+        audio_manager
+            .get_audio_sessions()
+            .unwrap()
+            .into_iter()
+            .filter(|s| s.process_name == name)
+            .map(|s| s.volume.round())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn ramps_glide_every_session_of_their_app_to_the_target() {
+        let audio_manager: Arc<dyn AudioManager> = Arc::new(SimAudioManager::new());
+        let mut mapping = ramped(1, ChannelTarget::AppByName);
+        mapping.process_names = vec!["chrome.exe".to_string()];
+        let mut ramps = VolumeRamps::default();
+
+        ramps.start(audio_manager.clone(), 1, 20.0, vec![(mapping, 20.0)]);
+        assert!(ramps.is_running(1));
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        assert_eq!(
+            session_volumes(audio_manager.as_ref(), "chrome.exe"),
+            vec![20.0, 20.0]
+        );
+        assert!(!ramps.is_running(1));
+    }
+
+    #[tokio::test]
+    async fn a_new_target_takes_over_the_running_glide() {
+        let audio_manager: Arc<dyn AudioManager> = Arc::new(SimAudioManager::new());
+        let mapping = ramped(1, ChannelTarget::App(5678));
+        let mut ramps = VolumeRamps::default();
+
+        ramps.start(audio_manager.clone(), 1, 0.0, vec![(mapping.clone(), 0.0)]);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        ramps.start(audio_manager.clone(), 1, 90.0, vec![(mapping, 90.0)]);
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        assert_eq!(
+            session_volumes(audio_manager.as_ref(), "Spotify.exe"),
+            vec![90.0]
+        );
+        assert!(!ramps.is_running(1));
+    }
+
+    #[tokio::test]
+    async fn soloing_stops_glides_on_ducked_apps() {
+        let audio_manager: Arc<dyn AudioManager> = Arc::new(SimAudioManager::new());
+        let solo = Arc::new(RwLock::new(Some(SoloState {
+            channel_id: 1,
+            saved_volumes: Vec::new(),
+            moved_channels: HashMap::new(),
+        })));
+        let mut writer = FaderWriter::with_solo(solo.clone());
+
+        writer.ramps.start(
+            audio_manager.clone(),
+            2,
+            10.0,
+            vec![(ramped(2, ChannelTarget::App(5678)), 10.0)],
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Left ducked, with the glide's end kept for when the solo clears
+        assert_eq!(
+            session_volumes(audio_manager.as_ref(), "Spotify.exe"),
+            vec![65.0]
+        );
+        assert!(!writer.ramps.is_running(2));
+        let solo = solo.read().await;
+        assert_eq!(solo.as_ref().unwrap().moved_channels.get(&2), Some(&10.0));
+    }
+
+    #[test]
+    fn mixer_channels_are_numbered_on_across_devices() {
+        let counts = HashMap::from([("COM4".to_string(), 2), ("COM3".to_string(), 3)]);
+        let values = HashMap::from([("COM4".to_string(), vec![10.0, 20.0])]);
+        let labels = PerDevice {
+            shared: HashMap::from([(1, "Game".to_string())]),
+            by_port: HashMap::from([(
+                "COM4".to_string(),
+                HashMap::from([(1, "Chat".to_string())]),
+            )]),
+        };
+
+        let channels = mixer_channels(&counts, &values, &labels);
+        let numbering: Vec<(usize, usize, Option<&str>)> = channels
+            .iter()
+            .map(|c| (c.id, c.channel, c.device.as_deref()))
+            .collect();
+        assert_eq!(
+            numbering,
+            vec![
+                (1, 1, Some("COM3")),
+                (2, 2, Some("COM3")),
+                (3, 3, Some("COM3")),
+                (4, 1, Some("COM4")),
+                (5, 2, Some("COM4")),
+            ]
+        );
+        let labels_shown: Vec<Option<&str>> = channels.iter().map(|c| c.label.as_deref()).collect();
+        assert_eq!(
+            labels_shown,
+            vec![Some("Game"), None, None, Some("Chat"), None]
+        );
+        let values_shown: Vec<f32> = channels.iter().map(|c| c.value).collect();
+        assert_eq!(values_shown, vec![0.0, 0.0, 0.0, 10.0, 20.0]);
+
+        let unconnected = mixer_channels(&HashMap::new(), &values, &labels);
+        assert_eq!(unconnected.len(), DEFAULT_CHANNEL_COUNT);
+        assert!(unconnected
+            .iter()
+            .all(|c| c.device.is_none() && c.id == c.channel));
+    }
+
+    /// Feeds fader values to one channel, returning which were applied
+    fn takeover_passes(
+        takeover: &mut SoftTakeover,
+        audio_manager: &dyn AudioManager,
+        mappings: &[ChannelMapping],
+        values: &[f32],
+    ) -> Vec<bool> {
+        values
+            .iter()
+            .map(|&value| takeover.engage(audio_manager, 1, mappings, value))
+            .collect()
+    }
+
+    #[test]
+    fn takeover_engages_when_the_fader_crosses_from_below() {
+        let audio_manager = SimAudioManager::new();
+        let mappings = [ChannelMapping::new(1, ChannelTarget::Master)];
+        let mut takeover = SoftTakeover::default();
+
+        assert_eq!(
+            takeover_passes(
+                &mut takeover,
+                &audio_manager,
+                &mappings,
+                &[10.0, 50.0, 80.0, 60.0]
+            ),
+            vec![false, false, true, true]
+        );
+    }
+
+    #[test]
+    fn takeover_engages_when_the_fader_crosses_from_above() {
+        let audio_manager = SimAudioManager::new();
+        let mappings = [ChannelMapping::new(1, ChannelTarget::Master)];
+        let mut takeover = SoftTakeover::default();
+
+        assert_eq!(
+            takeover_passes(
+                &mut takeover,
+                &audio_manager,
+                &mappings,
+                &[95.0, 90.0, 60.0]
+            ),
+            vec![false, false, true]
+        );
+    }
+
+    #[test]
+    fn takeover_engages_within_tolerance_of_the_target() {
+        let audio_manager = SimAudioManager::new();
+        let mappings = [ChannelMapping::new(1, ChannelTarget::Master)];
+        let mut takeover = SoftTakeover::default();
+
+        assert_eq!(
+            takeover_passes(&mut takeover, &audio_manager, &mappings, &[74.0, 10.0]),
+            vec![true, true]
+        );
+    }
+
+    #[test]
+    fn takeover_rearms_when_the_mappings_change() {
+        let audio_manager = SimAudioManager::new();
+        let master = [ChannelMapping::new(1, ChannelTarget::Master)];
+        let spotify = [ChannelMapping::new(1, ChannelTarget::App(5678))];
+        let mut takeover = SoftTakeover::default();
+
+        assert_eq!(
+            takeover_passes(&mut takeover, &audio_manager, &master, &[75.0, 10.0]),
+            vec![true, true]
+        );
+        // Spotify sits at 65, so the fader has to come back up to it
+        assert_eq!(
+            takeover_passes(&mut takeover, &audio_manager, &spotify, &[10.0, 70.0]),
+            vec![false, true]
+        );
+    }
+
+    #[test]
+    fn takeover_reads_the_target_volume_once_per_arming() {
+        let audio_manager = SimAudioManager::new();
+        let mappings = [ChannelMapping::new(1, ChannelTarget::Master)];
+        let mut takeover = SoftTakeover::default();
+
+        assert!(!takeover.engage(&audio_manager, 1, &mappings, 10.0));
+        audio_manager
+            .set_master_volume(Volume::from_percent(20.0).unwrap())
+            .unwrap();
+        // Still waiting for the 75% it read when armed
+        assert!(!takeover.engage(&audio_manager, 1, &mappings, 50.0));
+        assert!(takeover.engage(&audio_manager, 1, &mappings, 80.0));
+    }
+
+    #[test]
+    fn crossfade_takeover_engages_right_away() {
+        let audio_manager = SimAudioManager::new();
+        let mappings = [ChannelMapping::new(
+            1,
+            ChannelTarget::Crossfade {
+                app_a: 1234,
+                app_b: 5678,
+                equal_power: false,
+            },
+        )];
+        let mut takeover = SoftTakeover::default();
+
+        assert_eq!(
+            takeover_passes(&mut takeover, &audio_manager, &mappings, &[10.0, 90.0]),
+            vec![true, true]
+        );
+    }
+
+    #[test]
+    fn switching_the_output_device_moves_the_default() {
+        let audio_manager = SimAudioManager::new();
+        audio_manager
+            .set_default_output_device("sim-headphones")
+            .unwrap();
+
+        let defaults: Vec<String> = audio_manager
+            .list_output_devices()
+            .unwrap()
+            .into_iter()
+            .filter(|device| device.is_default)
+            .map(|device| device.id)
+            .collect();
+        assert_eq!(defaults, vec!["sim-headphones".to_string()]);
+        assert!(audio_manager.set_default_output_device("nowhere").is_err());
+        // Backends that can't switch say so
+        assert!(RecordingAudioManager::default()
+            .set_default_output_device("sim-speakers")
+            .is_err());
+    }
+
+    #[test]
+    fn last_fader_value_only_reads_devices_the_mapping_listens_to() {
+        let values = HashMap::from([
+            ("COM3".to_string(), vec![10.0, 20.0]),
+            ("COM4".to_string(), vec![30.0, 40.0]),
+        ]);
+        let mut mapping = ChannelMapping::new(2, ChannelTarget::Master);
+        assert_eq!(last_fader_value(&values, &mapping), Some(20.0));
+
+        mapping.device = Some("COM4".to_string());
+        assert_eq!(last_fader_value(&values, &mapping), Some(40.0));
+
+        mapping.device = Some("COM9".to_string());
+        assert_eq!(last_fader_value(&values, &mapping), None);
+    }
 }
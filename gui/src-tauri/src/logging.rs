@@ -0,0 +1,64 @@
+use anyhow::Result;
+use log::LevelFilter;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
+
+/// Base name of the log file in the app log directory
+const LOG_FILE_NAME: &str = "pc-audio-mixer";
+/// Size at which the log file is rotated, keeping one previous file
+const MAX_LOG_FILE_BYTES: u128 = 2 * 1024 * 1024;
+
+/// Most verbose level written to the log
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Error => LevelFilter::Error,
+            LogLevel::Warn => LevelFilter::Warn,
+            LogLevel::Info => LevelFilter::Info,
+            LogLevel::Debug => LevelFilter::Debug,
+            LogLevel::Trace => LevelFilter::Trace,
+        }
+    }
+}
+
+/// Logs to stdout and a rotating file. The plugin itself passes everything;
+/// `apply_level` sets the real filter, so it can change at runtime.
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri_plugin_log::Builder::new()
+        .level(LevelFilter::Trace)
+        .targets([
+            Target::new(TargetKind::Stdout),
+            Target::new(TargetKind::LogDir {
+                file_name: Some(LOG_FILE_NAME.to_string()),
+            }),
+        ])
+        .max_file_size(MAX_LOG_FILE_BYTES)
+        .rotation_strategy(RotationStrategy::KeepOne)
+        .build()
+}
+
+pub fn apply_level(level: LogLevel) {
+    log::set_max_level(level.filter());
+}
+
+/// Where the current log file is written, for attaching to bug reports
+pub fn log_path(app_handle: &AppHandle) -> Result<PathBuf> {
+    Ok(app_handle
+        .path()
+        .app_log_dir()?
+        .join(format!("{}.log", LOG_FILE_NAME)))
+}
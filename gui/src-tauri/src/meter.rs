@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+/// Lowest level reported in decibel mode; silence reads as this instead of -inf
+pub const METER_FLOOR_DB: f32 = -96.0;
+
+// Loudness doubles roughly every 10 dB, so perceived loudness grows as
+// amplitude^(20 * log10(2) / 10)
+const LOUDNESS_EXPONENT: f32 = 0.602_06;
+
+/// Units the peak meters are reported in
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MeterScale {
+    /// Raw peak scalar in `0.0..=1.0`
+    #[default]
+    Linear,
+    /// Decibels relative to full scale, from `METER_FLOOR_DB` up to 0
+    Decibel,
+}
+
+/// How peak levels are converted before they are sent to the UI
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MeterSettings {
+    pub scale: MeterScale,
+    /// Bend the reading towards perceived loudness before scaling
+    pub weighted: bool,
+}
+
+impl MeterSettings {
+    pub fn apply(self, peak: f32) -> f32 {
+        let peak = peak.clamp(0.0, 1.0);
+        let peak = if self.weighted {
+            perceptual_weight(peak)
+        } else {
+            peak
+        };
+        match self.scale {
+            MeterScale::Linear => peak,
+            MeterScale::Decibel => to_dbfs(peak),
+        }
+    }
+}
+
+/// Converts a peak scalar to dBFS, floored at `METER_FLOOR_DB`
+pub fn to_dbfs(peak: f32) -> f32 {
+    if peak <= 0.0 {
+        return METER_FLOOR_DB;
+    }
+    (20.0 * peak.log10()).clamp(METER_FLOOR_DB, 0.0)
+}
+
+/// Maps a peak scalar to relative loudness, so a meter at half height sounds
+/// half as loud rather than measuring half the amplitude
+pub fn perceptual_weight(peak: f32) -> f32 {
+    peak.clamp(0.0, 1.0).powf(LOUDNESS_EXPONENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_scale_is_zero_dbfs() {
+        assert!(to_dbfs(1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn half_scale_is_about_minus_six_dbfs() {
+        assert!((to_dbfs(0.5) + 6.0206).abs() < 1e-3);
+        assert!((to_dbfs(0.1) + 20.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn silence_reads_as_the_floor() {
+        assert_eq!(to_dbfs(0.0), METER_FLOOR_DB);
+        assert_eq!(to_dbfs(-1.0), METER_FLOOR_DB);
+        assert_eq!(to_dbfs(1e-9), METER_FLOOR_DB);
+    }
+
+    #[test]
+    fn linear_scale_passes_peaks_through() {
+        let settings = MeterSettings::default();
+        assert_eq!(settings.apply(0.5), 0.5);
+        assert_eq!(settings.apply(1.5), 1.0);
+    }
+
+    #[test]
+    fn ten_db_down_sounds_half_as_loud() {
+        // -10 dBFS
+        let peak = 10f32.powf(-0.5);
+        assert!((perceptual_weight(peak) - 0.5).abs() < 1e-3);
+        assert!(perceptual_weight(0.0).abs() < 1e-6);
+        assert!((perceptual_weight(1.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn weighting_raises_quiet_readings() {
+        for scale in [MeterScale::Linear, MeterScale::Decibel] {
+            let plain = MeterSettings {
+                scale,
+                weighted: false,
+            };
+            let weighted = MeterSettings {
+                scale,
+                weighted: true,
+            };
+            assert!(weighted.apply(0.25) > plain.apply(0.25), "{:?}", scale);
+            assert!((weighted.apply(1.0) - plain.apply(1.0)).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::{DeviceInfo, PotentiometerData};
+
+/// First byte of every binary frame
+pub const BINARY_FRAME_SYNC: u8 = 0xA5;
+// Upper bound on channels per frame, so a corrupt count can't stall the decoder
+const MAX_BINARY_CHANNELS: usize = 16;
+/// Trailing checksum field of a JSON frame, covering every byte before it
+const JSON_CRC_FIELD: &str = ",\"crc\":";
+
+/// `device` value in the firmware's reply to `ID?`
+pub const DEVICE_ID: &str = "pc-audio-mixer";
+/// Start of the firmware's reply to `ID?`
+const DEVICE_ID_PREFIX: &str = "{\"device\"";
+
+/// Wire format spoken by the firmware
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SerialProtocol {
+    /// Newline-terminated JSON objects, e.g. `{"pot1":1234,"pot2":2345,"pot3":3456}`
+    #[default]
+    Json,
+    /// `[0xA5][count][count x u16 LE][crc8]`, CRC covering count and values
+    Binary,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FrameErrorKind {
+    Malformed,
+    ChecksumMismatch,
+    /// Decoded fine but a reading is outside the ADC range
+    OutOfRange,
+}
+
+/// A frame that could not be decoded
+#[derive(Debug, Clone)]
+pub struct FrameError {
+    pub kind: FrameErrorKind,
+    /// The offending input, truncated for display
+    pub raw: String,
+    pub message: String,
+}
+
+impl FrameError {
+    fn malformed(raw: &str, message: String) -> Self {
+        Self {
+            kind: FrameErrorKind::Malformed,
+            raw: raw.to_string(),
+            message,
+        }
+    }
+
+    fn checksum_mismatch(raw: String, expected: u8, actual: u8) -> Self {
+        Self {
+            kind: FrameErrorKind::ChecksumMismatch,
+            raw,
+            message: format!(
+                "CRC mismatch: expected {:02x}, got {:02x}",
+                expected, actual
+            ),
+        }
+    }
+
+    fn out_of_range(raw: String, message: String) -> Self {
+        Self {
+            kind: FrameErrorKind::OutOfRange,
+            raw,
+            message,
+        }
+    }
+}
+
+/// CRC-8 with polynomial 0x07 and zero initial value (CRC-8/SMBUS)
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Parses one line of the JSON protocol; blank lines yield `None`.
+///
+/// Lines ending in `,"crc":NN}` are checked against the CRC-8 of everything
+/// before that field. Lines without it are accepted unchecked, so older
+/// firmware keeps working.
+pub fn parse_json_line(line: &str) -> Option<Result<PotentiometerData, FrameError>> {
+    let line = line.trim();
+    // A late reply to `ID?` isn't a reading, nor an error
+    if line.is_empty() || line.starts_with(DEVICE_ID_PREFIX) {
+        return None;
+    }
+
+    if let Some(idx) = line.rfind(JSON_CRC_FIELD) {
+        let crc_str = line[idx + JSON_CRC_FIELD.len()..]
+            .trim_end_matches('}')
+            .trim();
+        let expected = match crc_str.parse::<u8>() {
+            Ok(crc) => crc,
+            Err(e) => {
+                return Some(Err(FrameError::malformed(
+                    line,
+                    format!("Invalid crc field: {}", e),
+                )))
+            }
+        };
+
+        let actual = crc8(&line.as_bytes()[..idx]);
+        if actual != expected {
+            return Some(Err(FrameError::checksum_mismatch(
+                line.to_string(),
+                expected,
+                actual,
+            )));
+        }
+    }
+
+    Some(
+        serde_json::from_str::<PotentiometerData>(line)
+            .map_err(|e| FrameError::malformed(line, e.to_string()))
+            .and_then(|data| {
+                data.validate()
+                    .map(|()| data)
+                    .map_err(|message| FrameError::out_of_range(line.to_string(), message))
+            }),
+    )
+}
+
+/// Incremental decoder for the binary protocol
+pub struct BinaryFrameDecoder {
+    buffer: Vec<u8>,
+}
+
+impl BinaryFrameDecoder {
+    pub fn new() -> Self {
+        Self { buffer: Vec::new() }
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Returns the next complete frame, or `None` until more bytes arrive
+    pub fn next_frame(&mut self) -> Option<Result<PotentiometerData, FrameError>> {
+        loop {
+            // Resynchronise on the sync byte, discarding anything before it
+            let start = match self.buffer.iter().position(|&b| b == BINARY_FRAME_SYNC) {
+                Some(start) => start,
+                None => {
+                    self.buffer.clear();
+                    return None;
+                }
+            };
+            self.buffer.drain(..start);
+
+            if self.buffer.len() < 2 {
+                return None;
+            }
+
+            let count = self.buffer[1] as usize;
+            if count == 0 || count > MAX_BINARY_CHANNELS {
+                // Not a real frame header, skip this sync byte
+                self.buffer.drain(..1);
+                continue;
+            }
+
+            let frame_len = 2 + count * 2 + 1;
+            if self.buffer.len() < frame_len {
+                return None;
+            }
+
+            let frame: Vec<u8> = self.buffer.drain(..frame_len).collect();
+            let payload = &frame[1..frame_len - 1];
+            let expected = frame[frame_len - 1];
+
+            let actual = crc8(payload);
+            if actual != expected {
+                return Some(Err(FrameError::checksum_mismatch(
+                    hex(&frame),
+                    expected,
+                    actual,
+                )));
+            }
+
+            let values: Vec<u16> = payload[1..]
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .collect();
+
+            let data = PotentiometerData::from_raw_values(&values);
+            return Some(match data.validate() {
+                Ok(()) => Ok(data),
+                Err(message) => Err(FrameError::out_of_range(hex(&frame), message)),
+            });
+        }
+    }
+}
+
+impl Default for BinaryFrameDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads the firmware's reply to `ID?` out of a line. The reply may follow
+/// binary frame bytes on the same line, so it's searched for rather than
+/// expected at the start. Replies from other devices are ignored.
+pub fn parse_device_id(line: &str) -> Option<DeviceInfo> {
+    let start = line.find(DEVICE_ID_PREFIX)?;
+    let info: DeviceInfo = serde_json::from_str(line[start..].trim_end()).ok()?;
+    (info.device == DEVICE_ID).then_some(info)
+}
+
+/// Space-separated hex bytes, for showing binary data
+pub fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
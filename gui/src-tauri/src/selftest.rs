@@ -0,0 +1,185 @@
+use anyhow::Result;
+
+use crate::audio::{AudioManager, SYSTEM_SOUNDS_PROCESS_ID};
+use crate::types::{SelfTestOutcome, SelfTestReport, SelfTestStep};
+use crate::volume::Volume;
+
+/// How far from its current level each volume is moved during the test,
+/// small enough not to startle anyone listening
+const TEST_OFFSET: f32 = 10.0;
+/// Largest difference between a volume written and read back that still passes
+const READ_BACK_TOLERANCE: f32 = 1.0;
+
+/// Sets master and, when the backend supports it, one app to a nearby test
+/// volume, reads each back and restores the original level
+pub fn run(audio_manager: &dyn AudioManager) -> SelfTestReport {
+    let mut steps = Vec::new();
+
+    round_trip(
+        &mut steps,
+        "master",
+        || audio_manager.get_master_volume(),
+        |volume| audio_manager.set_master_volume(volume),
+    );
+
+    if !audio_manager.supports_per_app_volume() {
+        steps.push(step(
+            "Find an app session",
+            SelfTestOutcome::Skipped,
+            "The backend has no per-app volume".to_string(),
+        ));
+    } else {
+        match audio_manager.get_audio_sessions() {
+            Err(e) => steps.push(step(
+                "Find an app session",
+                SelfTestOutcome::Failed,
+                e.to_string(),
+            )),
+            Ok(sessions) => {
+                let app = sessions
+                    .into_iter()
+                    .find(|s| s.process_id != 0 && s.process_id != SYSTEM_SOUNDS_PROCESS_ID);
+                match app {
+                    None => steps.push(step(
+                        "Find an app session",
+                        SelfTestOutcome::Skipped,
+                        "No app is playing audio".to_string(),
+                    )),
+                    Some(app) => {
+                        steps.push(step(
+                            "Find an app session",
+                            SelfTestOutcome::Passed,
+                            app.display_name.clone(),
+                        ));
+                        round_trip(
+                            &mut steps,
+                            &app.display_name,
+                            || app_volume(audio_manager, app.process_id),
+                            |volume| audio_manager.set_app_volume(app.process_id, volume),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    let passed = steps.iter().all(|s| s.outcome != SelfTestOutcome::Failed);
+    SelfTestReport {
+        capabilities: audio_manager.capabilities(),
+        steps,
+        passed,
+    }
+}
+
+/// Reads a volume, changes it, checks the change stuck and puts it back
+fn round_trip(
+    steps: &mut Vec<SelfTestStep>,
+    target: &str,
+    get: impl Fn() -> Result<f32>,
+    set: impl Fn(Volume) -> Result<()>,
+) {
+    let original = match get() {
+        Ok(volume) => volume,
+        Err(e) => {
+            steps.push(failed(format!("Read {} volume", target), e));
+            return;
+        }
+    };
+    steps.push(step(
+        format!("Read {} volume", target),
+        SelfTestOutcome::Passed,
+        format!("{:.0}%", original),
+    ));
+
+    let test_volume = if original > 50.0 {
+        original - TEST_OFFSET
+    } else {
+        original + TEST_OFFSET
+    };
+    let result = Volume::from_percent(test_volume)
+        .map_err(anyhow::Error::from)
+        .and_then(&set);
+    if let Err(e) = result {
+        steps.push(failed(format!("Set {} volume", target), e));
+        return;
+    }
+    steps.push(step(
+        format!("Set {} volume", target),
+        SelfTestOutcome::Passed,
+        format!("{:.0}%", test_volume),
+    ));
+    steps.push(read_back(
+        format!("Read back {} volume", target),
+        &get,
+        test_volume,
+    ));
+
+    let result = Volume::from_percent(original)
+        .map_err(anyhow::Error::from)
+        .and_then(&set);
+    match result {
+        Ok(()) => steps.push(read_back(
+            format!("Restore {} volume", target),
+            &get,
+            original,
+        )),
+        Err(e) => steps.push(failed(format!("Restore {} volume", target), e)),
+    }
+}
+
+fn read_back(name: String, get: impl Fn() -> Result<f32>, expected: f32) -> SelfTestStep {
+    match get() {
+        Ok(volume) if (volume - expected).abs() <= READ_BACK_TOLERANCE => {
+            step(name, SelfTestOutcome::Passed, format!("{:.0}%", volume))
+        }
+        Ok(volume) => step(
+            name,
+            SelfTestOutcome::Failed,
+            format!("Expected {:.0}%, read {:.0}%", expected, volume),
+        ),
+        Err(e) => failed(name, e),
+    }
+}
+
+/// Per-app volumes are only readable through the session list
+fn app_volume(audio_manager: &dyn AudioManager, process_id: u32) -> Result<f32> {
+    audio_manager
+        .get_audio_sessions()?
+        .into_iter()
+        .find(|s| s.process_id == process_id)
+        .map(|s| s.volume)
+        .ok_or_else(|| anyhow::anyhow!("Session {} went away", process_id))
+}
+
+fn step(name: impl Into<String>, outcome: SelfTestOutcome, detail: String) -> SelfTestStep {
+    SelfTestStep {
+        name: name.into(),
+        outcome,
+        detail,
+    }
+}
+
+fn failed(name: String, error: anyhow::Error) -> SelfTestStep {
+    step(name, SelfTestOutcome::Failed, error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::SimAudioManager;
+
+    #[test]
+    fn sim_backend_passes_and_keeps_its_volumes() {
+        let audio_manager = SimAudioManager::new();
+        let before = audio_manager.get_audio_sessions().unwrap();
+
+        let report = run(&audio_manager);
+
+        assert!(report.passed, "{:?}", report.steps);
+        assert!(report
+            .steps
+            .iter()
+            .all(|s| s.outcome == SelfTestOutcome::Passed));
+        assert_eq!(audio_manager.get_audio_sessions().unwrap(), before);
+    }
+}
@@ -1,29 +1,382 @@
-use anyhow::{anyhow, Result};
-use serde_json;
-use serialport::{self, SerialPort};
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use tokio::sync::mpsc;
+use anyhow::Result;
+use serialport::{self, ClearBuffer, SerialPort};
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self, error::TrySendError};
 use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
 
-use crate::types::{ConnectionStatus, PotentiometerData, SerialPortInfo};
+use crate::error::MixerError;
+use crate::protocol::{
+    hex, parse_device_id, parse_json_line, BinaryFrameDecoder, FrameErrorKind, SerialProtocol,
+};
+use crate::types::{
+    ConnectionStatus, DeviceDiagnostics, DeviceInfo, PotentiometerData, SerialParseError,
+    SerialPortInfo, UsbDeviceFilter,
+};
+
+/// USB VID/PID set by the firmware (`UsbVidPid(0x16c0, 0x27dd)`)
+const PICO_USB_VID: u16 = 0x16c0;
+const PICO_USB_PID: u16 = 0x27dd;
+const MAX_ERROR_LINE_CHARS: usize = 80;
+const PARSE_ERROR_EVENT_INTERVAL: Duration = Duration::from_secs(1);
+const DIAGNOSTICS_EVENT_INTERVAL: Duration = Duration::from_secs(1);
+/// How long a device gets to answer `ID?` before it's rejected
+const IDENTIFY_TIMEOUT: Duration = Duration::from_millis(500);
+const IDENTIFY_READ_TIMEOUT: Duration = Duration::from_millis(50);
+/// How long to wait for the end of a line left over from identifying
+const LINE_SYNC_TIMEOUT: Duration = Duration::from_millis(200);
+const READ_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Locks `mutex`, recovering the guard if a panicking thread poisoned it, so
+/// one panic in the reader doesn't take every later command down with it
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| {
+        log::warn!("Recovering poisoned serial mutex");
+        mutex.clear_poison();
+        poisoned.into_inner()
+    })
+}
+
+/// Asks the device who it is and waits for the reply, so a port that merely
+/// looks like a Pico isn't mistaken for the mixer. Frames arriving in the
+/// meantime are discarded.
+fn identify(port: &mut dyn SerialPort) -> Result<DeviceInfo, MixerError> {
+    // The leading newline ends any partial command left in the firmware's buffer
+    port.write_all(b"\nID?\n")?;
+    port.flush()?;
+    port.set_timeout(IDENTIFY_READ_TIMEOUT)?;
+
+    let deadline = Instant::now() + IDENTIFY_TIMEOUT;
+    let mut buffer = [0u8; 256];
+    let mut pending: Vec<u8> = Vec::new();
+    let mut device = None;
+
+    while device.is_none() && Instant::now() < deadline {
+        let n = match port.read(&mut buffer) {
+            Ok(n) => n,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::TimedOut | io::ErrorKind::Interrupted
+                ) =>
+            {
+                continue
+            }
+            Err(e) => return Err(e.into()),
+        };
+        pending.extend_from_slice(&buffer[..n]);
+
+        while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            if let Some(info) = parse_device_id(&String::from_utf8_lossy(&line)) {
+                device = Some(info);
+                break;
+            }
+        }
+    }
+
+    port.set_timeout(READ_TIMEOUT)?;
+    device.ok_or_else(|| MixerError::NotAMixer("Not a mixer device (no reply to ID?)".to_string()))
+}
+
+/// Reads up to and including the next newline, so the reader starts on a
+/// whole line rather than the tail of one the device was halfway through
+fn skip_partial_line(port: &mut dyn SerialPort) -> Result<(), MixerError> {
+    port.set_timeout(IDENTIFY_READ_TIMEOUT)?;
+
+    let deadline = Instant::now() + LINE_SYNC_TIMEOUT;
+    let mut byte = [0u8; 1];
+    while Instant::now() < deadline {
+        match port.read(&mut byte) {
+            Ok(1) if byte[0] == b'\n' => break,
+            Ok(_) => {}
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::TimedOut | io::ErrorKind::Interrupted
+                ) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    port.set_timeout(READ_TIMEOUT)?;
+    Ok(())
+}
+
+/// An open port behind a lock, `None` once closed
+type SharedPort = Arc<Mutex<Option<Box<dyn SerialPort>>>>;
+
+/// Reads from the device's port, locking it only for the duration of each
+/// read. Writes go through a clone of the port, so they never wait out a read.
+struct SharedPortReader {
+    port: SharedPort,
+    writer: SharedPort,
+}
+
+impl Read for SharedPortReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut port_guard = lock(&self.port);
+        // A closed port reads as the end of the stream
+        let Some(port) = port_guard.as_mut() else {
+            return Ok(0);
+        };
+
+        match port.read(buf) {
+            // An open port never ends, so an empty read is only a timeout
+            Ok(0) => Err(io::ErrorKind::TimedOut.into()),
+            Ok(n) => Ok(n),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::TimedOut | io::ErrorKind::Interrupted
+                ) =>
+            {
+                Err(e)
+            }
+            Err(e) => {
+                // The device is gone, so close the port rather than retry
+                *port_guard = None;
+                *lock(&self.writer) = None;
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Messages produced by the reader task
+#[derive(Debug, Clone)]
+pub enum SerialEvent {
+    Data(PotentiometerData),
+    /// A line failed to parse; throttled to at most one per second
+    ParseError(SerialParseError),
+    /// The port stopped responding (e.g. the device was unplugged) and was closed
+    Disconnected(ConnectionStatus),
+    /// Everything received, line by line (hex chunks in binary mode), while
+    /// the raw monitor is on
+    Raw(String),
+    /// Latest health readings; throttled to at most one per second
+    Diagnostics(DeviceDiagnostics),
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SmoothingSettings {
+    /// Weight of the previous value in the moving average (0.0 disables it)
+    pub smoothing_factor: f32,
+    /// Minimum change in raw ADC counts before a new value is forwarded
+    pub deadband: u16,
+}
+
+/// Readings at or below this are the bottom of travel; matches the firmware's
+/// deadzone
+const DEADZONE_LOW: u16 = 16;
+/// Readings at or above this are the top of travel; matches the firmware's
+/// deadzone
+const DEADZONE_HIGH: u16 = 4080;
+
+/// Whether `raw` is at either end of travel, where the deadband and the
+/// moving average would otherwise leave a fader short of 0% or 100%
+fn at_end_of_travel(raw: u16) -> bool {
+    raw <= DEADZONE_LOW || raw >= DEADZONE_HIGH
+}
+
+/// Exponential moving average plus deadband over raw pot readings, so idle
+/// sliders stop producing a stream of tiny volume changes
+struct PotSmoother {
+    smoothed: Option<Vec<f32>>,
+    last_sent: Option<Vec<u16>>,
+    last_buttons: Option<Vec<bool>>,
+}
+
+impl PotSmoother {
+    fn new() -> Self {
+        Self {
+            smoothed: None,
+            last_sent: None,
+            last_buttons: None,
+        }
+    }
+
+    /// Returns the smoothed reading if any channel moved past the deadband or
+    /// to either end of travel, any button changed state or any encoder turned
+    fn process(
+        &mut self,
+        data: &PotentiometerData,
+        settings: SmoothingSettings,
+    ) -> Option<PotentiometerData> {
+        let raw = data.raw_values();
+        let factor = settings.smoothing_factor.clamp(0.0, 0.99);
+
+        let smoothed: Vec<f32> = match &self.smoothed {
+            Some(previous) if previous.len() == raw.len() => previous
+                .iter()
+                .zip(&raw)
+                .map(|(&prev, &value)| {
+                    if at_end_of_travel(value) {
+                        value as f32
+                    } else {
+                        prev * factor + value as f32 * (1.0 - factor)
+                    }
+                })
+                .collect(),
+            _ => raw.iter().map(|&value| value as f32).collect(),
+        };
+        let values: Vec<u16> = smoothed.iter().map(|v| v.round() as u16).collect();
+        self.smoothed = Some(smoothed);
+
+        let changed = match &self.last_sent {
+            Some(last) if last.len() == values.len() => last.iter().zip(&values).any(|(&a, &b)| {
+                a.abs_diff(b) > settings.deadband || (a != b && at_end_of_travel(b))
+            }),
+            _ => true,
+        };
+
+        let buttons = data.buttons();
+        let buttons_changed = self.last_buttons.as_ref() != Some(&buttons);
+
+        // Deltas are relative, so dropping one would lose the movement
+        let encoders_turned = data
+            .encoder_deltas()
+            .iter()
+            .any(|delta| delta.is_some_and(|d| d != 0));
+
+        if !changed && !buttons_changed && !encoders_turned {
+            return None;
+        }
+
+        self.last_sent = Some(values.clone());
+        self.last_buttons = Some(buttons);
+        Some(PotentiometerData {
+            btn1: data.btn1,
+            btn2: data.btn2,
+            btn3: data.btn3,
+            enc1: data.enc1,
+            enc2: data.enc2,
+            enc3: data.enc3,
+            ..PotentiometerData::from_raw_values(&values)
+        })
+    }
+}
+
+/// One open mixer and everything tracked about it. Clones share state, so a
+/// device can be taken out of the map before its port is locked.
+#[derive(Clone)]
+struct ConnectedDevice {
+    port_name: String,
+    // Held by the reader while it waits for data; `None` once it found the
+    // device gone
+    port: SharedPort,
+    // Clone of `port` that commands are written through
+    writer: SharedPort,
+    info: Option<DeviceInfo>,
+    parse_error_count: Arc<AtomicU64>,
+    last_parse_error: Arc<Mutex<Option<String>>>,
+    crc_rejection_count: Arc<AtomicU64>,
+    // Frames carrying a reading above the ADC range
+    out_of_range_count: Arc<AtomicU64>,
+    // Frames superseded by a newer one while the consumer was behind
+    dropped_frame_count: Arc<AtomicU64>,
+    // When the last valid frame arrived (or the port opened, before any did)
+    last_data_instant: Arc<Mutex<Option<Instant>>>,
+    diagnostics: Arc<Mutex<Option<DeviceDiagnostics>>>,
+    // Cancels the running reader task, so only one ever reads the port
+    reader_token: Arc<Mutex<CancellationToken>>,
+}
+
+impl ConnectedDevice {
+    /// `ports` is the port to read from and a clone of it to write to
+    fn new(
+        port_name: String,
+        ports: Option<(Box<dyn SerialPort>, Box<dyn SerialPort>)>,
+        info: Option<DeviceInfo>,
+    ) -> Self {
+        let (port, writer) = ports.unzip();
+        Self {
+            port_name,
+            port: Arc::new(Mutex::new(port)),
+            writer: Arc::new(Mutex::new(writer)),
+            info,
+            parse_error_count: Arc::new(AtomicU64::new(0)),
+            last_parse_error: Arc::new(Mutex::new(None)),
+            crc_rejection_count: Arc::new(AtomicU64::new(0)),
+            out_of_range_count: Arc::new(AtomicU64::new(0)),
+            dropped_frame_count: Arc::new(AtomicU64::new(0)),
+            last_data_instant: Arc::new(Mutex::new(Some(Instant::now()))),
+            diagnostics: Arc::new(Mutex::new(None)),
+            reader_token: Arc::new(Mutex::new(CancellationToken::new())),
+        }
+    }
+
+    /// Checked on the writer, which unlike the port isn't held through reads
+    fn is_open(&self) -> bool {
+        lock(&self.writer).is_some()
+    }
+
+    fn close(&self) {
+        *lock(&self.writer) = None;
+        *lock(&self.port) = None;
+        lock(&self.reader_token).cancel();
+    }
+
+    fn status(&self) -> ConnectionStatus {
+        ConnectionStatus {
+            connected: self.is_open(),
+            port: Some(self.port_name.clone()),
+            error: None,
+            error_code: None,
+            parse_error_count: self.parse_error_count.load(Ordering::Relaxed),
+            last_parse_error: lock(&self.last_parse_error).clone(),
+            crc_rejection_count: self.crc_rejection_count.load(Ordering::Relaxed),
+            out_of_range_count: self.out_of_range_count.load(Ordering::Relaxed),
+            dropped_frame_count: self.dropped_frame_count.load(Ordering::Relaxed),
+            device: self.info.clone(),
+        }
+    }
+}
 
 pub struct SerialManager {
-    port: Arc<Mutex<Option<Box<dyn SerialPort>>>>,
-    port_name: Arc<Mutex<Option<String>>>,
+    // Open devices by port name
+    devices: Mutex<HashMap<String, ConnectedDevice>>,
+    protocol: Arc<Mutex<SerialProtocol>>,
+    smoothing: Arc<Mutex<SmoothingSettings>>,
+    raw_monitor: Arc<AtomicBool>,
+    device_filter: Mutex<Option<UsbDeviceFilter>>,
 }
 
 impl SerialManager {
     pub fn new() -> Self {
         Self {
-            port: Arc::new(Mutex::new(None)),
-            port_name: Arc::new(Mutex::new(None)),
+            devices: Mutex::new(HashMap::new()),
+            protocol: Arc::new(Mutex::new(SerialProtocol::default())),
+            smoothing: Arc::new(Mutex::new(SmoothingSettings::default())),
+            raw_monitor: Arc::new(AtomicBool::new(false)),
+            device_filter: Mutex::new(None),
         }
     }
 
+    pub fn set_protocol(&self, protocol: SerialProtocol) {
+        *lock(&self.protocol) = protocol;
+    }
+
+    pub fn set_smoothing(&self, settings: SmoothingSettings) {
+        *lock(&self.smoothing) = settings;
+    }
+
+    /// USB IDs to look for before the stock firmware's when auto-detecting
+    pub fn set_device_filter(&self, filter: Option<UsbDeviceFilter>) {
+        *lock(&self.device_filter) = filter;
+    }
+
+    /// Turns forwarding of everything received as `SerialEvent::Raw` on or off
+    pub fn set_raw_monitor(&self, enabled: bool) {
+        self.raw_monitor.store(enabled, Ordering::Relaxed);
+    }
+
     pub fn list_ports() -> Result<Vec<SerialPortInfo>> {
-        let ports =
-            serialport::available_ports().map_err(|e| anyhow!("Failed to list ports: {}", e))?;
+        let ports = serialport::available_ports().map_err(MixerError::from)?;
 
         Ok(ports
             .into_iter()
@@ -43,139 +396,511 @@ impl SerialManager {
             .collect())
     }
 
-    pub fn find_pico_port() -> Option<String> {
-        if let Ok(ports) = serialport::available_ports() {
-            for port in ports {
-                let port_name_lower = port.port_name.to_lowercase();
+    /// Picks the most likely mixer port that isn't connected yet
+    pub fn find_pico_port(&self) -> Option<String> {
+        let connected = self.connected_ports();
+        let ports: Vec<_> = serialport::available_ports()
+            .ok()?
+            .into_iter()
+            .filter(|port| !connected.contains(&port.port_name))
+            .collect();
 
-                // Check for Pico identifiers
+        // An exact VID/PID match is unambiguous, so it wins over any heuristic.
+        // A configured filter is the user naming their device, so it goes first.
+        let configured = *lock(&self.device_filter);
+        let stock = UsbDeviceFilter {
+            vid: PICO_USB_VID,
+            pid: PICO_USB_PID,
+        };
+        for filter in configured.iter().chain([&stock]) {
+            for port in &ports {
                 if let serialport::SerialPortType::UsbPort(info) = &port.port_type {
-                    if let Some(product) = &info.product {
-                        let product_lower = product.to_lowercase();
-                        if product_lower.contains("pico") || product_lower.contains("rp2040") {
-                            return Some(port.port_name);
-                        }
+                    if info.vid == filter.vid && info.pid == filter.pid {
+                        return Some(port.port_name.clone());
                     }
+                }
+            }
+        }
 
-                    if let Some(manufacturer) = &info.manufacturer {
-                        let manufacturer_lower = manufacturer.to_lowercase();
-                        if manufacturer_lower.contains("raspberry") {
-                            return Some(port.port_name);
-                        }
+        for port in &ports {
+            // Check for Pico identifiers
+            if let serialport::SerialPortType::UsbPort(info) = &port.port_type {
+                if let Some(product) = &info.product {
+                    let product_lower = product.to_lowercase();
+                    if product_lower.contains("pico") || product_lower.contains("rp2040") {
+                        return Some(port.port_name.clone());
                     }
                 }
 
-                // Check for common patterns
-                if port_name_lower.contains("usbmodem")
-                    || port_name_lower.contains("ttyacm")
-                    || port_name_lower.contains("com") && port_name_lower.len() <= 5
-                {
-                    // This might be our device
-                    return Some(port.port_name);
+                if let Some(manufacturer) = &info.manufacturer {
+                    let manufacturer_lower = manufacturer.to_lowercase();
+                    if manufacturer_lower.contains("raspberry") {
+                        return Some(port.port_name.clone());
+                    }
                 }
             }
         }
+
+        // Last resort: common port name patterns
+        for port in ports {
+            let port_name_lower = port.port_name.to_lowercase();
+            if port_name_lower.contains("usbmodem")
+                || port_name_lower.contains("ttyacm")
+                || port_name_lower.contains("com") && port_name_lower.len() <= 5
+            {
+                // This might be our device
+                return Some(port.port_name);
+            }
+        }
+
         None
     }
 
+    /// Opens `port_name`, or the first likely mixer not yet connected, next to
+    /// any devices already open
     pub fn connect(&self, port_name: Option<String>) -> Result<ConnectionStatus> {
-        // Disconnect if already connected
-        self.disconnect();
-
-        let port_to_use = port_name.or_else(Self::find_pico_port);
+        let port_to_use = port_name.or_else(|| self.find_pico_port());
 
         if let Some(port_name) = port_to_use {
+            // Reconnecting a port replaces its old connection
+            self.disconnect(&port_name);
+
             match serialport::new(&port_name, 115200)
-                .timeout(Duration::from_millis(1000))
+                .timeout(READ_TIMEOUT)
                 .open()
             {
-                Ok(port) => {
-                    *self.port.lock().unwrap() = Some(port);
-                    *self.port_name.lock().unwrap() = Some(port_name.clone());
+                Ok(mut port) => {
+                    // Drops the boot banner and anything else sent before we listened
+                    if let Err(e) = port.clear(ClearBuffer::Input) {
+                        log::warn!("Failed to clear input buffer on {}: {}", port_name, e);
+                    }
+
+                    let device = match identify(port.as_mut()) {
+                        Ok(device) => device,
+                        Err(error) => {
+                            return Ok(ConnectionStatus {
+                                connected: false,
+                                port: Some(port_name.clone()),
+                                error: Some(format!("{}: {}", port_name, error)),
+                                error_code: Some(error.code().to_string()),
+                                ..Default::default()
+                            });
+                        }
+                    };
+                    log::info!(
+                        "Connected to {} firmware {} with {} channels on {}",
+                        device.device,
+                        device.fw,
+                        device.channels,
+                        port_name
+                    );
+
+                    // Binary frames resync on their own start byte
+                    if *lock(&self.protocol) == SerialProtocol::Json {
+                        if let Err(e) = skip_partial_line(port.as_mut()) {
+                            log::warn!("Failed to sync to a line start on {}: {}", port_name, e);
+                        }
+                    }
+
+                    let writer = match port.try_clone() {
+                        Ok(writer) => writer,
+                        Err(e) => {
+                            let error = MixerError::from(e);
+                            return Ok(ConnectionStatus {
+                                connected: false,
+                                port: Some(port_name.clone()),
+                                error: Some(format!("Failed to connect: {}", error)),
+                                error_code: Some(error.code().to_string()),
+                                ..Default::default()
+                            });
+                        }
+                    };
+                    let connected =
+                        ConnectedDevice::new(port_name.clone(), Some((port, writer)), Some(device));
+                    let status = connected.status();
+                    lock(&self.devices).insert(port_name, connected);
 
+                    Ok(status)
+                }
+                Err(e) => {
+                    let error = MixerError::from(e);
                     Ok(ConnectionStatus {
-                        connected: true,
+                        connected: false,
                         port: Some(port_name),
-                        error: None,
+                        error: Some(format!("Failed to connect: {}", error)),
+                        error_code: Some(error.code().to_string()),
+                        ..Default::default()
                     })
                 }
-                Err(e) => Ok(ConnectionStatus {
-                    connected: false,
-                    port: None,
-                    error: Some(format!("Failed to connect: {}", e)),
-                }),
             }
         } else {
+            let error = MixerError::SerialNotFound("No Pico device found".to_string());
             Ok(ConnectionStatus {
                 connected: false,
-                port: None,
-                error: Some("No Pico device found".to_string()),
+                error: Some(error.to_string()),
+                error_code: Some(error.code().to_string()),
+                ..Default::default()
             })
         }
     }
 
-    pub fn disconnect(&self) {
-        *self.port.lock().unwrap() = None;
-        *self.port_name.lock().unwrap() = None;
+    /// Sends a newline-terminated command line to every connected device,
+    /// e.g. `RATE 100`
+    pub fn send_command(&self, command: &str) -> Result<()> {
+        let devices = self.open_devices();
+        if devices.is_empty() {
+            return Err(MixerError::NotConnected.into());
+        }
+
+        for device in devices {
+            write_line(&device, command)?;
+        }
+        Ok(())
     }
 
-    pub fn is_connected(&self) -> bool {
-        self.port.lock().unwrap().is_some()
+    /// Sends a command line to the device on `port_name` only, e.g. `SET 1 2048`
+    pub fn send_command_to(&self, port_name: &str, command: &str) -> Result<()> {
+        let device = self
+            .device(Some(port_name))
+            .ok_or(MixerError::NotConnected)?;
+        write_line(&device, command)
     }
 
-    pub fn get_status(&self) -> ConnectionStatus {
-        let port_lock = self.port_name.lock().unwrap();
-        ConnectionStatus {
-            connected: self.is_connected(),
-            port: port_lock.clone(),
-            error: None,
+    pub fn disconnect(&self, port_name: &str) {
+        // Taken out first, since closing waits for the reader to release the port
+        let device = lock(&self.devices).remove(port_name);
+        if let Some(device) = device {
+            device.close();
         }
     }
 
-    pub async fn start_reading(&self, tx: mpsc::Sender<PotentiometerData>) -> Result<()> {
-        let port = self.port.clone();
+    pub fn disconnect_all(&self) {
+        let devices: Vec<ConnectedDevice> = lock(&self.devices).drain().map(|(_, d)| d).collect();
+        for device in devices {
+            device.close();
+        }
+    }
+
+    /// Devices whose port is still open, sorted by port name
+    fn open_devices(&self) -> Vec<ConnectedDevice> {
+        let mut devices: Vec<ConnectedDevice> = lock(&self.devices).values().cloned().collect();
+        devices.retain(ConnectedDevice::is_open);
+        devices.sort_by(|a, b| a.port_name.cmp(&b.port_name));
+        devices
+    }
+
+    /// `port_name`'s device, or the first open one for `None`
+    fn device(&self, port_name: Option<&str>) -> Option<ConnectedDevice> {
+        match port_name {
+            Some(port_name) => lock(&self.devices).get(port_name).cloned(),
+            None => self.open_devices().into_iter().next(),
+        }
+    }
+
+    pub fn connected_ports(&self) -> Vec<String> {
+        self.open_devices()
+            .into_iter()
+            .map(|device| device.port_name)
+            .collect()
+    }
+
+    pub fn list_connected(&self) -> Vec<ConnectionStatus> {
+        self.open_devices()
+            .iter()
+            .map(ConnectedDevice::status)
+            .collect()
+    }
+
+    /// Latest health readings, `None` until the firmware sends any
+    pub fn get_diagnostics(&self, port_name: Option<&str>) -> Option<DeviceDiagnostics> {
+        let device = self.device(port_name)?;
+        let diagnostics = *lock(&device.diagnostics);
+        diagnostics
+    }
+
+    /// Time since the port's last valid frame, or since connecting if none has
+    /// arrived yet; `None` while disconnected
+    pub fn time_since_last_data(&self, port_name: &str) -> Option<Duration> {
+        let device = self
+            .device(Some(port_name))
+            .filter(ConnectedDevice::is_open)?;
+        let last_data = *lock(&device.last_data_instant);
+        last_data.map(|instant| instant.elapsed())
+    }
+
+    pub fn is_connected(&self, port_name: &str) -> bool {
+        self.device(Some(port_name))
+            .is_some_and(|device| device.is_open())
+    }
+
+    /// Status of `port_name`, or of the first connected device for `None`
+    pub fn get_status(&self, port_name: Option<&str>) -> ConnectionStatus {
+        match self.device(port_name) {
+            Some(device) => device.status(),
+            None => ConnectionStatus {
+                port: port_name.map(str::to_string),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub async fn start_reading(
+        &self,
+        port_name: &str,
+        tx: mpsc::Sender<SerialEvent>,
+    ) -> Result<()> {
+        let device = self
+            .device(Some(port_name))
+            .ok_or(MixerError::NotConnected)?;
+        let source = SharedPortReader {
+            port: device.port.clone(),
+            writer: device.writer.clone(),
+        };
+        self.spawn_reader(&device, Box::new(source), tx);
+
+        Ok(())
+    }
+
+    /// Decodes frames from `source` on a background task until it ends or
+    /// fails. Kept apart from the port so any byte stream can be fed in.
+    fn spawn_reader(
+        &self,
+        device: &ConnectedDevice,
+        mut source: Box<dyn Read + Send>,
+        tx: mpsc::Sender<SerialEvent>,
+    ) {
+        // Stop any earlier reader of this device before this one starts
+        let cancel = CancellationToken::new();
+        std::mem::replace(&mut *lock(&device.reader_token), cancel.clone()).cancel();
+
+        let protocol = self.protocol.clone();
+        let smoothing = self.smoothing.clone();
+        let raw_monitor = self.raw_monitor.clone();
+        let port_name = device.port_name.clone();
+        let parse_error_count = device.parse_error_count.clone();
+        let last_parse_error = device.last_parse_error.clone();
+        let crc_rejection_count = device.crc_rejection_count.clone();
+        let out_of_range_count = device.out_of_range_count.clone();
+        let dropped_frame_count = device.dropped_frame_count.clone();
+        let last_data_instant = device.last_data_instant.clone();
+        let diagnostics = device.diagnostics.clone();
 
         tokio::spawn(async move {
             let mut buffer = vec![0u8; 256];
             let mut line_buffer = String::new();
+            let mut binary_decoder = BinaryFrameDecoder::new();
+            let mut smoother = PotSmoother::new();
+            let mut last_error_event: Option<Instant> = None;
+            let mut last_diagnostics_event: Option<Instant> = None;
+            // Data frames waiting for room in the channel, see `hold`
+            let mut held: VecDeque<PotentiometerData> = VecDeque::new();
 
-            loop {
-                let data_available = {
-                    let mut port_guard = port.lock().unwrap();
-                    if let Some(ref mut port) = *port_guard {
-                        match port.read(&mut buffer) {
-                            Ok(n) if n > 0 => {
-                                line_buffer.push_str(&String::from_utf8_lossy(&buffer[..n]));
-                                true
-                            }
-                            _ => false,
+            'read: loop {
+                let received = match source.read(&mut buffer) {
+                    // End of the stream, e.g. the port was closed by `disconnect`
+                    Ok(0) => {
+                        // Nothing left to read, so waiting for room can't hold anything up
+                        for data in held.drain(..) {
+                            let _ = tx.send(SerialEvent::Data(data)).await;
                         }
-                    } else {
-                        // Port disconnected
+                        break;
+                    }
+                    Ok(n) => buffer[..n].to_vec(),
+                    Err(e)
+                        if matches!(
+                            e.kind(),
+                            io::ErrorKind::TimedOut | io::ErrorKind::Interrupted
+                        ) =>
+                    {
+                        Vec::new()
+                    }
+                    // A newer reader owns the port now, so this one's error is moot
+                    Err(_) if cancel.is_cancelled() => break,
+                    Err(e) => {
+                        log::error!("Serial port {} read failed, closing it: {}", port_name, e);
+                        // A gone device isn't a stalled one
+                        *lock(&last_data_instant) = None;
+                        let _ = tx
+                            .send(SerialEvent::Disconnected(ConnectionStatus {
+                                connected: false,
+                                port: Some(port_name.clone()),
+                                error: Some(format!("Device disconnected: {}", e)),
+                                ..Default::default()
+                            }))
+                            .await;
                         break;
                     }
                 };
 
-                if data_available {
-                    // Process complete lines
-                    while let Some(newline_pos) = line_buffer.find('\n') {
-                        let line = &line_buffer[..newline_pos];
+                // Cancelled while blocked in `read`; drop the bytes rather than race the new reader
+                if cancel.is_cancelled() {
+                    break;
+                }
 
-                        // Try to parse JSON
-                        if let Ok(data) = serde_json::from_str::<PotentiometerData>(line) {
-                            let _ = tx.send(data).await;
+                if !received.is_empty() {
+                    // Decode every complete frame received so far
+                    let mut frames = Vec::new();
+                    let monitoring = raw_monitor.load(Ordering::Relaxed);
+                    let mut raw_lines = Vec::new();
+                    match *lock(&protocol) {
+                        SerialProtocol::Json => {
+                            line_buffer.push_str(&String::from_utf8_lossy(&received));
+                            while let Some(newline_pos) = line_buffer.find('\n') {
+                                let line = line_buffer[..newline_pos].trim();
+                                if monitoring {
+                                    raw_lines.push(line.to_string());
+                                }
+                                if !line.is_empty() && !line.starts_with('{') {
+                                    // Not a data frame, e.g. the optional firmware greeting
+                                    log::debug!("Skipping non-JSON serial line: {:?}", line);
+                                } else if let Some(frame) = parse_json_line(line) {
+                                    frames.push(frame);
+                                }
+                                line_buffer.drain(..=newline_pos);
+                            }
+                        }
+                        SerialProtocol::Binary => {
+                            if monitoring {
+                                raw_lines.push(hex(&received));
+                            }
+                            binary_decoder.push(&received);
+                            while let Some(frame) = binary_decoder.next_frame() {
+                                frames.push(frame);
+                            }
                         }
+                    }
 
-                        line_buffer.drain(..=newline_pos);
+                    for line in raw_lines {
+                        let _ = tx.send(SerialEvent::Raw(line)).await;
                     }
+
+                    for frame in frames {
+                        match frame {
+                            Ok(data) => {
+                                // Counts even when smoothing holds the frame back
+                                *lock(&last_data_instant) = Some(Instant::now());
+                                // Taken before smoothing, which drops frames from still faders
+                                if let Some(readings) = data.diagnostics() {
+                                    *lock(&diagnostics) = Some(readings);
+                                    let throttled = last_diagnostics_event
+                                        .is_some_and(|t| t.elapsed() < DIAGNOSTICS_EVENT_INTERVAL);
+                                    if !throttled {
+                                        last_diagnostics_event = Some(Instant::now());
+                                        // Readings repeat every second, so one lost to a
+                                        // full channel isn't worth waiting for
+                                        let _ = tx.try_send(SerialEvent::Diagnostics(readings));
+                                    }
+                                }
+                                let settings = *lock(&smoothing);
+                                if let Some(data) = smoother.process(&data, settings) {
+                                    if hold(&mut held, data) {
+                                        dropped_frame_count.fetch_add(1, Ordering::Relaxed);
+                                    }
+                                    if !try_send_held(&tx, &mut held) {
+                                        break 'read;
+                                    }
+                                }
+                            }
+                            Err(e) if e.kind == FrameErrorKind::ChecksumMismatch => {
+                                crc_rejection_count.fetch_add(1, Ordering::Relaxed);
+                                log::debug!("Rejected serial frame {:?}: {}", e.raw, e.message);
+                            }
+                            Err(e) if e.kind == FrameErrorKind::OutOfRange => {
+                                let count = out_of_range_count.fetch_add(1, Ordering::Relaxed) + 1;
+                                log::warn!(
+                                    "Rejected serial frame {:?} ({} so far): {}",
+                                    e.raw,
+                                    count,
+                                    e.message
+                                );
+                            }
+                            Err(e) => {
+                                let count = parse_error_count.fetch_add(1, Ordering::Relaxed) + 1;
+                                let line: String =
+                                    e.raw.chars().take(MAX_ERROR_LINE_CHARS).collect();
+                                *lock(&last_parse_error) =
+                                    Some(format!("{} in {:?}", e.message, line));
+
+                                let throttled = last_error_event
+                                    .is_some_and(|t| t.elapsed() < PARSE_ERROR_EVENT_INTERVAL);
+                                if !throttled {
+                                    last_error_event = Some(Instant::now());
+                                    let _ = tx
+                                        .send(SerialEvent::ParseError(SerialParseError {
+                                            line,
+                                            error: e.message,
+                                            count,
+                                        }))
+                                        .await;
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // Retry a frame held back earlier, now the consumer may have caught up
+                if !try_send_held(&tx, &mut held) {
+                    break;
                 }
 
-                sleep(Duration::from_millis(10)).await;
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = sleep(Duration::from_millis(10)) => {}
+                }
             }
         });
+    }
+}
 
-        Ok(())
+fn write_line(device: &ConnectedDevice, command: &str) -> Result<()> {
+    let mut port_lock = lock(&device.writer);
+    let port = port_lock.as_mut().ok_or(MixerError::NotConnected)?;
+    port.write_all(format!("{}\n", command).as_bytes())?;
+    port.flush()?;
+    Ok(())
+}
+
+/// Queues a frame until the channel has room. Only the newest pot readings
+/// matter, so the last frame still waiting is folded into this one, its
+/// encoder deltas added on, unless the buttons differ: merging those would
+/// lose a press. Returns whether a frame was folded away.
+fn hold(held: &mut VecDeque<PotentiometerData>, mut data: PotentiometerData) -> bool {
+    let Some(older) = held.back() else {
+        held.push_back(data);
+        return false;
+    };
+    if older.buttons() != data.buttons() {
+        held.push_back(data);
+        return false;
+    }
+
+    let sum = |older: Option<i32>, newer: Option<i32>| match (older, newer) {
+        (Some(a), Some(b)) => Some(a.saturating_add(b)),
+        (a, b) => b.or(a),
+    };
+    data.enc1 = sum(older.enc1, data.enc1);
+    data.enc2 = sum(older.enc2, data.enc2);
+    data.enc3 = sum(older.enc3, data.enc3);
+    held.pop_back();
+    held.push_back(data);
+    true
+}
+
+/// Sends held frames while the channel has room, so a slow consumer never
+/// blocks serial reads. Returns `false` once the receiver is gone.
+fn try_send_held(tx: &mpsc::Sender<SerialEvent>, held: &mut VecDeque<PotentiometerData>) -> bool {
+    while !held.is_empty() {
+        match tx.try_reserve() {
+            Ok(permit) => {
+                if let Some(data) = held.pop_front() {
+                    permit.send(SerialEvent::Data(data));
+                }
+            }
+            Err(TrySendError::Full(())) => return true,
+            Err(TrySendError::Closed(())) => return false,
+        }
     }
+    true
 }
 
 impl Default for SerialManager {
@@ -183,3 +908,319 @@ impl Default for SerialManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::crc8;
+    use std::collections::VecDeque;
+    use std::io::Cursor;
+
+    /// Hands out one chunk per read, like bytes trickling in over USB
+    struct ChunkedReader(VecDeque<Vec<u8>>);
+
+    impl ChunkedReader {
+        fn new(chunks: &[&[u8]]) -> Self {
+            Self(chunks.iter().map(|chunk| chunk.to_vec()).collect())
+        }
+    }
+
+    impl Read for ChunkedReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let Some(chunk) = self.0.pop_front() else {
+                return Ok(0);
+            };
+            buf[..chunk.len()].copy_from_slice(&chunk);
+            Ok(chunk.len())
+        }
+    }
+
+    /// Fails every read, like a port whose device was unplugged
+    struct Unplugged;
+
+    impl Read for Unplugged {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+    }
+
+    fn test_device() -> ConnectedDevice {
+        ConnectedDevice::new("test".to_string(), None, None)
+    }
+
+    /// Runs the reader over `source` until it ends, returning every event sent
+    async fn read_all(
+        manager: &SerialManager,
+        device: &ConnectedDevice,
+        source: impl Read + Send + 'static,
+    ) -> Vec<SerialEvent> {
+        let (tx, mut rx) = mpsc::channel(100);
+        manager.spawn_reader(device, Box::new(source), tx);
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn full_channel_keeps_only_the_newest_frame() {
+        let manager = SerialManager::new();
+        let device = test_device();
+        let input = concat!(
+            "{\"pot1\":1,\"pot2\":2,\"pot3\":3}\n",
+            "{\"pot1\":4,\"pot2\":5,\"pot3\":6}\n",
+            "{\"pot1\":7,\"pot2\":8,\"pot3\":9}\n",
+        );
+
+        // Nothing is received until the reader has queued the first frame and
+        // hit a full channel for the other two
+        let (tx, mut rx) = mpsc::channel(1);
+        manager.spawn_reader(&device, Box::new(Cursor::new(input)), tx);
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+
+        assert_eq!(readings(&events), vec![vec![1, 2, 3], vec![7, 8, 9]]);
+        assert_eq!(device.status().dropped_frame_count, 1);
+    }
+
+    #[tokio::test]
+    async fn full_channel_keeps_encoder_turns_and_button_presses() {
+        let manager = SerialManager::new();
+        let device = test_device();
+        let input = concat!(
+            "{\"pot1\":1,\"pot2\":2,\"pot3\":3,\"enc1\":1}\n",
+            "{\"pot1\":1,\"pot2\":2,\"pot3\":3,\"enc1\":2}\n",
+            "{\"pot1\":1,\"pot2\":2,\"pot3\":3,\"enc1\":3}\n",
+            "{\"pot1\":1,\"pot2\":2,\"pot3\":3,\"btn1\":true}\n",
+            "{\"pot1\":1,\"pot2\":2,\"pot3\":3,\"btn1\":false}\n",
+        );
+
+        let (tx, mut rx) = mpsc::channel(1);
+        manager.spawn_reader(&device, Box::new(Cursor::new(input)), tx);
+        let mut frames = Vec::new();
+        while let Some(event) = rx.recv().await {
+            if let SerialEvent::Data(data) = event {
+                frames.push((data.enc1, data.btn1));
+            }
+        }
+
+        // The second and third turns are merged, the press and release both arrive
+        assert_eq!(
+            frames,
+            vec![
+                (Some(1), false),
+                (Some(5), false),
+                (None, true),
+                (None, false)
+            ]
+        );
+        assert_eq!(device.status().dropped_frame_count, 1);
+    }
+
+    fn readings(events: &[SerialEvent]) -> Vec<Vec<u16>> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                SerialEvent::Data(data) => Some(data.raw_values()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn valid_lines_arrive_in_order() {
+        let manager = SerialManager::new();
+        let device = test_device();
+        let input = "{\"pot1\":1,\"pot2\":2,\"pot3\":3}\n{\"pot1\":4,\"pot2\":5,\"pot3\":6}\n";
+
+        let events = read_all(&manager, &device, Cursor::new(input)).await;
+
+        assert_eq!(readings(&events), vec![vec![1, 2, 3], vec![4, 5, 6]]);
+    }
+
+    #[tokio::test]
+    async fn lines_split_across_reads_are_reassembled() {
+        let manager = SerialManager::new();
+        let device = test_device();
+        let source = ChunkedReader::new(&[
+            b"{\"pot1\":10,\"po",
+            b"t2\":20,\"pot3\":30}\n{\"pot1\"",
+            b":11,\"pot2\":21,\"pot3\":31}",
+            b"\n",
+        ]);
+
+        let events = read_all(&manager, &device, source).await;
+
+        assert_eq!(readings(&events), vec![vec![10, 20, 30], vec![11, 21, 31]]);
+    }
+
+    #[tokio::test]
+    async fn failed_read_reports_a_disconnect_and_clears_the_stall_clock() {
+        let manager = SerialManager::new();
+        let device = test_device();
+        let input = Cursor::new("{\"pot1\":1,\"pot2\":2,\"pot3\":3}\n").chain(Unplugged);
+
+        let events = read_all(&manager, &device, input).await;
+
+        assert_eq!(readings(&events), vec![vec![1, 2, 3]]);
+        assert!(matches!(
+            events.last(),
+            Some(SerialEvent::Disconnected(status)) if !status.connected
+        ));
+        assert!(lock(&device.last_data_instant).is_none());
+    }
+
+    #[tokio::test]
+    async fn disconnecting_one_port_leaves_the_others() {
+        let manager = SerialManager::new();
+        for port in ["COM3", "COM4"] {
+            let device = ConnectedDevice::new(port.to_string(), None, None);
+            lock(&manager.devices).insert(port.to_string(), device);
+        }
+
+        manager.disconnect("COM3");
+
+        let ports: Vec<String> = lock(&manager.devices).keys().cloned().collect();
+        assert_eq!(ports, vec!["COM4".to_string()]);
+        assert_eq!(
+            manager.get_status(Some("COM4")).port.as_deref(),
+            Some("COM4")
+        );
+        let (tx, _rx) = mpsc::channel(1);
+        assert!(manager.start_reading("COM3", tx).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn devices_read_side_by_side_keep_their_own_counts() {
+        let manager = SerialManager::new();
+        let first = ConnectedDevice::new("COM3".to_string(), None, None);
+        let second = ConnectedDevice::new("COM4".to_string(), None, None);
+
+        let (first_events, second_events) = tokio::join!(
+            read_all(
+                &manager,
+                &first,
+                Cursor::new("{\"pot1\":1,\"pot2\":2,\"pot3\":3}\n")
+            ),
+            read_all(
+                &manager,
+                &second,
+                Cursor::new("{\"pot1\":4,\"pot2\n{\"pot1\":7,\"pot2\":8,\"pot3\":9}\n")
+            ),
+        );
+
+        assert_eq!(readings(&first_events), vec![vec![1, 2, 3]]);
+        assert_eq!(readings(&second_events), vec![vec![7, 8, 9]]);
+        assert_eq!(first.status().parse_error_count, 0);
+        assert_eq!(second.status().parse_error_count, 1);
+    }
+
+    #[tokio::test]
+    async fn unterminated_line_is_held_back() {
+        let manager = SerialManager::new();
+        let device = test_device();
+        let input = "{\"pot1\":1,\"pot2\":2,\"pot3\":3}\n{\"pot1\":4,\"pot2\":5";
+
+        let events = read_all(&manager, &device, Cursor::new(input)).await;
+
+        assert_eq!(readings(&events), vec![vec![1, 2, 3]]);
+    }
+
+    #[tokio::test]
+    async fn garbage_between_frames_is_skipped_or_reported() {
+        let manager = SerialManager::new();
+        let device = test_device();
+        let input = concat!(
+            "Hello, World!\r\n",
+            "{\"pot1\":1,\"pot2\":2,\"pot3\":3}\n",
+            "{\"device\":\"pc-audio-mixer\",\"fw\":\"0.1.0\",\"channels\":3}\n",
+            "{\"pot1\":4,\"pot2\n",
+            "{\"pot1\":7,\"pot2\":8,\"pot3\":9}\n",
+        );
+
+        let events = read_all(&manager, &device, Cursor::new(input)).await;
+
+        assert_eq!(readings(&events), vec![vec![1, 2, 3], vec![7, 8, 9]]);
+        let parse_errors = events
+            .iter()
+            .filter(|event| matches!(event, SerialEvent::ParseError(_)))
+            .count();
+        assert_eq!(parse_errors, 1);
+        assert_eq!(device.status().parse_error_count, 1);
+    }
+
+    #[tokio::test]
+    async fn frames_with_a_bad_checksum_are_counted_and_dropped() {
+        let manager = SerialManager::new();
+        let device = test_device();
+        let body = "{\"pot1\":1,\"pot2\":2,\"pot3\":3";
+        let input = format!(
+            "{},\"crc\":{}}}\n{},\"crc\":{}}}\n",
+            body,
+            crc8(body.as_bytes()).wrapping_add(1),
+            body,
+            crc8(body.as_bytes())
+        );
+
+        let events = read_all(&manager, &device, Cursor::new(input)).await;
+
+        assert_eq!(readings(&events), vec![vec![1, 2, 3]]);
+        assert_eq!(device.status().crc_rejection_count, 1);
+    }
+
+    #[tokio::test]
+    async fn frames_above_the_adc_range_are_counted_and_dropped() {
+        let manager = SerialManager::new();
+        let device = test_device();
+        let input = "{\"pot1\":4095,\"pot2\":0,\"pot3\":0}\n\
+                     {\"pot1\":4096,\"pot2\":0,\"pot3\":0}\n\
+                     {\"pot1\":1,\"pot2\":65535,\"pot3\":0}\n";
+
+        let events = read_all(&manager, &device, Cursor::new(input)).await;
+
+        assert_eq!(readings(&events), vec![vec![4095, 0, 0]]);
+        assert_eq!(device.status().out_of_range_count, 2);
+        assert_eq!(device.status().parse_error_count, 0);
+    }
+
+    #[tokio::test]
+    async fn binary_frames_split_across_reads_are_decoded() {
+        let manager = SerialManager::new();
+        let device = test_device();
+        manager.set_protocol(SerialProtocol::Binary);
+
+        let mut frame = vec![0xA5, 3];
+        for value in [100u16, 200, 300] {
+            frame.extend_from_slice(&value.to_le_bytes());
+        }
+        frame.push(crc8(&frame[1..]));
+        let source = ChunkedReader::new(&[&[0x42, 0x00], &frame[..4], &frame[4..]]);
+
+        let events = read_all(&manager, &device, source).await;
+
+        assert_eq!(readings(&events), vec![vec![100, 200, 300]]);
+    }
+
+    #[test]
+    fn moves_to_either_end_of_travel_pass_the_deadband() {
+        let settings = SmoothingSettings {
+            smoothing_factor: 0.9,
+            deadband: 50,
+        };
+        let mut smoother = PotSmoother::new();
+        let mut send = |values: &[u16]| {
+            smoother
+                .process(&PotentiometerData::from_raw_values(values), settings)
+                .map(|data| data.raw_values())
+        };
+
+        assert_eq!(send(&[30, 4060, 2048]), Some(vec![30, 4060, 2048]));
+        // Within the deadband and damped by the average, but at the rails
+        assert_eq!(send(&[0, 4095, 2048]), Some(vec![0, 4095, 2048]));
+        assert_eq!(send(&[0, 4095, 2060]), None);
+    }
+}
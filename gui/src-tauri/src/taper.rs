@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+// Steepness of the logarithmic curve; ln(1000) gives roughly a 60 dB range
+const LOG_CURVE_STEEPNESS: f32 = 6.907_755;
+
+/// Response curve applied between fader position and output volume
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VolumeTaper {
+    #[default]
+    Linear,
+    /// Audio taper: fine control at low volume, matching how loudness is perceived
+    Logarithmic,
+    /// Flat near both ends for fine control around silence and full volume
+    SShape,
+}
+
+impl VolumeTaper {
+    /// Maps a fader position in `0.0..=1.0` to a volume scalar in `0.0..=1.0`
+    pub fn apply(self, position: f32) -> f32 {
+        let x = position.clamp(0.0, 1.0);
+        match self {
+            VolumeTaper::Linear => x,
+            VolumeTaper::Logarithmic => {
+                (LOG_CURVE_STEEPNESS * x).exp_m1() / LOG_CURVE_STEEPNESS.exp_m1()
+            }
+            VolumeTaper::SShape => {
+                let smooth = x * x * (3.0 - 2.0 * x);
+                smooth * smooth
+            }
+        }
+    }
+
+    /// Fader position in `0.0..=1.0` that `apply` maps to `volume`
+    pub fn invert(self, volume: f32) -> f32 {
+        let y = volume.clamp(0.0, 1.0);
+        match self {
+            VolumeTaper::Linear => y,
+            VolumeTaper::Logarithmic => {
+                (y * LOG_CURVE_STEEPNESS.exp_m1()).ln_1p() / LOG_CURVE_STEEPNESS
+            }
+            // Closed-form inverse of the smoothstep
+            VolumeTaper::SShape => 0.5 - ((1.0 - 2.0 * y.sqrt()).asin() / 3.0).sin(),
+        }
+    }
+}
+
+/// Gains of the two sides of a crossfade at a position in `0.0..=1.0`: side A
+/// at full volume at 0.0, side B at 1.0. Equal power keeps the combined
+/// loudness steady through the middle, where linear dips.
+pub fn crossfade_gains(position: f32, equal_power: bool) -> (f32, f32) {
+    let x = position.clamp(0.0, 1.0);
+    if equal_power {
+        let angle = x * std::f32::consts::FRAC_PI_2;
+        // cos(pi/2) comes out a hair below zero in f32
+        (angle.cos().max(0.0), angle.sin())
+    } else {
+        (1.0 - x, x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [VolumeTaper; 3] = [
+        VolumeTaper::Linear,
+        VolumeTaper::Logarithmic,
+        VolumeTaper::SShape,
+    ];
+
+    #[test]
+    fn endpoints_map_to_silence_and_full_volume() {
+        for taper in ALL {
+            assert!(taper.apply(0.0).abs() < 1e-6, "{:?} at 0.0", taper);
+            assert!((taper.apply(1.0) - 1.0).abs() < 1e-6, "{:?} at 1.0", taper);
+        }
+    }
+
+    #[test]
+    fn out_of_range_positions_are_clamped() {
+        for taper in ALL {
+            assert_eq!(taper.apply(-0.5), taper.apply(0.0));
+            assert_eq!(taper.apply(1.5), taper.apply(1.0));
+        }
+    }
+
+    #[test]
+    fn invert_undoes_apply() {
+        for taper in ALL {
+            for step in 0..=20 {
+                let position = step as f32 / 20.0;
+                let back = taper.invert(taper.apply(position));
+                assert!(
+                    (back - position).abs() < 1e-3,
+                    "{:?} at {}",
+                    taper,
+                    position
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn midpoint_differs_per_curve() {
+        let linear = VolumeTaper::Linear.apply(0.5);
+        let log = VolumeTaper::Logarithmic.apply(0.5);
+        let s_shape = VolumeTaper::SShape.apply(0.5);
+
+        assert!((linear - 0.5).abs() < 1e-6);
+        assert!(log < linear);
+        assert!((log - s_shape).abs() > 0.01);
+        assert!((s_shape - linear).abs() > 0.01);
+    }
+
+    #[test]
+    fn curves_are_monotonic() {
+        for taper in ALL {
+            let mut previous = taper.apply(0.0);
+            for step in 1..=100 {
+                let value = taper.apply(step as f32 / 100.0);
+                assert!(value >= previous, "{:?} decreased at step {}", taper, step);
+                previous = value;
+            }
+        }
+    }
+
+    #[test]
+    fn crossfade_endpoints_give_one_side_only() {
+        for equal_power in [false, true] {
+            assert_eq!(crossfade_gains(0.0, equal_power), (1.0, 0.0));
+            assert_eq!(crossfade_gains(1.0, equal_power), (0.0, 1.0));
+            assert_eq!(crossfade_gains(-1.0, equal_power), (1.0, 0.0));
+            assert_eq!(crossfade_gains(2.0, equal_power), (0.0, 1.0));
+        }
+    }
+
+    #[test]
+    fn linear_crossfade_gains_sum_to_one() {
+        assert_eq!(crossfade_gains(0.5, false), (0.5, 0.5));
+        for step in 0..=100 {
+            let (a, b) = crossfade_gains(step as f32 / 100.0, false);
+            assert!((a + b - 1.0).abs() < 1e-6, "step {}", step);
+        }
+    }
+
+    #[test]
+    fn equal_power_crossfade_keeps_power_constant() {
+        let (a, b) = crossfade_gains(0.5, true);
+        assert!((a - b).abs() < 1e-6);
+        assert!((a - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+
+        let mut previous_b = 0.0;
+        for step in 0..=100 {
+            let (a, b) = crossfade_gains(step as f32 / 100.0, true);
+            assert!((a * a + b * b - 1.0).abs() < 1e-5, "step {}", step);
+            assert!(b >= previous_b, "B decreased at step {}", step);
+            previous_b = b;
+        }
+    }
+}
@@ -1,33 +1,256 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::logging::LogLevel;
+use crate::meter::MeterScale;
+use crate::protocol::SerialProtocol;
+use crate::taper::VolumeTaper;
+
+/// Full-scale reading of the Pico's 12-bit ADC
+pub const ADC_MAX: u16 = 4095;
+/// Granularity of fader percentages unless the config picks another
+pub const DEFAULT_VOLUME_STEP: f32 = 2.0;
+
+/// Rounds `percentage` to the nearest multiple of `step`. Steps that divide
+/// 100 reach both ends exactly; a non-positive step leaves it unrounded.
+pub fn quantize(percentage: f32, step: f32) -> f32 {
+    if step > 0.0 {
+        (percentage / step).round() * step
+    } else {
+        percentage
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PotentiometerData {
     pub pot1: u16,
     pub pot2: u16,
     pub pot3: u16,
+    // Extra channels on larger boards (e.g. the MCP3008 build), absent on the
+    // three-pot one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pot4: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pot5: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pot6: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pot7: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pot8: Option<u16>,
+    // Mute buttons, absent from older firmware and the binary protocol
+    #[serde(default)]
+    pub btn1: bool,
+    #[serde(default)]
+    pub btn2: bool,
+    #[serde(default)]
+    pub btn3: bool,
+    // Encoder detents since the previous frame; present only for channels
+    // fitted with an encoder, whose pot value is then ignored
+    #[serde(default)]
+    pub enc1: Option<i32>,
+    #[serde(default)]
+    pub enc2: Option<i32>,
+    #[serde(default)]
+    pub enc3: Option<i32>,
+    // Sent by firmware built with the `diagnostics` feature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temp_c: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uptime_ms: Option<u64>,
 }
 
 impl PotentiometerData {
-    pub fn to_percentages(&self) -> (f32, f32, f32) {
-        // Helper function to round to nearest 2%
-        let round_to_2 = |val: f32| -> f32 {
-            let percentage = (val / 4095.0) * 100.0;
-            (percentage / 2.0).round() * 2.0
+    /// Checks every pot reading fits the 12-bit ADC. A frame with one out of
+    /// range is rejected whole rather than clamped: the value can only come
+    /// from a corrupt frame or faulty hardware, so the rest of it isn't
+    /// trustworthy either, and clamping would turn the fault into a jump to 100%.
+    pub fn validate(&self) -> Result<(), String> {
+        let out_of_range = self
+            .raw_values()
+            .into_iter()
+            .enumerate()
+            .find(|&(_, value)| value > ADC_MAX);
+        match out_of_range {
+            Some((index, value)) => Err(format!(
+                "pot{} reading {} is above the ADC maximum of {}",
+                index + 1,
+                value,
+                ADC_MAX
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Raw ADC readings indexed by `channel_id - 1`, one per channel the
+    /// frame carried
+    pub fn raw_values(&self) -> Vec<u16> {
+        let extra = [self.pot4, self.pot5, self.pot6, self.pot7, self.pot8];
+        [self.pot1, self.pot2, self.pot3]
+            .into_iter()
+            .chain(extra.into_iter().map_while(|pot| pot))
+            .collect()
+    }
+
+    pub fn from_raw_values(values: &[u16]) -> Self {
+        let value = |i: usize| values.get(i).copied().unwrap_or(0);
+        Self {
+            pot1: value(0),
+            pot2: value(1),
+            pot3: value(2),
+            pot4: values.get(3).copied(),
+            pot5: values.get(4).copied(),
+            pot6: values.get(5).copied(),
+            pot7: values.get(6).copied(),
+            pot8: values.get(7).copied(),
+            btn1: false,
+            btn2: false,
+            btn3: false,
+            enc1: None,
+            enc2: None,
+            enc3: None,
+            temp_c: None,
+            uptime_ms: None,
+        }
+    }
+
+    /// Health readings carried by the frame, if the firmware sends any
+    pub fn diagnostics(&self) -> Option<DeviceDiagnostics> {
+        (self.temp_c.is_some() || self.uptime_ms.is_some()).then_some(DeviceDiagnostics {
+            temp_c: self.temp_c,
+            uptime_ms: self.uptime_ms,
+        })
+    }
+
+    /// Button states indexed by `channel_id - 1`, `true` while pressed
+    pub fn buttons(&self) -> Vec<bool> {
+        vec![self.btn1, self.btn2, self.btn3]
+    }
+
+    /// Encoder deltas indexed by `channel_id - 1`, `None` for pot channels
+    pub fn encoder_deltas(&self) -> Vec<Option<i32>> {
+        vec![self.enc1, self.enc2, self.enc3]
+    }
+
+    /// Channel percentages indexed by `channel_id - 1`, using the calibrated
+    /// range for each channel (full ADC range where none is stored), rounded
+    /// to multiples of `step`
+    pub fn to_percentages_calibrated(
+        &self,
+        calibration: &[CalibrationData],
+        step: f32,
+    ) -> Vec<f32> {
+        self.raw_values()
+            .into_iter()
+            .enumerate()
+            .map(|(i, raw)| {
+                let percentage = calibration
+                    .get(i)
+                    .copied()
+                    .unwrap_or_default()
+                    .to_percentage(raw);
+                quantize(percentage, step)
+            })
+            .collect()
+    }
+}
+
+/// Observed ADC range of a physical fader
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct CalibrationData {
+    pub min: u16,
+    pub max: u16,
+}
+
+impl CalibrationData {
+    /// Starting point for a sweep, so the first reading sets both bounds
+    pub fn empty() -> Self {
+        Self {
+            min: ADC_MAX,
+            max: 0,
+        }
+    }
+
+    pub fn observe(&mut self, raw: u16) {
+        self.min = self.min.min(raw);
+        self.max = self.max.max(raw);
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.max > self.min
+    }
+
+    /// Maps `[min, max]` onto `[0, 100]`, clamped but not rounded
+    pub fn to_percentage(self, raw: u16) -> f32 {
+        let cal = if self.is_valid() {
+            self
+        } else {
+            Self::default()
         };
 
-        (
-            round_to_2(self.pot1 as f32),
-            round_to_2(self.pot2 as f32),
-            round_to_2(self.pot3 as f32),
-        )
+        let clamped = raw.clamp(cal.min, cal.max);
+        (clamped - cal.min) as f32 / (cal.max - cal.min) as f32 * 100.0
+    }
+
+    /// Reading at `percentage` of `[min, max]`, the inverse of `to_percentage`
+    pub fn to_raw(self, percentage: f32) -> u16 {
+        let cal = if self.is_valid() {
+            self
+        } else {
+            Self::default()
+        };
+
+        let span = f32::from(cal.max - cal.min);
+        cal.min + (percentage.clamp(0.0, 100.0) / 100.0 * span).round() as u16
+    }
+}
+
+impl Default for CalibrationData {
+    fn default() -> Self {
+        Self {
+            min: 0,
+            max: ADC_MAX,
+        }
     }
 }
 
+/// Health readings from firmware built with the `diagnostics` feature
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct DeviceDiagnostics {
+    /// RP2040 die temperature
+    pub temp_c: Option<f32>,
+    /// Time since the device booted; a drop means it reset
+    pub uptime_ms: Option<u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MixerChannel {
+    /// Unique across devices; the second device's channels follow the first's
     pub id: usize,
+    /// Channel number on its own device, as used by mappings and calibration
+    pub channel: usize,
     pub value: f32, // 0.0 to 100.0
     pub is_physical: bool,
+    /// User-given name, e.g. "Game"
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Port of the device the channel is on; `None` while nothing is connected
+    #[serde(default)]
+    pub device: Option<String>,
+}
+
+/// Event payload tagged with the port of the device it came from
+#[derive(Debug, Clone, Serialize)]
+pub struct FromDevice<T> {
+    pub port: String,
+    #[serde(flatten)]
+    pub payload: T,
+}
+
+/// Payload of the `serial-raw` event, inside `FromDevice`
+#[derive(Debug, Clone, Serialize)]
+pub struct RawSerialLine {
+    pub line: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -37,6 +260,99 @@ pub struct AudioSession {
     pub display_name: String,
     pub volume: f32, // 0.0 to 100.0
     pub is_muted: bool,
+    /// Sessions merged into this entry; more than 1 for multi-process apps
+    #[serde(default = "default_session_count")]
+    pub session_count: usize,
+}
+
+/// How `get_audio_sessions_filtered` trims and orders the session list
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct SessionFilter {
+    #[serde(default)]
+    pub sort_by_name: bool,
+    #[serde(default)]
+    pub hide_muted: bool,
+    /// Hide sessions at zero volume
+    #[serde(default)]
+    pub hide_silent: bool,
+}
+
+/// Payload of the `audio-sessions-delta` event: how the session list changed
+/// since the previous poll
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SessionDelta {
+    pub added: Vec<AudioSession>,
+    /// Sessions whose volume, mute state or other fields differ, in full
+    pub changed: Vec<AudioSession>,
+    /// Process IDs of sessions that went away
+    pub removed: Vec<u32>,
+}
+
+impl SessionDelta {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+fn default_session_count() -> usize {
+    1
+}
+
+/// What the active audio backend can actually do
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AudioCapabilities {
+    pub per_app_volume: bool,
+    pub mute: bool,
+    pub metering: bool,
+}
+
+/// Which audio backend is active and where, for platform-specific UI and
+/// bug reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendInfo {
+    /// `AudioManager::backend_name`
+    pub name: String,
+    /// `std::env::consts::OS`, e.g. "windows", "macos" or "linux"
+    pub os: String,
+    /// Whether the backend is a stand-in that doesn't reach the OS
+    pub is_fallback: bool,
+    pub capabilities: AudioCapabilities,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SelfTestOutcome {
+    Passed,
+    Failed,
+    /// The backend doesn't support it, or there was nothing to try it on
+    Skipped,
+}
+
+/// One operation tried by `self_test`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestStep {
+    pub name: String,
+    pub outcome: SelfTestOutcome,
+    /// What was read back, or why the step failed or was skipped
+    pub detail: String,
+}
+
+/// Result of `self_test`, for the diagnostics panel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub capabilities: AudioCapabilities,
+    pub steps: Vec<SelfTestStep>,
+    /// Whether no step failed
+    pub passed: bool,
+}
+
+/// An audio output endpoint a channel can be mapped to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AudioDevice {
+    /// Backend-specific endpoint ID, stable across restarts
+    pub id: String,
+    pub name: String,
+    pub is_default: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,17 +361,1000 @@ pub struct SerialPortInfo {
     pub description: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Identity reported by the firmware in reply to `ID?`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeviceInfo {
+    pub device: String,
+    /// Firmware version
+    pub fw: String,
+    /// Number of fader channels the hardware has
+    pub channels: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ConnectionStatus {
     pub connected: bool,
     pub port: Option<String>,
     pub error: Option<String>,
+    /// `MixerError` code for `error`, e.g. `serial_busy`
+    #[serde(default)]
+    pub error_code: Option<String>,
+    #[serde(default)]
+    pub parse_error_count: u64,
+    #[serde(default)]
+    pub last_parse_error: Option<String>,
+    /// Frames dropped because their checksum didn't match
+    #[serde(default)]
+    pub crc_rejection_count: u64,
+    /// Frames dropped because a reading was above the ADC range
+    #[serde(default)]
+    pub out_of_range_count: u64,
+    /// Frames skipped because a newer one arrived before the app caught up
+    #[serde(default)]
+    pub dropped_frame_count: u64,
+    /// What the connected device identified itself as
+    #[serde(default)]
+    pub device: Option<DeviceInfo>,
+}
+
+/// Payload of the `mapped-app-appeared` and `mapped-app-disappeared` events:
+/// a session controlled by a channel started or went away
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MappedApp {
+    pub channel_id: usize,
+    /// Port of the device the mapping is limited to, if any
+    pub device: Option<String>,
+    pub process_id: u32,
+    pub process_name: String,
+}
+
+/// Payload of the `mapping-rematched` event: an app mapping's session went away,
+/// e.g. recreated by the app after an output device change, and the mapping
+/// was pointed at a running session of the same executable
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RematchedMapping {
+    pub channel_id: usize,
+    /// Port of the device the mapping is limited to, if any
+    pub device: Option<String>,
+    pub process_name: String,
+    pub old_process_id: u32,
+    pub new_process_id: u32,
+}
+
+impl RematchedMapping {
+    /// Points the mappings this came from at the new session, returning
+    /// whether any matched
+    pub fn apply(&self, mappings: &mut [ChannelMapping]) -> bool {
+        let mut matched = false;
+        for mapping in mappings.iter_mut().filter(|m| {
+            m.channel_id == self.channel_id
+                && m.device == self.device
+                && m.target == ChannelTarget::App(self.old_process_id)
+        }) {
+            mapping.target = ChannelTarget::App(self.new_process_id);
+            matched = true;
+        }
+        matched
+    }
+}
+
+/// Payload of the `serial-stalled` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerialStalled {
+    pub port: Option<String>,
+    pub seconds_since_data: f32,
+}
+
+/// Payload of the `identified-channel` event: the fader moved during
+/// `identify_channels`, or `null` if none moved in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentifiedChannel {
+    pub port: String,
+    pub channel_id: usize,
+    /// How far the fader moved, in percentage points
+    pub delta: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerialParseError {
+    pub line: String,
+    pub error: String,
+    pub count: u64,
+}
+
+/// What a physical channel controls
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub enum ChannelTarget {
+    #[default]
+    Master,
+    /// The default capture device, e.g. a microphone
+    Input,
+    /// A specific output device by ID, e.g. headphones next to the speakers
+    Device(String),
+    App(u32),
+    /// Every session of the executables in the mapping's `process_names`,
+    /// whatever their PIDs
+    AppByName,
+    /// Whichever app is in the foreground, following focus as it moves
+    FollowFocus,
+    /// Fades from `app_a` at the bottom of the fader over to `app_b` at the top
+    Crossfade {
+        app_a: u32,
+        app_b: u32,
+        /// Keep the combined loudness steady instead of dipping mid-fade
+        #[serde(default)]
+        equal_power: bool,
+    },
+}
+
+/// What a global hotkey does when pressed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction {
+    ToggleMasterMute,
+    /// Toggle mute on everything mapped to the channel
+    ToggleChannelMute {
+        channel_id: usize,
+    },
+    /// Move the channel's volume by `delta` percentage points
+    AdjustChannelVolume {
+        channel_id: usize,
+        delta: f32,
+    },
+}
+
+/// An OS-wide keyboard shortcut bound to a mixer action
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HotkeyBinding {
+    /// Accelerator string, e.g. `CmdOrCtrl+Shift+M`
+    pub accelerator: String,
+    pub action: HotkeyAction,
+}
+
+/// Host-side smoothing applied to a channel's fader readings
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterKind {
+    #[default]
+    None,
+    /// Exponential moving average; lower `alpha` smooths more but lags more
+    Ema { alpha: f32 },
+    /// Median of the last `n` readings, which drops isolated spikes
+    Median { n: usize },
+}
+
+/// What a channel's mute button does when pressed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ButtonAction {
+    /// Toggle mute on the channel's own target
+    #[default]
+    ToggleMute,
+    /// Toggle `mute_all`/`unmute_all`
+    ToggleMuteAll,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChannelMapping {
+    pub channel_id: usize,
+    // Legacy mappings have no target; they start out by name with no names,
+    // which controls nothing until `migrate_legacy_target` fills them in
+    #[serde(default = "unassigned_target")]
+    pub target: ChannelTarget,
+    pub process_name: Option<String>,
+    /// Executables controlled together, e.g. every Chrome process
+    #[serde(default)]
+    pub process_names: Vec<String>,
+    /// Set for faders mounted upside-down
+    #[serde(default)]
+    pub inverted: bool,
+    #[serde(default)]
+    pub taper: VolumeTaper,
+    #[serde(default)]
+    pub button_action: ButtonAction,
+    /// Glides volume changes over this many milliseconds instead of jumping;
+    /// 0 applies them instantly
+    #[serde(default)]
+    pub ramp_ms: u32,
+    #[serde(default)]
+    pub filter: FilterKind,
+    /// Volume at the bottom of the fader's travel
+    #[serde(default)]
+    pub min_volume: f32,
+    /// Volume at the top of the fader's travel
+    #[serde(default = "default_max_volume")]
+    pub max_volume: f32,
+    /// Mute the target at the bottom of the fader's travel instead of setting
+    /// it to `min_volume`, and unmute it once the fader moves up again, as
+    /// set by `AppConfig::mute_thresholds`
+    #[serde(default)]
+    pub mute_at_zero: bool,
+    /// Port of the fader bank this channel belongs to; `None` matches the
+    /// channel on every connected device
+    #[serde(default)]
+    pub device: Option<String>,
+    // Pre-`target` fields, only read so older configs can be migrated
+    #[serde(default, skip_serializing)]
+    pub is_master: bool,
+    #[serde(default, skip_serializing)]
+    pub process_id: Option<u32>,
+}
+
+fn default_max_volume() -> f32 {
+    100.0
+}
+
+fn unassigned_target() -> ChannelTarget {
+    ChannelTarget::AppByName
+}
+
+impl Default for ChannelMapping {
+    fn default() -> Self {
+        Self {
+            channel_id: 0,
+            target: ChannelTarget::default(),
+            process_name: None,
+            process_names: Vec::new(),
+            inverted: false,
+            taper: VolumeTaper::default(),
+            button_action: ButtonAction::default(),
+            ramp_ms: 0,
+            filter: FilterKind::default(),
+            min_volume: 0.0,
+            max_volume: default_max_volume(),
+            mute_at_zero: false,
+            device: None,
+            is_master: false,
+            process_id: None,
+        }
+    }
+}
+
+impl ChannelMapping {
+    pub fn new(channel_id: usize, target: ChannelTarget) -> Self {
+        Self {
+            channel_id,
+            target,
+            ..Default::default()
+        }
+    }
+
+    /// Converts the legacy `is_master`/`process_id` pair into a `target`.
+    /// App mappings saved with only a process name match it by name, and
+    /// ones with neither stay unassigned. Mappings saved with a `target`
+    /// are left alone.
+    pub fn migrate_legacy_target(&mut self) {
+        match (self.is_master, self.process_id.take()) {
+            (true, _) => self.target = ChannelTarget::Master,
+            (false, Some(process_id)) => self.target = ChannelTarget::App(process_id),
+            (false, None) => {
+                if self.target == ChannelTarget::AppByName && self.process_names.is_empty() {
+                    self.process_names.extend(self.process_name.clone());
+                }
+            }
+        }
+        self.is_master = false;
+    }
+
+    /// Whether this mapping listens to the device on `port`
+    pub fn applies_to_device(&self, port: &str) -> bool {
+        self.device.as_deref().is_none_or(|device| device == port)
+    }
+
+    /// Where the fader is along this mapping's travel, 0 at its bottom
+    pub fn fader_position(&self, fader: f32) -> f32 {
+        if self.inverted {
+            100.0 - fader
+        } else {
+            fader
+        }
+    }
+
+    /// Converts a fader percentage into the volume to apply to the target,
+    /// spreading the fader's whole travel over `min_volume..=max_volume`
+    pub fn target_volume(&self, fader: f32) -> f32 {
+        let position = self.fader_position(fader);
+        let min = self.min_volume.clamp(0.0, 100.0);
+        let max = self.max_volume.clamp(0.0, 100.0);
+        min + self.taper.apply(position / 100.0) * (max - min)
+    }
+
+    /// Fader percentage at which `target_volume` gives `volume`. Volumes
+    /// outside `min_volume..=max_volume` land on the nearer end of travel.
+    pub fn fader_for_volume(&self, volume: f32) -> f32 {
+        let min = self.min_volume.clamp(0.0, 100.0);
+        let max = self.max_volume.clamp(0.0, 100.0);
+        let position = if max == min {
+            0.0
+        } else {
+            self.taper.invert((volume - min) / (max - min)) * 100.0
+        };
+        // Flipping is its own inverse
+        self.fader_position(position)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    pub mappings: Vec<ChannelMapping>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileList {
+    pub active_profile: String,
+    pub profiles: Vec<Profile>,
 }
 
+pub const DEFAULT_PROFILE_NAME: &str = "Default";
+
+/// Bumped when a config change can't be read by older builds
+pub const CONFIG_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    /// Format version, so imports from newer builds can be rejected
+    #[serde(default = "default_config_version")]
+    pub version: u32,
     pub start_with_windows: bool,
     pub minimize_to_tray: bool,
     pub auto_connect: bool,
     pub theme: String,
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+    #[serde(default)]
+    pub active_profile: String,
+    // Flat mappings from configs written before profiles existed; only read
+    // so they can be migrated into the "Default" profile.
+    #[serde(default, skip_serializing)]
+    pub channel_mappings: Vec<ChannelMapping>,
+    /// Per-channel fader range, indexed by `channel_id - 1`
+    #[serde(default)]
+    pub calibration: Vec<CalibrationData>,
+    /// User-given channel names by channel id, shared by every profile
+    #[serde(default)]
+    pub channel_labels: HashMap<usize, String>,
+    /// Fader ranges of devices calibrated on their own, by port. A device's
+    /// table replaces `calibration` for it.
+    #[serde(default)]
+    pub device_calibration: HashMap<String, Vec<CalibrationData>>,
+    /// Channel names given on one device, by port, checked before `channel_labels`
+    #[serde(default)]
+    pub device_channel_labels: HashMap<String, HashMap<usize, String>>,
+    /// Weight of the previous value in the moving average (0.0 disables it)
+    #[serde(default = "default_smoothing_factor")]
+    pub smoothing_factor: f32,
+    /// Minimum change in raw ADC counts before a new value is forwarded
+    #[serde(default = "default_deadband")]
+    pub deadband: u16,
+    #[serde(default)]
+    pub protocol: SerialProtocol,
+    /// Port of the last successful connection, tried first on startup
+    #[serde(default)]
+    pub last_port: Option<String>,
+    /// Show one entry per executable instead of one per process
+    #[serde(default = "default_merge_sessions")]
+    pub merge_sessions: bool,
+    /// Last known volume of each app by lowercase process name, restored on launch
+    #[serde(default)]
+    pub session_volumes: HashMap<String, f32>,
+    /// Seconds without a valid frame before the port counts as stalled (0 disables)
+    #[serde(default = "default_stall_timeout_secs")]
+    pub stall_timeout_secs: u64,
+    /// Reopen the port when it stalls
+    #[serde(default = "default_reconnect_on_stall")]
+    pub reconnect_on_stall: bool,
+    /// Spacing of repeated connect attempts while no device answers
+    #[serde(default)]
+    pub reconnect_backoff: BackoffConfig,
+    #[serde(default)]
+    pub mute_thresholds: MuteThresholds,
+    /// Volume percentage other apps drop to while a channel is soloed
+    #[serde(default = "default_duck_level")]
+    pub duck_level: f32,
+    /// Seconds between checks for new or closed audio sessions
+    #[serde(default = "default_session_poll_interval_secs")]
+    pub session_poll_interval_secs: u64,
+    /// Global shortcuts, registered with the OS on startup
+    #[serde(default)]
+    pub hotkeys: Vec<HotkeyBinding>,
+    /// List Windows system sounds as a session that can be mapped
+    #[serde(default)]
+    pub expose_system_sounds: bool,
+    /// Executables left out of filtered session lists, e.g. system helpers
+    #[serde(default)]
+    pub session_blocklist: Vec<String>,
+    /// Flips every fader, for a slider bank mounted upside down; a channel's
+    /// own `inverted` flips it back
+    #[serde(default)]
+    pub invert_all_channels: bool,
+    /// Ignore a fader after its mappings change until it reaches the volume
+    /// its target already has
+    #[serde(default)]
+    pub soft_takeover: bool,
+    /// Drive motorized faders with `SET` commands when a mapped volume is
+    /// changed by something other than the fader
+    #[serde(default)]
+    pub motorized_faders: bool,
+    /// Unmute a muted target when its fader moves
+    #[serde(default)]
+    pub unmute_on_fader_move: bool,
+    #[serde(default)]
+    pub volume_priority: VolumePriority,
+    /// Percentage points that fader readings are rounded to
+    #[serde(default = "default_volume_step")]
+    pub volume_step: f32,
+    /// JSON control server for other tools; read on startup
+    #[serde(default)]
+    pub control_server: ControlServerConfig,
+    /// USB IDs of a custom firmware build, matched before the stock ones
+    #[serde(default)]
+    pub device_filter: Option<UsbDeviceFilter>,
+    /// Units of the peak-levels event
+    #[serde(default)]
+    pub meter_scale: MeterScale,
+    /// Bend peak meters towards perceived loudness
+    #[serde(default)]
+    pub meter_weighting: bool,
+    /// Where the main window was last left, restored on startup
+    #[serde(default)]
+    pub window: Option<WindowGeometry>,
+    /// Most verbose level written to the log
+    #[serde(default)]
+    pub log_level: LogLevel,
+}
+
+/// Window position and size in physical pixels
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UsbDeviceFilter {
+    pub vid: u16,
+    pub pid: u16,
+}
+
+/// What the device's onboard LED shows, sent as `LED ON|OFF|AUTO`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LedMode {
+    /// Off until USB is enumerated, solid while connected, blinking while streaming
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+impl LedMode {
+    pub fn command(self) -> &'static str {
+        match self {
+            LedMode::Auto => "LED AUTO",
+            LedMode::On => "LED ON",
+            LedMode::Off => "LED OFF",
+        }
+    }
+}
+
+/// Which side wins when the UI and a fader change the same channel in quick
+/// succession; the other is ignored for a moment
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum VolumePriority {
+    /// UI changes are refused while a fader on the channel is moving
+    #[serde(rename = "physical_priority")]
+    Physical,
+    /// Faders are ignored for a moment after the UI changes their channel
+    #[default]
+    #[serde(rename = "ui_priority")]
+    Ui,
+}
+
+/// Exponential backoff between reconnect attempts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BackoffConfig {
+    #[serde(default = "default_backoff_initial_ms")]
+    pub initial_ms: u64,
+    #[serde(default = "default_backoff_max_ms")]
+    pub max_ms: u64,
+    /// Growth of the delay after each failed attempt
+    #[serde(default = "default_backoff_multiplier")]
+    pub multiplier: f32,
+    /// Fraction each delay is randomly spread by, either way
+    #[serde(default = "default_backoff_jitter")]
+    pub jitter: f32,
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        Self {
+            initial_ms: default_backoff_initial_ms(),
+            max_ms: default_backoff_max_ms(),
+            multiplier: default_backoff_multiplier(),
+            jitter: default_backoff_jitter(),
+        }
+    }
+}
+
+/// When `mute_at_zero` targets mute and unmute, and how often a mute button
+/// may change state. The gap between the two fader thresholds keeps a noisy
+/// fader resting at the bottom from toggling the mute.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct MuteThresholds {
+    /// Fader position, in percent of travel, below which the target mutes
+    #[serde(default = "default_mute_below")]
+    pub mute_below: f32,
+    /// Position the fader has to rise above before the target unmutes
+    #[serde(default = "default_unmute_above")]
+    pub unmute_above: f32,
+    /// Shortest time between two accepted changes of a mute button
+    #[serde(default = "default_button_debounce_ms")]
+    pub button_debounce_ms: u64,
+}
+
+impl Default for MuteThresholds {
+    fn default() -> Self {
+        Self {
+            mute_below: default_mute_below(),
+            unmute_above: default_unmute_above(),
+            button_debounce_ms: default_button_debounce_ms(),
+        }
+    }
+}
+
+impl MuteThresholds {
+    /// Whether a `mute_at_zero` target should be muted with its fader at
+    /// `position`, given whether it is muted now
+    pub fn should_mute(&self, position: f32, muted: bool) -> bool {
+        if muted {
+            position <= self.unmute_above
+        } else {
+            position < self.mute_below
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ControlServerConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_control_server_port")]
+    pub port: u16,
+    /// Listen on every interface instead of only localhost
+    #[serde(default)]
+    pub allow_remote: bool,
+}
+
+impl Default for ControlServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_control_server_port(),
+            allow_remote: false,
+        }
+    }
+}
+
+fn default_config_version() -> u32 {
+    CONFIG_VERSION
+}
+
+fn default_session_poll_interval_secs() -> u64 {
+    2
+}
+
+fn default_smoothing_factor() -> f32 {
+    0.5
+}
+
+fn default_merge_sessions() -> bool {
+    true
+}
+
+fn default_stall_timeout_secs() -> u64 {
+    3
+}
+
+fn default_reconnect_on_stall() -> bool {
+    true
+}
+
+fn default_backoff_initial_ms() -> u64 {
+    500
+}
+
+fn default_backoff_max_ms() -> u64 {
+    10_000
+}
+
+fn default_backoff_multiplier() -> f32 {
+    2.0
+}
+
+fn default_backoff_jitter() -> f32 {
+    0.2
+}
+
+fn default_mute_below() -> f32 {
+    1.0
+}
+
+fn default_unmute_above() -> f32 {
+    3.0
+}
+
+fn default_button_debounce_ms() -> u64 {
+    50
+}
+
+fn default_duck_level() -> f32 {
+    20.0
+}
+
+fn default_volume_step() -> f32 {
+    DEFAULT_VOLUME_STEP
+}
+
+fn default_control_server_port() -> u16 {
+    9470
+}
+
+fn default_deadband() -> u16 {
+    12
+}
+
+impl AppConfig {
+    pub fn active_mappings(&self) -> Vec<ChannelMapping> {
+        self.profiles
+            .iter()
+            .find(|p| p.name == self.active_profile)
+            .map(|p| p.mappings.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn calibration_tables(&self) -> PerDevice<Vec<CalibrationData>> {
+        PerDevice {
+            shared: self.calibration.clone(),
+            by_port: self.device_calibration.clone(),
+        }
+    }
+
+    pub fn label_tables(&self) -> PerDevice<HashMap<usize, String>> {
+        PerDevice {
+            shared: self.channel_labels.clone(),
+            by_port: self.device_channel_labels.clone(),
+        }
+    }
+}
+
+/// A per-channel table shared by every device, plus tables for devices set
+/// up on their own
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PerDevice<T> {
+    pub shared: T,
+    pub by_port: HashMap<String, T>,
+}
+
+impl<T> PerDevice<T> {
+    /// The table of the device on `port`, or the shared one if it has none
+    pub fn for_port(&self, port: Option<&str>) -> &T {
+        port.and_then(|port| self.by_port.get(port))
+            .unwrap_or(&self.shared)
+    }
+}
+
+impl PerDevice<HashMap<usize, String>> {
+    /// Name of a channel on `port`, falling back to the shared name
+    pub fn label(&self, port: Option<&str>, channel_id: usize) -> Option<&String> {
+        self.for_port(port)
+            .get(&channel_id)
+            .or_else(|| self.shared.get(&channel_id))
+    }
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            start_with_windows: false,
+            minimize_to_tray: true,
+            auto_connect: true,
+            theme: "dark".to_string(),
+            profiles: vec![Profile {
+                name: DEFAULT_PROFILE_NAME.to_string(),
+                // Pot 1 drives master volume out of the box
+                mappings: vec![ChannelMapping::new(1, ChannelTarget::Master)],
+            }],
+            active_profile: DEFAULT_PROFILE_NAME.to_string(),
+            channel_mappings: Vec::new(),
+            calibration: Vec::new(),
+            channel_labels: HashMap::new(),
+            device_calibration: HashMap::new(),
+            device_channel_labels: HashMap::new(),
+            smoothing_factor: default_smoothing_factor(),
+            deadband: default_deadband(),
+            protocol: SerialProtocol::default(),
+            last_port: None,
+            merge_sessions: default_merge_sessions(),
+            session_volumes: HashMap::new(),
+            stall_timeout_secs: default_stall_timeout_secs(),
+            reconnect_on_stall: default_reconnect_on_stall(),
+            reconnect_backoff: BackoffConfig::default(),
+            mute_thresholds: MuteThresholds::default(),
+            duck_level: default_duck_level(),
+            session_poll_interval_secs: default_session_poll_interval_secs(),
+            hotkeys: Vec::new(),
+            expose_system_sounds: false,
+            session_blocklist: Vec::new(),
+            invert_all_channels: false,
+            soft_takeover: false,
+            motorized_faders: false,
+            unmute_on_fader_move: false,
+            volume_priority: VolumePriority::default(),
+            volume_step: default_volume_step(),
+            control_server: ControlServerConfig::default(),
+            device_filter: None,
+            meter_scale: MeterScale::default(),
+            meter_weighting: false,
+            window: None,
+            log_level: LogLevel::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pots(pot1: u16, pot2: u16, pot3: u16) -> PotentiometerData {
+        PotentiometerData::from_raw_values(&[pot1, pot2, pot3])
+    }
+
+    /// Percentages of an uncalibrated frame
+    fn uncalibrated(data: PotentiometerData, step: f32) -> Vec<f32> {
+        data.to_percentages_calibrated(&[], step)
+    }
+
+    #[test]
+    fn to_percentages_maps_endpoints_exactly() {
+        assert_eq!(
+            uncalibrated(pots(0, ADC_MAX, 0), DEFAULT_VOLUME_STEP),
+            vec![0.0, 100.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn to_percentages_midpoint_is_fifty() {
+        assert_eq!(
+            uncalibrated(pots(2047, 2048, 2049), DEFAULT_VOLUME_STEP),
+            vec![50.0, 50.0, 50.0]
+        );
+    }
+
+    #[test]
+    fn to_percentages_rounds_to_two_percent_steps() {
+        let mut previous = 0.0;
+        for raw in 0..=ADC_MAX {
+            let percentage = uncalibrated(pots(raw, 0, 0), DEFAULT_VOLUME_STEP)[0];
+            assert_eq!(percentage % 2.0, 0.0, "raw {} gave {}", raw, percentage);
+            assert!(percentage >= previous, "raw {} decreased", raw);
+            previous = percentage;
+        }
+
+        // Just under 1% rounds down to 0, just over rounds up to 2
+        assert_eq!(uncalibrated(pots(40, 0, 0), DEFAULT_VOLUME_STEP)[0], 0.0);
+        assert_eq!(uncalibrated(pots(42, 0, 0), DEFAULT_VOLUME_STEP)[0], 2.0);
+    }
+
+    #[test]
+    fn to_percentages_quantizes_to_the_configured_step() {
+        // 1500 of 4095 is about 36.63%
+        let cases = [(1.0, 37.0), (2.0, 36.0), (5.0, 35.0)];
+        for (step, expected) in cases {
+            assert_eq!(uncalibrated(pots(1500, 0, 0), step)[0], expected);
+        }
+        assert_eq!(uncalibrated(pots(ADC_MAX, 0, 0), 5.0)[0], 100.0);
+    }
+
+    #[test]
+    fn to_percentages_clamps_above_full_scale() {
+        assert_eq!(
+            uncalibrated(pots(u16::MAX, ADC_MAX + 1, 0), DEFAULT_VOLUME_STEP),
+            vec![100.0, 100.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn to_raw_round_trips_through_to_percentage() {
+        for calibration in [
+            CalibrationData::default(),
+            CalibrationData {
+                min: 200,
+                max: 3900,
+            },
+        ] {
+            for step in 0..=50 {
+                let percentage = step as f32 * 2.0;
+                let raw = calibration.to_raw(percentage);
+                assert_eq!(
+                    quantize(calibration.to_percentage(raw), DEFAULT_VOLUME_STEP),
+                    percentage,
+                    "{:?}",
+                    calibration
+                );
+            }
+        }
+        assert_eq!(CalibrationData::default().to_raw(150.0), ADC_MAX);
+    }
+
+    #[test]
+    fn validate_accepts_the_full_adc_range() {
+        assert!(pots(0, ADC_MAX, 2048).validate().is_ok());
+        assert!(PotentiometerData::from_raw_values(&[ADC_MAX; 8])
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_readings_above_full_scale() {
+        assert!(pots(ADC_MAX + 1, 0, 0).validate().is_err());
+        assert!(pots(0, 0, u16::MAX).validate().is_err());
+
+        let error = PotentiometerData::from_raw_values(&[0, 0, 0, 0, ADC_MAX + 1])
+            .validate()
+            .unwrap_err();
+        assert!(error.starts_with("pot5 reading 4096"), "{}", error);
+    }
+
+    #[test]
+    fn target_volume_spreads_full_sweep_over_volume_range() {
+        for taper in [VolumeTaper::Linear, VolumeTaper::Logarithmic] {
+            let mapping = ChannelMapping {
+                taper,
+                min_volume: 30.0,
+                max_volume: 85.0,
+                ..ChannelMapping::new(1, ChannelTarget::Master)
+            };
+
+            let sweep: Vec<f32> = (0..=50)
+                .map(|step| mapping.target_volume(step as f32 * 2.0))
+                .collect();
+            assert_eq!(sweep[0], 30.0);
+            assert_eq!(sweep[50], 85.0);
+            assert!(sweep.windows(2).all(|pair| pair[0] <= pair[1]));
+        }
+    }
+
+    #[test]
+    fn target_volume_inverted_swaps_range_ends() {
+        let mapping = ChannelMapping {
+            inverted: true,
+            min_volume: 30.0,
+            max_volume: 85.0,
+            ..ChannelMapping::new(1, ChannelTarget::Master)
+        };
+        assert_eq!(mapping.target_volume(0.0), 85.0);
+        assert_eq!(mapping.target_volume(100.0), 30.0);
+    }
+
+    #[test]
+    fn fader_for_volume_inverts_target_volume() {
+        for (taper, inverted) in [
+            (VolumeTaper::Linear, false),
+            (VolumeTaper::Logarithmic, true),
+            (VolumeTaper::SShape, false),
+        ] {
+            let mapping = ChannelMapping {
+                taper,
+                inverted,
+                min_volume: 20.0,
+                max_volume: 80.0,
+                ..ChannelMapping::new(1, ChannelTarget::Master)
+            };
+            for fader in [0.0, 24.0, 50.0, 76.0, 100.0] {
+                let back = mapping.fader_for_volume(mapping.target_volume(fader));
+                assert!((back - fader).abs() < 0.1, "{:?} at {}", taper, fader);
+            }
+            // Outside the range, the fader goes to the nearer end
+            let bottom = if inverted { 100.0 } else { 0.0 };
+            assert_eq!(mapping.fader_for_volume(5.0), bottom);
+        }
+    }
+
+    #[test]
+    fn device_tables_replace_the_shared_one_for_their_port() {
+        let mut config = AppConfig {
+            calibration: vec![CalibrationData { min: 10, max: 4000 }],
+            ..Default::default()
+        };
+        config.device_calibration.insert(
+            "COM4".to_string(),
+            vec![CalibrationData { min: 50, max: 3900 }],
+        );
+
+        let tables = config.calibration_tables();
+        assert_eq!(tables.for_port(Some("COM3"))[0].min, 10);
+        assert_eq!(tables.for_port(Some("COM4"))[0].min, 50);
+        assert_eq!(tables.for_port(None)[0].min, 10);
+    }
+
+    #[test]
+    fn legacy_mapping_with_only_a_process_name_matches_by_name() {
+        let mut mapping: ChannelMapping =
+            serde_json::from_str(r#"{"channel_id":2,"process_name":"Spotify.exe"}"#).unwrap();
+        mapping.migrate_legacy_target();
+        assert_eq!(mapping.target, ChannelTarget::AppByName);
+        assert_eq!(mapping.process_names, vec!["Spotify.exe".to_string()]);
+
+        let mut master: ChannelMapping =
+            serde_json::from_str(r#"{"channel_id":1,"is_master":true}"#).unwrap();
+        master.migrate_legacy_target();
+        assert_eq!(master.target, ChannelTarget::Master);
+    }
+
+    #[test]
+    fn legacy_mapping_without_a_target_stays_unassigned() {
+        let mut mapping: ChannelMapping =
+            serde_json::from_str(r#"{"channel_id":1,"process_name":null}"#).unwrap();
+        mapping.migrate_legacy_target();
+        assert_eq!(mapping.target, ChannelTarget::AppByName);
+        assert!(mapping.process_names.is_empty());
+
+        let mut legacy: ChannelMapping =
+            serde_json::from_str(r#"{"channel_id":1,"is_master":false,"process_id":null}"#)
+                .unwrap();
+        legacy.migrate_legacy_target();
+        assert_eq!(legacy.target, ChannelTarget::AppByName);
+        assert!(legacy.process_names.is_empty());
+    }
+
+    #[test]
+    fn mappings_with_a_target_are_not_migrated() {
+        let mut mapping: ChannelMapping = serde_json::from_str(
+            r#"{"channel_id":1,"target":"Master","process_name":"Spotify.exe","process_names":["Spotify.exe"]}"#,
+        )
+        .unwrap();
+        mapping.migrate_legacy_target();
+        assert_eq!(mapping.target, ChannelTarget::Master);
+        assert_eq!(mapping.process_names, vec!["Spotify.exe".to_string()]);
+
+        // Round-trips through the current format unchanged
+        let saved = serde_json::to_string(&mapping).unwrap();
+        let mut reloaded: ChannelMapping = serde_json::from_str(&saved).unwrap();
+        reloaded.migrate_legacy_target();
+        assert_eq!(reloaded, mapping);
+    }
+
+    #[test]
+    fn mapping_without_range_covers_full_volume() {
+        let mapping: ChannelMapping =
+            serde_json::from_str(r#"{"channel_id":1,"process_name":null}"#).unwrap();
+        assert_eq!(mapping.target_volume(0.0), 0.0);
+        assert_eq!(mapping.target_volume(100.0), 100.0);
+    }
+
+    #[test]
+    fn mute_thresholds_hold_their_state_while_dithering() {
+        let thresholds = MuteThresholds::default();
+        let mut muted = false;
+        let mut changes = 0;
+        for position in [2.0, 0.0, 2.0, 0.0, 2.0, 3.0, 2.0, 0.0, 2.0, 4.0, 2.0, 4.0] {
+            let next = thresholds.should_mute(position, muted);
+            if next != muted {
+                changes += 1;
+            }
+            muted = next;
+        }
+        // Muted at the first 0, unmuted at the first 4, and nothing in between
+        assert_eq!(changes, 2);
+        assert!(!muted);
+
+        assert!(thresholds.should_mute(0.5, false));
+        assert!(!thresholds.should_mute(1.0, false));
+        assert!(thresholds.should_mute(3.0, true));
+        assert!(!thresholds.should_mute(3.5, true));
+    }
 }
@@ -0,0 +1,62 @@
+use crate::error::MixerError;
+
+/// A volume level, always finite and within 0-100%. Commands and the UI speak
+/// percent while the Windows APIs want a 0.0-1.0 scalar, so every conversion
+/// goes through here.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct Volume(f32);
+
+impl Volume {
+    /// Clamps `percent` to 0-100; NaN and infinities are rejected
+    pub fn from_percent(percent: f32) -> Result<Self, MixerError> {
+        if !percent.is_finite() {
+            return Err(MixerError::InvalidVolume(percent));
+        }
+        Ok(Self(percent.clamp(0.0, 100.0)))
+    }
+
+    /// Clamps `scalar` to 0.0-1.0; NaN and infinities are rejected
+    pub fn from_scalar(scalar: f32) -> Result<Self, MixerError> {
+        if !scalar.is_finite() {
+            return Err(MixerError::InvalidVolume(scalar));
+        }
+        Self::from_percent(scalar * 100.0)
+    }
+
+    pub fn as_percent(self) -> f32 {
+        self.0
+    }
+
+    pub fn as_scalar(self) -> f32 {
+        self.0 / 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_and_scalar_agree() {
+        let volume = Volume::from_percent(25.0).unwrap();
+        assert_eq!(volume.as_percent(), 25.0);
+        assert_eq!(volume.as_scalar(), 0.25);
+        assert_eq!(Volume::from_scalar(0.25).unwrap(), volume);
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped() {
+        assert_eq!(Volume::from_percent(150.0).unwrap().as_percent(), 100.0);
+        assert_eq!(Volume::from_percent(-5.0).unwrap().as_percent(), 0.0);
+        assert_eq!(Volume::from_scalar(1.5).unwrap().as_percent(), 100.0);
+        assert_eq!(Volume::from_scalar(-0.1).unwrap().as_percent(), 0.0);
+    }
+
+    #[test]
+    fn non_finite_values_are_rejected() {
+        for value in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert!(Volume::from_percent(value).is_err());
+            assert!(Volume::from_scalar(value).is_err());
+        }
+    }
+}
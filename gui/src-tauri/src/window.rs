@@ -0,0 +1,133 @@
+use anyhow::Result;
+use std::time::Duration;
+use tauri::{AppHandle, PhysicalPosition, PhysicalSize, WebviewWindow, WindowEvent};
+use tokio::sync::mpsc;
+
+use crate::config;
+use crate::types::WindowGeometry;
+
+/// Quiet time after the last move or resize before the geometry is saved,
+/// so dragging the window doesn't rewrite the config on every frame
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+/// Height of the strip along the top of the window that has to stay on a
+/// monitor, enough to grab the title bar
+const TITLE_BAR_HEIGHT: i64 = 32;
+/// Width of that strip that has to be visible
+const MIN_VISIBLE_WIDTH: i64 = 64;
+
+/// Moves and resizes the window to a saved geometry. A geometry from a monitor
+/// that is no longer attached is pulled back onto the closest one.
+pub fn restore_geometry(window: &WebviewWindow, geometry: WindowGeometry) -> Result<()> {
+    let monitors: Vec<WindowGeometry> = window
+        .available_monitors()?
+        .iter()
+        .map(|monitor| WindowGeometry {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        })
+        .collect();
+    let geometry = place_on_screen(geometry, &monitors);
+
+    window.set_size(PhysicalSize::new(geometry.width, geometry.height))?;
+    window.set_position(PhysicalPosition::new(geometry.x, geometry.y))?;
+    Ok(())
+}
+
+/// Saves the window geometry to the config once it stops moving or resizing
+pub fn track_geometry(app_handle: AppHandle, window: WebviewWindow) {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    window.on_window_event(move |event| {
+        if matches!(event, WindowEvent::Moved(_) | WindowEvent::Resized(_)) {
+            let _ = tx.send(());
+        }
+    });
+
+    // Ends once the window is destroyed and drops the sender
+    tauri::async_runtime::spawn(async move {
+        while rx.recv().await.is_some() {
+            loop {
+                match tokio::time::timeout(SAVE_DEBOUNCE, rx.recv()).await {
+                    Ok(Some(())) => continue,
+                    Ok(None) => return,
+                    Err(_) => break,
+                }
+            }
+            if let Err(e) = save_geometry(&app_handle, &window) {
+                log::warn!("Failed to save window geometry: {}", e);
+            }
+        }
+    });
+}
+
+fn save_geometry(app_handle: &AppHandle, window: &WebviewWindow) -> Result<()> {
+    // Minimized windows report a parking position far off-screen, and a
+    // maximized one would come back as a normal window covering the screen
+    if window.is_minimized()? || window.is_maximized()? {
+        return Ok(());
+    }
+    let position = window.outer_position()?;
+    let size = window.inner_size()?;
+    let geometry = WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    };
+
+    let mut config = config::load_config(app_handle)?;
+    if config.window == Some(geometry) {
+        return Ok(());
+    }
+    config.window = Some(geometry);
+    config::save_config(app_handle, &config)
+}
+
+/// Keeps `geometry` if its title bar is reachable on one of `monitors`,
+/// otherwise shrinks it to fit and moves it onto the monitor it overlaps
+/// most, or the first one if it overlaps none
+fn place_on_screen(geometry: WindowGeometry, monitors: &[WindowGeometry]) -> WindowGeometry {
+    let title_bar = WindowGeometry {
+        height: geometry.height.min(TITLE_BAR_HEIGHT as u32),
+        ..geometry
+    };
+    let (title_width, title_height) = overlap(title_bar, monitors.iter().copied());
+    if title_width >= MIN_VISIBLE_WIDTH && title_height > 0 {
+        return geometry;
+    }
+
+    // Reversed because ties go to the last element
+    let Some(target) = monitors.iter().rev().copied().max_by_key(|&monitor| {
+        let (width, height) = overlap(geometry, std::iter::once(monitor));
+        width * height
+    }) else {
+        return geometry;
+    };
+
+    let width = geometry.width.min(target.width);
+    let height = geometry.height.min(target.height);
+    let max_x = i64::from(target.x) + i64::from(target.width - width);
+    let max_y = i64::from(target.y) + i64::from(target.height - height);
+    WindowGeometry {
+        x: i64::from(geometry.x).clamp(i64::from(target.x), max_x) as i32,
+        y: i64::from(geometry.y).clamp(i64::from(target.y), max_y) as i32,
+        width,
+        height,
+    }
+}
+
+/// Widest and tallest intersection of `area` with any of `monitors`
+fn overlap(area: WindowGeometry, monitors: impl Iterator<Item = WindowGeometry>) -> (i64, i64) {
+    let right = |g: WindowGeometry| i64::from(g.x) + i64::from(g.width);
+    let bottom = |g: WindowGeometry| i64::from(g.y) + i64::from(g.height);
+
+    monitors
+        .map(|monitor| {
+            let width = right(area).min(right(monitor)) - i64::from(area.x.max(monitor.x));
+            let height = bottom(area).min(bottom(monitor)) - i64::from(area.y.max(monitor.y));
+            (width.max(0), height.max(0))
+        })
+        .max_by_key(|&(width, height)| width * height)
+        .unwrap_or((0, 0))
+}
@@ -19,12 +19,98 @@ export interface PotentiometerData {
 	pot1: number
 	pot2: number
 	pot3: number
+	// Extra channels on larger boards, absent on the three-pot one
+	pot4?: number
+	pot5?: number
+	pot6?: number
+	pot7?: number
+	pot8?: number
+	btn1?: boolean
+	btn2?: boolean
+	btn3?: boolean
+	// Encoder detents since the previous frame, null for pot channels
+	enc1?: number | null
+	enc2?: number | null
+	enc3?: number | null
+	// Port of the device that sent the frame
+	port?: string
 }
 
 export interface ConnectionStatus {
 	connected: boolean
 	port: string | null
 	error: string | null
+	error_code?: MixerErrorCode | null
+	parse_error_count?: number
+	last_parse_error?: string | null
+	// Frames dropped for a reading above the 0-4095 ADC range
+	out_of_range_count?: number
+	// What the device reported in reply to `ID?`
+	device?: DeviceInfo | null
+}
+
+export interface DeviceInfo {
+	device: string
+	fw: string
+	channels: number
+}
+
+export type MixerErrorCode =
+	| 'serial_not_found'
+	| 'serial_busy'
+	| 'not_a_mixer'
+	| 'not_connected'
+	| 'com_failure'
+	| 'session_not_found'
+	| 'device_not_found'
+	| 'invalid_volume'
+	| 'invalid_argument'
+	| 'fader_active'
+	| 'config'
+	| 'io'
+	| 'other'
+
+// Shape of every error rejected by a backend command
+export interface MixerError {
+	code: MixerErrorCode
+	message: string
+}
+
+export function isMixerError(error: unknown): error is MixerError {
+	return typeof error === 'object' && error !== null && 'code' in error && 'message' in error
+}
+
+export interface RawSerialLine {
+	port: string
+	line: string
+}
+
+export interface SerialParseError {
+	port?: string
+	line: string
+	error: string
+	count: number
+}
+
+// Fader found by `identifyChannels`
+export interface IdentifiedChannel {
+	port: string
+	channel_id: number
+	// How far it moved, in percentage points
+	delta: number
+}
+
+// Mapped app that started or stopped, from `mapped-app-appeared`/`mapped-app-disappeared`
+export interface MappedApp {
+	channel_id: number
+	device: string | null
+	process_id: number
+	process_name: string
+}
+
+export interface SerialStalled {
+	port: string | null
+	seconds_since_data: number
 }
 
 export interface AudioSession {
@@ -33,12 +119,164 @@ export interface AudioSession {
 	display_name: string
 	volume: number
 	is_muted: boolean
+	// Processes merged into this entry (setting its volume applies to all of them)
+	session_count?: number
+}
+
+// Options of `getAudioSessionsFiltered`; master is never hidden and stays first
+export interface SessionFilter {
+	sort_by_name?: boolean
+	hide_muted?: boolean
+	// Hide sessions at zero volume
+	hide_silent?: boolean
+}
+
+// Changes to the session list since the previous `audio-sessions-delta` or
+// `audio-sessions-updated` event
+export interface SessionDelta {
+	added: AudioSession[]
+	changed: AudioSession[]
+	removed: number[]
+}
+
+// Mapping moved to a new session of the same app, from `mapping-rematched`
+export interface RematchedMapping {
+	channel_id: number
+	device: string | null
+	process_name: string
+	old_process_id: number
+	new_process_id: number
+}
+
+export interface AudioCapabilities {
+	per_app_volume: boolean
+	mute: boolean
+	metering: boolean
+}
+
+export interface BackendInfo {
+	// 'windows', 'stub' or 'sim'
+	name: string
+	// 'windows', 'macos', 'linux', ...
+	os: string
+	// True when the backend only stands in for a missing platform one
+	is_fallback: boolean
+	capabilities: AudioCapabilities
+}
+
+export type SelfTestOutcome = 'passed' | 'failed' | 'skipped'
+
+// One operation tried by `selfTest`
+export interface SelfTestStep {
+	name: string
+	outcome: SelfTestOutcome
+	// What was read back, or why it failed or was skipped
+	detail: string
+}
+
+export interface SelfTestReport {
+	capabilities: AudioCapabilities
+	steps: SelfTestStep[]
+	passed: boolean
+}
+
+export interface AudioDevice {
+	id: string
+	name: string
+	is_default: boolean
 }
 
 export interface MixerChannel {
+	// Unique across devices; the second device's channels follow the first's
 	id: number
+	// Channel number on its own device, as mappings use it
+	channel: number
 	value: number
 	is_physical: boolean
+	label?: string | null
+	// Port of the device the channel is on, null while nothing is connected
+	device?: string | null
+}
+
+export type HotkeyAction =
+	| 'toggle_master_mute'
+	| { toggle_channel_mute: { channel_id: number } }
+	| { adjust_channel_volume: { channel_id: number; delta: number } }
+
+// A system-wide shortcut; `accelerator` looks like 'CmdOrCtrl+Shift+M'
+export interface HotkeyBinding {
+	accelerator: string
+	action: HotkeyAction
+}
+
+// General settings, as stored in the config file
+export interface AppSettings {
+	start_with_windows: boolean
+	minimize_to_tray: boolean
+	auto_connect: boolean
+	theme: string
+	hotkeys?: HotkeyBinding[]
+	expose_system_sounds?: boolean
+	session_blocklist?: string[]
+	invert_all_channels?: boolean
+	soft_takeover?: boolean
+	motorized_faders?: boolean
+	unmute_on_fader_move?: boolean
+	volume_priority?: VolumePriority
+	volume_step?: number
+	control_server?: ControlServerConfig
+	device_filter?: UsbDeviceFilter | null
+	reconnect_backoff?: BackoffConfig
+	mute_thresholds?: MuteThresholds
+	log_level?: LogLevel
+	meter_scale?: MeterScale
+	meter_weighting?: boolean
+}
+
+// Units of peak-levels readings: 0-1 scalars or dBFS down to -96
+export type MeterScale = 'linear' | 'decibel'
+
+// Line-delimited JSON server for other tools, started with the app
+export interface ControlServerConfig {
+	enabled: boolean
+	port: number
+	allow_remote: boolean
+}
+
+export type LogLevel = 'error' | 'warn' | 'info' | 'debug' | 'trace'
+
+// Which of the UI and a fader wins when both move a channel at once
+export type VolumePriority = 'physical_priority' | 'ui_priority'
+
+// Delays between reconnect attempts, growing by `multiplier` up to `max_ms`
+export interface BackoffConfig {
+	initial_ms: number
+	max_ms: number
+	multiplier: number
+	// Fraction each delay is randomly spread by, e.g. 0.2 for +/-20%
+	jitter: number
+}
+
+// Mute-at-zero mutes below `mute_below` and unmutes above `unmute_above` (percent of travel)
+export interface MuteThresholds {
+	mute_below: number
+	unmute_above: number
+	// Shortest time between two accepted changes of a mute button
+	button_debounce_ms: number
+}
+
+// USB IDs of a custom firmware build, tried before the stock ones
+export interface UsbDeviceFilter {
+	vid: number
+	pid: number
+}
+
+// Health readings from firmware built with the `diagnostics` feature
+export interface DeviceDiagnostics {
+	// Set on events, which may come from any connected device
+	port?: string
+	temp_c: number | null
+	uptime_ms: number | null
 }
 
 export interface SerialPortInfo {
@@ -62,6 +300,22 @@ export const connectionStatus = writable<ConnectionStatus>({
 export const mixerChannels = writable<MixerChannel[]>([])
 export const availablePorts = writable<SerialPortInfo[]>([])
 export const audioSessions = writable<AudioSession[]>([])
+// Peak level by process ID, master output under 0; 0-1 or dBFS per the meter scale
+export const peakLevels = writable<Record<number, number>>({})
+export const defaultOutputDevice = writable<AudioDevice | null>(null)
+// Executable that follow-focus channels control, once something has been focused
+export const focusedApp = writable<string | null>(null)
+// Every open device, kept in step with `connection-status` events
+export const connectedDevices = writable<ConnectionStatus[]>([])
+// Most recent `serial-raw` lines, oldest first, while the raw monitor is on
+export const serialMonitorLines = writable<string[]>([])
+// Latest firmware health readings, null until the device sends any
+export const deviceDiagnostics = writable<DeviceDiagnostics | null>(null)
+// Result of the last `identifyChannels`, null until a fader moves (or it times out)
+export const identifiedChannel = writable<IdentifiedChannel | null>(null)
+// Channels whose mapped app has closed, shown as waiting until it starts again
+export const waitingChannels = writable<number[]>([])
+const SERIAL_MONITOR_MAX_LINES = 500
 
 // Derived stores
 export const channelValues = derived(
@@ -70,7 +324,7 @@ export const channelValues = derived(
 		return $channels.map(channel => {
 			if (channel.is_physical) {
 				// Get actual pot value
-				const potKey = `pot${channel.id}` as keyof PotentiometerData
+				const potKey = `pot${channel.channel}` as keyof PotentiometerData
 				const rawValue = $potData[potKey] || 0
 				const percentage = (rawValue / 4095) * 100
 				// Round to nearest 2%
@@ -85,6 +339,14 @@ export const channelValues = derived(
 	}
 )
 
+function applySessionDelta(sessions: AudioSession[], delta: SessionDelta): AudioSession[] {
+	const changed = new Map(delta.changed.map(session => [session.process_id, session]))
+	return sessions
+		.filter(session => !delta.removed.includes(session.process_id))
+		.map(session => changed.get(session.process_id) ?? session)
+		.concat(delta.added)
+}
+
 // Initialize event listeners
 export async function initializeListeners() {
 	// Listen for potentiometer data
@@ -95,6 +357,74 @@ export async function initializeListeners() {
 	// Listen for connection status changes
 	await listen<ConnectionStatus>('connection-status', (event: Event<ConnectionStatus>) => {
 		connectionStatus.set(event.payload)
+		trackConnectedDevice(event.payload)
+	})
+
+	// Listen for malformed serial lines (throttled to once per second)
+	await listen<SerialParseError>('serial-parse-error', (event: Event<SerialParseError>) => {
+		const { count, error, line } = event.payload
+		console.warn(`Serial parse error #${count}:`, error, line)
+	})
+
+	// Listen for peak meter updates (~30Hz)
+	await listen<[number, number][]>('peak-levels', (event: Event<[number, number][]>) => {
+		peakLevels.set(Object.fromEntries(event.payload))
+	})
+
+	// Listen for a port that is open but has stopped sending data
+	await listen<SerialStalled>('serial-stalled', (event: Event<SerialStalled>) => {
+		const { port, seconds_since_data } = event.payload
+		console.warn(`No data from ${port ?? 'serial port'} for ${seconds_since_data.toFixed(1)}s`)
+	})
+
+	// Listen for raw serial output, only sent while the raw monitor is on
+	await listen<RawSerialLine>('serial-raw', (event: Event<RawSerialLine>) => {
+		const { port, line } = event.payload
+		serialMonitorLines.update(lines => [...lines, `${port}: ${line}`].slice(-SERIAL_MONITOR_MAX_LINES))
+	})
+
+	// Listen for firmware health readings, at most once a second
+	await listen<DeviceDiagnostics>('device-diagnostics', (event: Event<DeviceDiagnostics>) => {
+		deviceDiagnostics.set(event.payload)
+	})
+
+	// Listen for the fader picked out by `identifyChannels`; null means it timed out
+	await listen<IdentifiedChannel | null>('identified-channel', (event: Event<IdentifiedChannel | null>) => {
+		identifiedChannel.set(event.payload)
+	})
+
+	// Listen for mapped apps starting, which the backend has already synced to their fader
+	await listen<MappedApp>('mapped-app-appeared', (event: Event<MappedApp>) => {
+		const { channel_id } = event.payload
+		waitingChannels.update(channels => channels.filter(id => id !== channel_id))
+	})
+
+	// Listen for mapped apps closing, leaving their channel with nothing to control
+	await listen<MappedApp>('mapped-app-disappeared', (event: Event<MappedApp>) => {
+		const { channel_id } = event.payload
+		waitingChannels.update(channels => (channels.includes(channel_id) ? channels : [...channels, channel_id]))
+	})
+
+	// Listen for mapped apps recreating their session, which the backend has
+	// already re-matched by process name
+	await listen<RematchedMapping>('mapping-rematched', (event: Event<RematchedMapping>) => {
+		const { channel_id } = event.payload
+		waitingChannels.update(channels => channels.filter(id => id !== channel_id))
+	})
+
+	// Listen for the default output device switching, e.g. headphones plugged in
+	await listen<AudioDevice>('device-changed', (event: Event<AudioDevice>) => {
+		defaultOutputDevice.set(event.payload)
+	})
+
+	// Listen for follow-focus channels switching to a newly focused app
+	await listen<string>('focused-app-changed', (event: Event<string>) => {
+		focusedApp.set(event.payload)
+	})
+
+	// Listen for the device reporting a different number of channels
+	await listen<number>('channels-updated', async () => {
+		await loadMixerChannels()
 	})
 
 	// Listen for audio session updates
@@ -109,6 +439,11 @@ export async function initializeListeners() {
 			console.error('Error handling audio-sessions-updated event:', error)
 		}
 	})
+
+	// Most polls only send what changed; apply it to the current list
+	await listen<SessionDelta>('audio-sessions-delta', (event: Event<SessionDelta>) => {
+		audioSessions.update(sessions => applySessionDelta(sessions, event.payload))
+	})
 }
 
 // API Functions
@@ -123,29 +458,57 @@ export async function listSerialPorts(): Promise<SerialPortInfo[]> {
 	}
 }
 
+// Adds a newly connected device to `connectedDevices`, or drops one that closed
+function trackConnectedDevice(status: ConnectionStatus): void {
+	if (!status.port) {
+		return
+	}
+	connectedDevices.update(devices => {
+		const others = devices.filter(device => device.port !== status.port)
+		return status.connected ? [...others, status] : others
+	})
+}
+
+export async function listConnectedDevices(): Promise<ConnectionStatus[]> {
+	try {
+		const devices = await invoke<ConnectionStatus[]>('list_connected_devices')
+		connectedDevices.set(devices)
+		return devices
+	} catch (error) {
+		console.error('Failed to list connected devices:', error)
+		return []
+	}
+}
+
+// Opens another device next to any already connected; omit `port` to pick the
+// first mixer not yet connected (reconnecting one needs its port)
 export async function connectSerial(port?: string): Promise<ConnectionStatus> {
 	try {
 		const status = await invoke<ConnectionStatus>('connect_serial', { port })
 		connectionStatus.set(status)
+		trackConnectedDevice(status)
 		return status
 	} catch (error) {
 		console.error('Failed to connect serial:', error)
 		const status = {
 			connected: false,
 			port: null,
-			error: error as string,
+			error: isMixerError(error) ? error.message : String(error),
+			error_code: isMixerError(error) ? error.code : null,
 		}
 		connectionStatus.set(status)
 		return status
 	}
 }
 
-export async function disconnectSerial(): Promise<void> {
+// Closes one device, or all of them when `port` is omitted
+export async function disconnectSerial(port?: string): Promise<void> {
 	try {
-		await invoke('disconnect_serial')
+		await invoke('disconnect_serial', { port })
+		connectedDevices.update(devices => (port ? devices.filter(device => device.port !== port) : []))
 		connectionStatus.set({
 			connected: false,
-			port: null,
+			port: port ?? null,
 			error: null,
 		})
 	} catch (error) {
@@ -153,6 +516,62 @@ export async function disconnectSerial(): Promise<void> {
 	}
 }
 
+export async function setUpdateRate(intervalMs: number): Promise<void> {
+	try {
+		await invoke('set_update_rate', { intervalMs })
+	} catch (error) {
+		console.error('Failed to set update rate:', error)
+	}
+}
+
+// 'auto' lets the LED show the connection state again
+export type LedMode = 'auto' | 'on' | 'off'
+
+export async function setDeviceLed(mode: LedMode): Promise<void> {
+	try {
+		await invoke('set_device_led', { mode })
+	} catch (error) {
+		console.error('Failed to set device LED:', error)
+	}
+}
+
+// Resolves right away; the moved fader arrives in `identifiedChannel`
+export async function identifyChannels(timeoutMs?: number): Promise<void> {
+	try {
+		identifiedChannel.set(null)
+		await invoke('identify_channels', { timeoutMs })
+	} catch (error) {
+		console.error('Failed to identify channels:', error)
+	}
+}
+
+export async function cancelIdentifyChannels(): Promise<void> {
+	try {
+		await invoke('cancel_identify_channels')
+	} catch (error) {
+		console.error('Failed to cancel channel identification:', error)
+	}
+}
+
+export async function getDeviceDiagnostics(port?: string): Promise<DeviceDiagnostics | null> {
+	try {
+		const diagnostics = await invoke<DeviceDiagnostics | null>('get_device_diagnostics', { port })
+		deviceDiagnostics.set(diagnostics)
+		return diagnostics
+	} catch (error) {
+		console.error('Failed to get device diagnostics:', error)
+		return null
+	}
+}
+
+export async function setRawMonitor(enabled: boolean): Promise<void> {
+	try {
+		await invoke('set_raw_monitor', { enabled })
+	} catch (error) {
+		console.error('Failed to set raw serial monitor:', error)
+	}
+}
+
 export async function setMasterVolume(volume: number): Promise<void> {
 	try {
 		await invoke('set_master_volume', { volume })
@@ -161,6 +580,183 @@ export async function setMasterVolume(volume: number): Promise<void> {
 	}
 }
 
+// Each update is a [processId, volume] pair, applied in a single call
+export async function setAllVolumes(updates: [number, number][]): Promise<void> {
+	try {
+		await invoke('set_all_volumes', { updates })
+	} catch (error) {
+		console.error('Failed to set volumes:', error)
+	}
+}
+
+// Matches the executable name case-insensitively; resolves to the number of sessions changed
+export async function setVolumeByName(name: string, volume: number): Promise<number> {
+	try {
+		return await invoke<number>('set_volume_by_name', { name, volume })
+	} catch (error) {
+		console.error('Failed to set volume by name:', error)
+		return 0
+	}
+}
+
+export async function muteAll(): Promise<void> {
+	try {
+		await invoke('mute_all')
+	} catch (error) {
+		console.error('Failed to mute all:', error)
+	}
+}
+
+export async function unmuteAll(): Promise<void> {
+	try {
+		await invoke('unmute_all')
+	} catch (error) {
+		console.error('Failed to unmute all:', error)
+	}
+}
+
+// Remembers current volumes so restoreSnapshot (or the tray) can put them back
+export async function snapshotVolumes(): Promise<void> {
+	try {
+		await invoke('snapshot_volumes')
+	} catch (error) {
+		console.error('Failed to snapshot volumes:', error)
+	}
+}
+
+export async function restoreSnapshot(): Promise<void> {
+	try {
+		await invoke('restore_snapshot')
+	} catch (error) {
+		console.error('Failed to restore volume snapshot:', error)
+	}
+}
+
+export async function exportConfig(path: string): Promise<void> {
+	try {
+		await invoke('export_config', { path })
+	} catch (error) {
+		console.error('Failed to export config:', error)
+		throw error
+	}
+}
+
+// Rethrows so the caller can show why an import was rejected
+export async function importConfig(path: string): Promise<void> {
+	try {
+		await invoke('import_config', { path })
+	} catch (error) {
+		console.error('Failed to import config:', error)
+		throw error
+	}
+}
+
+export async function getSettings(): Promise<AppSettings | null> {
+	try {
+		return await invoke<AppSettings>('get_settings')
+	} catch (error) {
+		console.error('Failed to get settings:', error)
+		return null
+	}
+}
+
+// Takes effect immediately, without a restart
+export async function setLogLevel(level: LogLevel): Promise<void> {
+	try {
+		await invoke('set_log_level', { level })
+	} catch (error) {
+		console.error('Failed to set log level:', error)
+	}
+}
+
+// Full path of the log file, for attaching to bug reports
+export async function getLogPath(): Promise<string | null> {
+	try {
+		return await invoke<string>('get_log_path')
+	} catch (error) {
+		console.error('Failed to get log path:', error)
+		return null
+	}
+}
+
+// Only the given settings change; resolves to the full settings after saving
+export async function updateSettings(changes: Partial<AppSettings>): Promise<AppSettings | null> {
+	try {
+		return await invoke<AppSettings>('update_settings', {
+			startWithWindows: changes.start_with_windows,
+			minimizeToTray: changes.minimize_to_tray,
+			autoConnect: changes.auto_connect,
+			theme: changes.theme,
+		})
+	} catch (error) {
+		console.error('Failed to update settings:', error)
+		return null
+	}
+}
+
+export async function soloChannel(channelId: number): Promise<void> {
+	try {
+		await invoke('solo_channel', { channelId })
+	} catch (error) {
+		console.error('Failed to solo channel:', error)
+	}
+}
+
+export async function clearSolo(): Promise<void> {
+	try {
+		await invoke('clear_solo')
+	} catch (error) {
+		console.error('Failed to clear solo:', error)
+	}
+}
+
+// Rethrows so the caller can show why a shortcut couldn't be bound
+export async function setHotkey(
+	accelerator: string,
+	action: HotkeyAction,
+): Promise<HotkeyBinding[]> {
+	try {
+		return await invoke<HotkeyBinding[]>('set_hotkey', { accelerator, action })
+	} catch (error) {
+		console.error('Failed to set hotkey:', error)
+		throw error
+	}
+}
+
+export async function clearHotkey(accelerator: string): Promise<HotkeyBinding[] | null> {
+	try {
+		return await invoke<HotkeyBinding[]>('clear_hotkey', { accelerator })
+	} catch (error) {
+		console.error('Failed to clear hotkey:', error)
+		return null
+	}
+}
+
+// Resolves to the channel's new volume, or null if it isn't mapped
+export async function adjustChannelVolume(channelId: number, delta: number): Promise<number | null> {
+	try {
+		return await invoke<number>('adjust_channel_volume', { channelId, delta })
+	} catch (error) {
+		console.error('Failed to adjust channel volume:', error)
+		return null
+	}
+}
+
+// Pass null or an empty string to remove the channel's name; pass `port` to name
+// only that device's channel
+export async function setChannelLabel(
+	channelId: number,
+	label: string | null,
+	port?: string
+): Promise<void> {
+	try {
+		await invoke('set_channel_label', { channelId, label, port })
+		await loadMixerChannels()
+	} catch (error) {
+		console.error('Failed to set channel label:', error)
+	}
+}
+
 export async function loadMixerChannels(): Promise<MixerChannel[]> {
 	try {
 		const channels = await invoke<MixerChannel[]>('get_mixer_channels')
@@ -172,6 +768,191 @@ export async function loadMixerChannels(): Promise<MixerChannel[]> {
 	}
 }
 
+export async function setMergeSessions(enabled: boolean): Promise<void> {
+	try {
+		await invoke('set_merge_sessions', { enabled })
+		await getAudioSessions()
+	} catch (error) {
+		console.error('Failed to set session merging:', error)
+	}
+}
+
+// Lists Windows system sounds as a session that faders can be mapped to
+export async function setExposeSystemSounds(enabled: boolean): Promise<void> {
+	try {
+		await invoke('set_expose_system_sounds', { enabled })
+		await getAudioSessions()
+	} catch (error) {
+		console.error('Failed to set system sounds visibility:', error)
+	}
+}
+
+// Pass null to go back to detecting the stock firmware only
+export async function setDeviceFilter(filter: UsbDeviceFilter | null): Promise<void> {
+	try {
+		await invoke('set_device_filter', { filter })
+	} catch (error) {
+		console.error('Failed to set device filter:', error)
+	}
+}
+
+// Weighting bends readings towards perceived loudness before scaling
+export async function setMeterScale(scale: MeterScale, weighted: boolean): Promise<void> {
+	try {
+		await invoke('set_meter_scale', { scale, weighted })
+	} catch (error) {
+		console.error('Failed to set meter scale:', error)
+	}
+}
+
+// Flips every fader at once, for a slider bank mounted upside down
+export async function setInvertAll(enabled: boolean): Promise<void> {
+	try {
+		await invoke('set_invert_all', { enabled })
+	} catch (error) {
+		console.error('Failed to set invert all channels:', error)
+	}
+}
+
+export async function setReconnectBackoff(backoff: BackoffConfig): Promise<void> {
+	try {
+		await invoke('set_reconnect_backoff', { backoff })
+	} catch (error) {
+		console.error('Failed to set reconnect backoff:', error)
+	}
+}
+
+export async function setMuteThresholds(thresholds: MuteThresholds): Promise<void> {
+	try {
+		await invoke('set_mute_thresholds', { thresholds })
+	} catch (error) {
+		console.error('Failed to set mute thresholds:', error)
+	}
+}
+
+// Faders wait until they reach their target's volume before taking over
+export async function setSoftTakeover(enabled: boolean): Promise<void> {
+	try {
+		await invoke('set_soft_takeover', { enabled })
+	} catch (error) {
+		console.error('Failed to set soft takeover:', error)
+	}
+}
+
+// Moving a fader unmutes its target if it was muted, e.g. in the Windows mixer
+export async function setUnmuteOnFaderMove(enabled: boolean): Promise<void> {
+	try {
+		await invoke('set_unmute_on_fader_move', { enabled })
+	} catch (error) {
+		console.error('Failed to set unmute on fader move:', error)
+	}
+}
+
+// Motorized faders follow volume changes made elsewhere, e.g. in the OS mixer
+export async function setMotorizedFaders(enabled: boolean): Promise<void> {
+	try {
+		await invoke('set_motorized_faders', { enabled })
+	} catch (error) {
+		console.error('Failed to set motorized faders:', error)
+	}
+}
+
+export async function setVolumePriority(priority: VolumePriority): Promise<void> {
+	try {
+		await invoke('set_volume_priority', { priority })
+	} catch (error) {
+		console.error('Failed to set volume priority:', error)
+	}
+}
+
+// Resolves to the step actually applied, after clamping to 0.5-25 percentage points
+export async function setVolumeStep(step: number): Promise<number | null> {
+	try {
+		return await invoke<number>('set_volume_step', { step })
+	} catch (error) {
+		console.error('Failed to set volume step:', error)
+		return null
+	}
+}
+
+// Resolves to the interval actually applied, after clamping to 1-60s
+export async function setSessionPollInterval(intervalSecs: number): Promise<number | null> {
+	try {
+		return await invoke<number>('set_session_poll_interval', { intervalSecs })
+	} catch (error) {
+		console.error('Failed to set session poll interval:', error)
+		return null
+	}
+}
+
+export async function getAudioCapabilities(): Promise<AudioCapabilities | null> {
+	try {
+		return await invoke<AudioCapabilities>('get_audio_capabilities')
+	} catch (error) {
+		console.error('Failed to get audio capabilities:', error)
+		return null
+	}
+}
+
+export async function getBackendInfo(): Promise<BackendInfo | null> {
+	try {
+		return await invoke<BackendInfo>('get_backend_info')
+	} catch (error) {
+		console.error('Failed to get backend info:', error)
+		return null
+	}
+}
+
+// Changes master (and one app, if supported) by a little, reads it back and restores it
+export async function selfTest(): Promise<SelfTestReport | null> {
+	try {
+		return await invoke<SelfTestReport>('self_test')
+	} catch (error) {
+		console.error('Failed to run audio self-test:', error)
+		return null
+	}
+}
+
+export async function listOutputDevices(): Promise<AudioDevice[]> {
+	try {
+		return await invoke<AudioDevice[]>('list_output_devices')
+	} catch (error) {
+		console.error('Failed to list output devices:', error)
+		return []
+	}
+}
+
+// Makes the device the default output, on backends that can switch it
+export async function setOutputDevice(deviceId: string): Promise<AudioDevice | null> {
+	try {
+		const device = await invoke<AudioDevice>('set_output_device', { deviceId })
+		defaultOutputDevice.set(device)
+		return device
+	} catch (error) {
+		console.error('Failed to set output device:', error)
+		return null
+	}
+}
+
+// Icons by executable, fetched once each since sessions no longer carry them
+const sessionIcons = new Map<string, Promise<string | null>>()
+
+// Base64 PNG of the session's executable icon, render with a
+// `data:image/png;base64,` URL; null where the backend has none
+export function getSessionIcon(session: AudioSession): Promise<string | null> {
+	const key = session.process_name.toLowerCase()
+	let icon = sessionIcons.get(key)
+	if (!icon) {
+		icon = invoke<string | null>('get_session_icon', { processId: session.process_id }).catch(error => {
+			console.error('Failed to get session icon:', error)
+			sessionIcons.delete(key)
+			return null
+		})
+		sessionIcons.set(key, icon)
+	}
+	return icon
+}
+
 export async function getAudioSessions(): Promise<AudioSession[]> {
 	try {
 		const sessions = await invoke<AudioSession[]>('get_audio_sessions')
@@ -183,6 +964,36 @@ export async function getAudioSessions(): Promise<AudioSession[]> {
 	}
 }
 
+// For pickers like the mapping dropdown; leaves the `audioSessions` store alone
+export async function getAudioSessionsFiltered(filter: SessionFilter): Promise<AudioSession[]> {
+	try {
+		return await invoke<AudioSession[]>('get_audio_sessions_filtered', { filter })
+	} catch (error) {
+		console.error('Failed to get filtered audio sessions:', error)
+		return []
+	}
+}
+
+// Executable names, matched case-insensitively, e.g. 'RuntimeBroker.exe'
+export async function setSessionBlocklist(names: string[]): Promise<void> {
+	try {
+		await invoke('set_session_blocklist', { names })
+	} catch (error) {
+		console.error('Failed to set session blocklist:', error)
+	}
+}
+
+export async function refreshAudioSessions(): Promise<AudioSession[]> {
+	try {
+		const sessions = await invoke<AudioSession[]>('refresh_audio_sessions')
+		audioSessions.set(sessions)
+		return sessions
+	} catch (error) {
+		console.error('Failed to refresh audio sessions:', error)
+		return []
+	}
+}
+
 // Wait for Tauri to be ready
 async function waitForTauri(maxRetries = 50, retryDelay = 100): Promise<void> {
 	for (let i = 0; i < maxRetries; i++) {